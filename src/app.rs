@@ -1,11 +1,15 @@
 use crate::context::AuthContext;
-use crate::ui::auth::{LandingPage, LoginPage, SignupPage};
+use crate::ui::auth::{
+    ForgotPasswordPage, LandingPage, LoginPage, OAuthCallbackPage, ResetPasswordPage, SignupPage,
+};
 use crate::ui::auth::protected::ProtectedRoute;
+use crate::ui::components::DatabaseInfoPanel;
+use crate::ui::jobs::CronJobList;
 use leptos::prelude::*;
 use leptos_meta::{provide_meta_context, Stylesheet, Title};
 use leptos_router::{
     components::{Route, Router, Routes},
-    StaticSegment, WildcardSegment,
+    path, StaticSegment, WildcardSegment,
 };
 
 #[component]
@@ -32,6 +36,9 @@ pub fn App() -> impl IntoView {
                     <Route path=StaticSegment("") view=LandingPage/>
                     <Route path=StaticSegment("login") view=LoginPage/>
                     <Route path=StaticSegment("signup") view=SignupPage/>
+                    <Route path=StaticSegment("forgot-password") view=ForgotPasswordPage/>
+                    <Route path=StaticSegment("reset-password") view=ResetPasswordPage/>
+                    <Route path=path!("/auth/callback") view=OAuthCallbackPage/>
                     <Route path=StaticSegment("home") view=HomePage/>
                     <Route path=WildcardSegment("any") view=NotFound/>
                 </Routes>
@@ -60,9 +67,8 @@ fn HomePage() -> impl IntoView {
                                     "Logged in as: " {u.email}
                                 </p>
                             })}
-                            <p class="text-gray-500">
-                                "Your dashboard will appear here."
-                            </p>
+                            <CronJobList/>
+                            <DatabaseInfoPanel/>
                         </div>
                     </div>
                 </div>
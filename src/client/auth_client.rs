@@ -0,0 +1,138 @@
+use crate::client::SupabaseClient;
+use crate::config::{get_supabase_anon_key, get_supabase_url};
+use crate::context::AuthContext;
+use futures::{Sink, Stream};
+use leptos::prelude::*;
+use leptos::server_fn::client::browser::BrowserClient;
+use leptos::server_fn::client::Client;
+use leptos::server_fn::error::{FromServerFnError, ServerFnErrorErr};
+use leptos::server_fn::request::browser::BrowserRequest;
+use leptos::server_fn::response::browser::BrowserResponse;
+use leptos::server_fn::Bytes;
+use std::future::Future;
+
+/// Page the browser is sent to when a call needs a session and there is none
+pub const LOGIN_PATH: &str = "/login";
+
+/// Server function client that authenticates every call with the signed-in session
+///
+/// Sets `Authorization: Bearer <access_token>` on the request, which is where
+/// `get_user_id_from_request` on the server reads it from, and `X-CSRF-Token` once a CSRF
+/// token has been issued. Server functions opt in with
+/// `client = AuthClient` in their `#[server]` attribute. Without a session the request
+/// is not sent; the browser is redirected to [`LOGIN_PATH`] and the call fails.
+pub struct AuthClient;
+
+/// Access token of the current session: from [`AuthContext`] when called under the app's
+/// reactive owner, otherwise from the stored session it keeps in sync
+fn current_access_token() -> Option<String> {
+    let from_context = use_context::<AuthContext>().and_then(|auth| {
+        auth.session
+            .try_with_untracked(|s| s.as_ref().map(|s| s.access_token.clone()))
+            .flatten()
+    });
+    from_context.or_else(|| {
+        SupabaseClient::new(get_supabase_url(), get_supabase_anon_key())
+            .get_session()
+            .ok()
+            .flatten()
+            .map(|session| session.access_token)
+    })
+}
+
+/// Name of the cookie the server issues the CSRF token in (`CSRF_COOKIE` on the server)
+#[cfg(target_arch = "wasm32")]
+const CSRF_COOKIE: &str = "csrf_token";
+/// Header the server expects the CSRF token in on mutating calls
+const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// CSRF token to echo back: the one [`AuthContext`] was issued, or the cookie it was set
+/// in, which outlives a page reload
+fn current_csrf_token() -> Option<String> {
+    use_context::<AuthContext>()
+        .and_then(|auth| auth.csrf_token.try_get_untracked().flatten())
+        .or_else(csrf_cookie_value)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn csrf_cookie_value() -> Option<String> {
+    use wasm_bindgen::JsCast;
+
+    let cookies = web_sys::window()?
+        .document()?
+        .dyn_into::<web_sys::HtmlDocument>()
+        .ok()?
+        .cookie()
+        .ok()?;
+    cookies
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == CSRF_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn csrf_cookie_value() -> Option<String> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+fn redirect_to_login() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_href(LOGIN_PATH);
+    }
+}
+
+// Server functions only run their client on the browser
+#[cfg(not(target_arch = "wasm32"))]
+fn redirect_to_login() {}
+
+impl<Error, InputStreamError, OutputStreamError> Client<Error, InputStreamError, OutputStreamError> for AuthClient
+where
+    Error: FromServerFnError,
+    InputStreamError: FromServerFnError,
+    OutputStreamError: FromServerFnError,
+{
+    type Request = BrowserRequest;
+    type Response = BrowserResponse;
+
+    fn send(req: Self::Request) -> impl Future<Output = Result<Self::Response, Error>> + Send {
+        let authorized = match current_access_token() {
+            Some(token) => {
+                req.headers().set("Authorization", &format!("Bearer {}", token));
+                if let Some(csrf_token) = current_csrf_token() {
+                    req.headers().set(CSRF_HEADER, &csrf_token);
+                }
+                Ok(())
+            }
+            None => {
+                redirect_to_login();
+                Err("Not signed in".to_string())
+            }
+        };
+
+        async move {
+            authorized.map_err(|e| Error::from_server_fn_error(ServerFnErrorErr::Request(e)))?;
+            <BrowserClient as Client<Error, InputStreamError, OutputStreamError>>::send(req).await
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn open_websocket(
+        path: &str,
+    ) -> impl Future<
+        Output = Result<
+            (
+                impl Stream<Item = Result<Bytes, Bytes>> + Send + 'static,
+                impl Sink<Bytes> + Send + 'static,
+            ),
+            Error,
+        >,
+    > + Send {
+        <BrowserClient as Client<Error, InputStreamError, OutputStreamError>>::open_websocket(path)
+    }
+
+    fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+        <BrowserClient as Client<Error, InputStreamError, OutputStreamError>>::spawn(future)
+    }
+}
@@ -1,4 +1,5 @@
+pub mod auth_client;
 pub mod supabase;
 
+pub use auth_client::{AuthClient, LOGIN_PATH};
 pub use supabase::{SupabaseClient, Session, User, SupabaseError};
-
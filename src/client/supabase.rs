@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg(target_arch = "wasm32")]
+use crate::config::{check_redirect_origin, get_oauth_redirect_origins};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
@@ -23,6 +25,20 @@ pub struct Session {
     pub user: User,
 }
 
+impl Session {
+    /// Seconds left until `expires_at` at `now` (negative once expired), or `None`
+    /// when the session has no expiry
+    pub fn seconds_until_expiry(&self, now: i64) -> Option<i64> {
+        self.expires_at.map(|expires_at| expires_at - now)
+    }
+
+    /// Whether the session should be treated as expired at `now`, `buffer` seconds
+    /// ahead of its actual expiry so tokens don't run out mid-request
+    pub fn is_expired(&self, now: i64, buffer: i64) -> bool {
+        self.seconds_until_expiry(now).is_some_and(|left| left <= buffer)
+    }
+}
+
 // Response when email confirmation is required (signup returns just user)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignUpResponseWithoutSession {
@@ -49,15 +65,27 @@ pub struct SignUpResponseWithSession {
     pub token_type: Option<String>,
 }
 
-// Sign-in response always returns session data
+// Sign-in response always returns session data, but some configurations only
+// include the relative `expires_in` instead of the absolute `expires_at`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignInResponse {
     pub access_token: String,
     pub refresh_token: String,
+    #[serde(default)]
     pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub expires_in: Option<i64>,
     pub user: User,
 }
 
+/// Resolve a session's absolute expiry (Unix seconds).
+///
+/// Prefers `expires_at` and falls back to `now + expires_in` when only the relative
+/// lifetime was returned.
+pub fn resolve_expires_at(expires_at: Option<i64>, expires_in: Option<i64>, now: i64) -> Option<i64> {
+    expires_at.or_else(|| expires_in.map(|secs| now + secs))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignUpRequest {
     pub email: String,
@@ -70,12 +98,151 @@ pub struct SignInRequest {
     pub password: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordRecoveryRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePasswordRequest {
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResendRequest {
+    /// What to resend; `signup` for the sign-up confirmation email
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub email: String,
+}
+
+/// Human-readable message from a Supabase error response body.
+///
+/// Supabase reports errors as JSON under `msg`, `error_description`, `message` or
+/// `error` depending on the endpoint; anything else is returned as-is.
+pub fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| {
+            ["msg", "error_description", "message", "error"]
+                .iter()
+                .find_map(|key| json.get(key).and_then(|v| v.as_str()).map(str::to_string))
+        })
+        .unwrap_or_else(|| body.to_string())
+}
+
+/// Wait used when a 429 response carries no readable `Retry-After` header
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+
+/// sessionStorage key of the `state` of the OAuth sign-in in progress
+#[cfg(target_arch = "wasm32")]
+const OAUTH_STATE_KEY: &str = "supabase.auth.oauth_state";
+
+/// Seconds to wait according to a `Retry-After` header, which is either a number of
+/// seconds or an HTTP date. `parse_date` turns the latter into a Unix timestamp.
+pub fn parse_retry_after(value: &str, now: i64, parse_date: impl Fn(&str) -> Option<i64>) -> Option<u64> {
+    let value = value.trim();
+    value
+        .parse::<u64>()
+        .ok()
+        .or_else(|| parse_date(value).map(|at| at.saturating_sub(now).max(0) as u64))
+}
+
+/// Key/value pairs of a URL fragment (`#a=1&b=2`). Errors Supabase redirects back with
+/// (`error`/`error_description`) are returned as `Err`.
+fn fragment_params(fragment: &str) -> Result<Vec<(String, String)>, String> {
+    let params: Vec<(String, String)> = url::form_urlencoded::parse(fragment.trim_start_matches('#').as_bytes())
+        .into_owned()
+        .collect();
+
+    let error = ["error_description", "error"]
+        .iter()
+        .find_map(|name| params.iter().find(|(key, _)| key == name));
+    match error {
+        Some((_, message)) => Err(message.clone()),
+        None => Ok(params),
+    }
+}
+
+fn fragment_param(params: &[(String, String)], name: &str) -> Option<String> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+}
+
+/// Access token from the URL fragment of a password recovery link
+/// (`#access_token=...&type=recovery`), or the error Supabase put there instead
+pub fn parse_recovery_fragment(fragment: &str) -> Result<String, String> {
+    let params = fragment_params(fragment)?;
+
+    if fragment_param(&params, "type").as_deref() != Some("recovery") {
+        return Err("This link is not a password reset link".to_string());
+    }
+    fragment_param(&params, "access_token")
+        .ok_or_else(|| "The reset link is missing its access token".to_string())
+}
+
+/// Check the `state` query parameter of the OAuth callback URL against the one stored
+/// when the sign-in started, so tokens only land in the tab that asked for them
+///
+/// ```
+/// use cron_jobs::client::supabase::check_oauth_state;
+///
+/// assert!(check_oauth_state("?state=abc", Some("abc")).is_ok());
+/// assert!(check_oauth_state("?state=abc", Some("xyz")).is_err());
+/// assert!(check_oauth_state("?state=abc", None).is_err());
+/// assert!(check_oauth_state("", Some("abc")).is_err());
+/// ```
+pub fn check_oauth_state(query: &str, expected: Option<&str>) -> Result<(), String> {
+    let returned = url::form_urlencoded::parse(query.trim_start_matches('?').as_bytes())
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.into_owned());
+    match (returned, expected) {
+        (Some(returned), Some(expected)) if !expected.is_empty() && returned == expected => Ok(()),
+        _ => Err("This sign-in wasn't started from this browser tab, please try again".to_string()),
+    }
+}
+
+/// Tokens Supabase returns in the URL fragment after an OAuth sign-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: Option<i64>,
+}
+
+/// Parse the fragment of the OAuth callback URL; `now` resolves a relative `expires_in`
+pub fn parse_oauth_fragment(fragment: &str, now: i64) -> Result<OAuthTokens, String> {
+    let params = fragment_params(fragment)?;
+    let number = |name: &str| fragment_param(&params, name).and_then(|v| v.parse::<i64>().ok());
+
+    Ok(OAuthTokens {
+        access_token: fragment_param(&params, "access_token")
+            .ok_or_else(|| "Sign-in response is missing the access token".to_string())?,
+        refresh_token: fragment_param(&params, "refresh_token")
+            .ok_or_else(|| "Sign-in response is missing the refresh token".to_string())?,
+        expires_at: resolve_expires_at(number("expires_at"), number("expires_in"), now),
+    })
+}
+
 #[derive(Debug, Clone)]
 pub enum SupabaseError {
     Network(String),
     Auth(String),
     Parse(String),
     Storage(String),
+    /// Sign-up succeeded but the account must be confirmed by email before signing in
+    ConfirmationRequired(String),
+    /// Supabase refused the request because too many were sent recently
+    RateLimited(String),
+    /// Too many sign-in attempts; holds the number of seconds to wait before retrying
+    TooManyAttempts(u64),
 }
 
 impl SupabaseError {
@@ -91,6 +258,15 @@ impl std::fmt::Display for SupabaseError {
             SupabaseError::Auth(msg) => write!(f, "Authentication error: {}", msg),
             SupabaseError::Parse(msg) => write!(f, "Parse error: {}", msg),
             SupabaseError::Storage(msg) => write!(f, "Storage error: {}", msg),
+            SupabaseError::ConfirmationRequired(email) => write!(
+                f,
+                "Account created! Please check {} to confirm your account before signing in.",
+                email
+            ),
+            SupabaseError::RateLimited(msg) => write!(f, "Too many requests: {}", msg),
+            SupabaseError::TooManyAttempts(secs) => {
+                write!(f, "Too many attempts, try again in {} seconds", secs)
+            }
         }
     }
 }
@@ -99,11 +275,23 @@ impl std::fmt::Display for SupabaseError {
 pub struct SupabaseClient {
     url: String,
     anon_key: String,
+    /// Seconds before `expires_at` a session counts as expired
+    expiry_buffer: i64,
 }
 
 impl SupabaseClient {
     pub fn new(url: String, anon_key: String) -> Self {
-        Self { url, anon_key }
+        Self {
+            url,
+            anon_key,
+            expiry_buffer: crate::config::DEFAULT_SESSION_EXPIRY_BUFFER_SECS,
+        }
+    }
+
+    /// Treat sessions as expired `secs` seconds before their `expires_at`
+    pub fn with_expiry_buffer(mut self, secs: i64) -> Self {
+        self.expiry_buffer = secs.max(0);
+        self
     }
 }
 
@@ -118,7 +306,8 @@ impl SupabaseClient {
             .ok_or_else(|| SupabaseError::Storage("localStorage not available".to_string()))
     }
 
-    pub fn get_session(&self) -> Result<Option<Session>, SupabaseError> {
+    /// Session saved in localStorage, whether or not it has expired
+    fn stored_session() -> Result<Option<Session>, SupabaseError> {
         let storage = Self::get_storage()?;
         let session_str = match storage
             .get_item("supabase.auth.session")
@@ -128,21 +317,118 @@ impl SupabaseClient {
             None => return Ok(None),
         };
 
-        let session: Session = serde_json::from_str(&session_str)
-            .map_err(|e| SupabaseError::Parse(format!("Failed to parse session: {}", e)))?;
+        serde_json::from_str(&session_str)
+            .map(Some)
+            .map_err(|e| SupabaseError::Parse(format!("Failed to parse session: {}", e)))
+    }
+
+    /// The stored session if it is still valid.
+    ///
+    /// An expired session is kept in storage when it has a refresh token, so
+    /// [`refresh_session`](Self::refresh_session) can renew it; otherwise it is cleared.
+    pub fn get_session(&self) -> Result<Option<Session>, SupabaseError> {
+        let session = match Self::stored_session()? {
+            Some(session) => session,
+            None => return Ok(None),
+        };
 
-        // Check if session is expired
-        if let Some(expires_at) = session.expires_at {
-            let now = js_sys::Date::now() as i64 / 1000;
-            if now >= expires_at {
+        // Check if session is expired, or about to be
+        let now = js_sys::Date::now() as i64 / 1000;
+        if session.is_expired(now, self.expiry_buffer) {
+            if session.refresh_token.is_empty() {
                 self.clear_session()?;
-                return Ok(None);
             }
+            return Ok(None);
         }
 
         Ok(Some(session))
     }
 
+    /// Exchange the stored refresh token for a new session.
+    ///
+    /// The new session replaces the stored one. If Supabase rejects the refresh token
+    /// the stored session is cleared, so the user has to sign in again, and the error is
+    /// [`SupabaseError::Auth`]; a failure that may be transient keeps the session.
+    pub async fn refresh_session(&self) -> Result<Session, SupabaseError> {
+        let stored = Self::stored_session()?
+            .filter(|session| !session.refresh_token.is_empty())
+            .ok_or_else(|| SupabaseError::Auth("No session to refresh".to_string()))?;
+
+        let url = format!("{}/auth/v1/token?grant_type=refresh_token", self.url);
+        let payload = RefreshTokenRequest {
+            refresh_token: stored.refresh_token,
+        };
+        let payload_json = serde_json::to_string(&payload)
+            .map_err(|e| SupabaseError::Parse(format!("Failed to serialize request: {}", e)))?;
+
+        let headers = Headers::new()
+            .map_err(|e| SupabaseError::Network(format!("Failed to create headers: {:?}", e)))?;
+        headers.set("apikey", &self.anon_key)
+            .map_err(|e| SupabaseError::Network(format!("Failed to set apikey header: {:?}", e)))?;
+        headers.set("Content-Type", "application/json")
+            .map_err(|e| SupabaseError::Network(format!("Failed to set content-type header: {:?}", e)))?;
+
+        let opts = RequestInit::new();
+        opts.set_method("POST");
+        opts.set_mode(RequestMode::Cors);
+        opts.set_body(&JsValue::from_str(&payload_json));
+        opts.set_headers(&headers);
+
+        let request = Request::new_with_str_and_init(&url, &opts)
+            .map_err(|e| SupabaseError::Network(format!("Failed to create request: {:?}", e)))?;
+
+        let window = window().ok_or_else(|| SupabaseError::Network("Window not available".to_string()))?;
+        let resp_value = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|e| SupabaseError::Network(format!("Request failed: {:?}", e)))?;
+
+        let resp: Response = resp_value.dyn_into()
+            .map_err(|e| SupabaseError::Network(format!("Response is not a Response: {:?}", e)))?;
+
+        if !resp.ok() {
+            let status = resp.status();
+            let error_text = JsFuture::from(resp.text().map_err(|e| {
+                SupabaseError::Network(format!("Failed to get response text: {:?}", e))
+            })?)
+                .await
+                .ok()
+                .and_then(|v| v.as_string())
+                .unwrap_or_else(|| "Unknown error".to_string());
+
+            // 400/401 mean the refresh token is invalid or revoked; other failures
+            // may be transient, so the session is kept for a later attempt
+            if status == 400 || status == 401 {
+                self.clear_session()?;
+                return Err(SupabaseError::Auth(error_text));
+            }
+            return Err(SupabaseError::Network(format!("HTTP {}: {}", status, error_text)));
+        }
+
+        let json = JsFuture::from(resp.json().map_err(|e| {
+            SupabaseError::Parse(format!("Failed to get JSON: {:?}", e))
+        })?)
+            .await
+            .map_err(|e| SupabaseError::Parse(format!("Failed to parse JSON: {:?}", e)))?;
+
+        // The token endpoint answers a refresh with the same shape as a sign-in
+        let auth_response: SignInResponse = serde_wasm_bindgen::from_value(json)
+            .map_err(|e| SupabaseError::Parse(format!("Failed to deserialize response: {}", e)))?;
+
+        let session = Session {
+            access_token: auth_response.access_token,
+            refresh_token: auth_response.refresh_token,
+            expires_at: resolve_expires_at(
+                auth_response.expires_at,
+                auth_response.expires_in,
+                js_sys::Date::now() as i64 / 1000,
+            ),
+            user: auth_response.user,
+        };
+
+        self.set_session(session.clone())?;
+        Ok(session)
+    }
+
     pub fn set_session(&self, session: Session) -> Result<(), SupabaseError> {
         let storage = Self::get_storage()?;
         let session_str = serde_json::to_string(&session)
@@ -215,7 +501,11 @@ impl SupabaseClient {
             let session = Session {
                 access_token: signup_with_session.access_token,
                 refresh_token: signup_with_session.refresh_token,
-                expires_at: signup_with_session.expires_at,
+                expires_at: resolve_expires_at(
+                    signup_with_session.expires_at,
+                    signup_with_session.expires_in,
+                    js_sys::Date::now() as i64 / 1000,
+                ),
                 user: signup_with_session.user,
             };
             self.set_session(session.clone())?;
@@ -223,11 +513,9 @@ impl SupabaseClient {
         } else {
             // Try user-only response (email confirmation enabled)
             match serde_wasm_bindgen::from_value::<SignUpResponseWithoutSession>(json) {
-                Ok(_user_response) => {
+                Ok(user_response) => {
                     // Email confirmation required - user created but no session
-                    Err(SupabaseError::Auth(
-                        "Account created! Please check your email to confirm your account before signing in.".to_string()
-                    ))
+                    Err(SupabaseError::ConfirmationRequired(user_response.email))
                 }
                 Err(e) => {
                     Err(SupabaseError::Parse(format!("Failed to deserialize signup response: {}", e)))
@@ -266,6 +554,10 @@ impl SupabaseClient {
         let resp: Response = resp_value.dyn_into()
             .map_err(|e| SupabaseError::Network(format!("Response is not a Response: {:?}", e)))?;
 
+        if resp.status() == 429 {
+            return Err(SupabaseError::TooManyAttempts(Self::retry_after(&resp)));
+        }
+
         if !resp.ok() {
             let error_text = JsFuture::from(resp.text().map_err(|e| {
                 SupabaseError::Network(format!("Failed to get response text: {:?}", e))
@@ -289,7 +581,11 @@ impl SupabaseClient {
         let session = Session {
             access_token: auth_response.access_token,
             refresh_token: auth_response.refresh_token,
-            expires_at: auth_response.expires_at,
+            expires_at: resolve_expires_at(
+                auth_response.expires_at,
+                auth_response.expires_in,
+                js_sys::Date::now() as i64 / 1000,
+            ),
             user: auth_response.user,
         };
 
@@ -297,6 +593,218 @@ impl SupabaseClient {
         Ok(session)
     }
 
+    /// Seconds a 429 response asks us to wait. The header is only readable when
+    /// Supabase exposes it to CORS requests, hence the default.
+    fn retry_after(resp: &Response) -> u64 {
+        let now = js_sys::Date::now() as i64 / 1000;
+        resp.headers()
+            .get("Retry-After")
+            .ok()
+            .flatten()
+            .and_then(|value| {
+                parse_retry_after(&value, now, |date| {
+                    let millis = js_sys::Date::parse(date);
+                    (!millis.is_nan()).then_some(millis as i64 / 1000)
+                })
+            })
+            .unwrap_or(DEFAULT_RETRY_AFTER_SECS)
+    }
+
+    /// Send a request to the Supabase auth API, turning error responses into
+    /// [`SupabaseError::Auth`] with Supabase's message
+    async fn send(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<String>,
+        access_token: Option<&str>,
+    ) -> Result<Response, SupabaseError> {
+        let headers = Headers::new()
+            .map_err(|e| SupabaseError::Network(format!("Failed to create headers: {:?}", e)))?;
+        headers.set("apikey", &self.anon_key)
+            .map_err(|e| SupabaseError::Network(format!("Failed to set apikey header: {:?}", e)))?;
+        headers.set("Content-Type", "application/json")
+            .map_err(|e| SupabaseError::Network(format!("Failed to set content-type header: {:?}", e)))?;
+        if let Some(token) = access_token {
+            headers.set("Authorization", &format!("Bearer {}", token))
+                .map_err(|e| SupabaseError::Network(format!("Failed to set auth header: {:?}", e)))?;
+        }
+
+        let opts = RequestInit::new();
+        opts.set_method(method);
+        opts.set_mode(RequestMode::Cors);
+        if let Some(body) = body {
+            opts.set_body(&JsValue::from_str(&body));
+        }
+        opts.set_headers(&headers);
+
+        let request = Request::new_with_str_and_init(url, &opts)
+            .map_err(|e| SupabaseError::Network(format!("Failed to create request: {:?}", e)))?;
+
+        let window = window().ok_or_else(|| SupabaseError::Network("Window not available".to_string()))?;
+        let resp_value = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|e| SupabaseError::Network(format!("Request failed: {:?}", e)))?;
+
+        let resp: Response = resp_value.dyn_into()
+            .map_err(|e| SupabaseError::Network(format!("Response is not a Response: {:?}", e)))?;
+
+        if !resp.ok() {
+            let status = resp.status();
+            let error_text = JsFuture::from(resp.text().map_err(|e| {
+                SupabaseError::Network(format!("Failed to get response text: {:?}", e))
+            })?)
+                .await
+                .ok()
+                .and_then(|v| v.as_string())
+                .unwrap_or_else(|| "Unknown error".to_string());
+            if status == 429 {
+                return Err(SupabaseError::RateLimited(error_message(&error_text)));
+            }
+            return Err(SupabaseError::Auth(error_message(&error_text)));
+        }
+
+        Ok(resp)
+    }
+
+    /// Email a password reset link. The link returns the user to `/reset-password`.
+    pub async fn request_password_reset(&self, email: String) -> Result<(), SupabaseError> {
+        let origin = window()
+            .ok_or_else(|| SupabaseError::Network("Window not available".to_string()))?
+            .location()
+            .origin()
+            .map_err(|e| SupabaseError::Network(format!("Failed to read location: {:?}", e)))?;
+        let redirect_to: String = url::form_urlencoded::byte_serialize(
+            format!("{}/reset-password", origin).as_bytes(),
+        )
+        .collect();
+
+        let url = format!("{}/auth/v1/recover?redirect_to={}", self.url, redirect_to);
+        let payload_json = serde_json::to_string(&PasswordRecoveryRequest { email })
+            .map_err(|e| SupabaseError::Parse(format!("Failed to serialize request: {}", e)))?;
+
+        self.send("POST", &url, Some(payload_json), None).await?;
+        Ok(())
+    }
+
+    /// Send the sign-up confirmation email again
+    pub async fn resend_confirmation(&self, email: String) -> Result<(), SupabaseError> {
+        let url = format!("{}/auth/v1/resend", self.url);
+        let payload = ResendRequest {
+            kind: "signup".to_string(),
+            email,
+        };
+        let payload_json = serde_json::to_string(&payload)
+            .map_err(|e| SupabaseError::Parse(format!("Failed to serialize request: {}", e)))?;
+
+        self.send("POST", &url, Some(payload_json), None).await?;
+        Ok(())
+    }
+
+    /// Set a new password for the user the access token belongs to (e.g. the token from a
+    /// recovery link)
+    pub async fn update_password(&self, access_token: &str, new_password: String) -> Result<(), SupabaseError> {
+        let url = format!("{}/auth/v1/user", self.url);
+        let payload_json = serde_json::to_string(&UpdatePasswordRequest { password: new_password })
+            .map_err(|e| SupabaseError::Parse(format!("Failed to serialize request: {}", e)))?;
+
+        self.send("PUT", &url, Some(payload_json), Some(access_token)).await?;
+        Ok(())
+    }
+
+    /// Redirect the browser to the provider's sign-in page. Supabase sends the user back
+    /// to `/auth/callback`, where [`handle_oauth_callback`](Self::handle_oauth_callback)
+    /// finishes the sign-in.
+    ///
+    /// A fresh `state` is kept in sessionStorage and added to the callback URL, so the
+    /// callback only accepts tokens from a sign-in this tab started.
+    pub fn sign_in_with_oauth(&self, provider: &str) -> Result<(), SupabaseError> {
+        let window = window().ok_or_else(|| SupabaseError::Network("Window not available".to_string()))?;
+        let location = window.location();
+        let origin = location
+            .origin()
+            .map_err(|e| SupabaseError::Network(format!("Failed to read location: {:?}", e)))?;
+        check_redirect_origin(&origin, &get_oauth_redirect_origins()).map_err(SupabaseError::Auth)?;
+
+        let state = uuid::Uuid::new_v4().simple().to_string();
+        Self::oauth_state_storage()?
+            .set_item(OAUTH_STATE_KEY, &state)
+            .map_err(|_| SupabaseError::Storage("Failed to write to sessionStorage".to_string()))?;
+
+        let callback = format!("{}/auth/callback?state={}", origin, state);
+        let query: String = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("provider", provider)
+            .append_pair("redirect_to", &callback)
+            .finish();
+        let url = format!("{}/auth/v1/authorize?{}", self.url, query);
+
+        location
+            .set_href(&url)
+            .map_err(|e| SupabaseError::Network(format!("Failed to redirect: {:?}", e)))
+    }
+
+    fn oauth_state_storage() -> Result<web_sys::Storage, SupabaseError> {
+        window()
+            .ok_or_else(|| SupabaseError::Storage("Window not available".to_string()))?
+            .session_storage()
+            .map_err(|_| SupabaseError::Storage("Failed to access sessionStorage".to_string()))?
+            .ok_or_else(|| SupabaseError::Storage("sessionStorage not available".to_string()))
+    }
+
+    /// Store the session from the tokens in the OAuth callback URL fragment
+    ///
+    /// Tokens are only accepted on an origin from `VITE_OAUTH_REDIRECT_ORIGINS`, and only
+    /// with the `state` [`sign_in_with_oauth`](Self::sign_in_with_oauth) stored, which is
+    /// used up here, so a callback URL crafted elsewhere can't inject a session.
+    pub async fn handle_oauth_callback(&self) -> Result<Session, SupabaseError> {
+        let location = window()
+            .ok_or_else(|| SupabaseError::Network("Window not available".to_string()))?
+            .location();
+        let origin = location
+            .origin()
+            .map_err(|e| SupabaseError::Network(format!("Failed to read location: {:?}", e)))?;
+        check_redirect_origin(&origin, &get_oauth_redirect_origins()).map_err(SupabaseError::Auth)?;
+
+        let storage = Self::oauth_state_storage()?;
+        let expected = storage
+            .get_item(OAUTH_STATE_KEY)
+            .map_err(|_| SupabaseError::Storage("Failed to read from sessionStorage".to_string()))?;
+        storage
+            .remove_item(OAUTH_STATE_KEY)
+            .map_err(|_| SupabaseError::Storage("Failed to write to sessionStorage".to_string()))?;
+        let query = location
+            .search()
+            .map_err(|e| SupabaseError::Network(format!("Failed to read location: {:?}", e)))?;
+        check_oauth_state(&query, expected.as_deref()).map_err(SupabaseError::Auth)?;
+
+        let hash = location
+            .hash()
+            .map_err(|e| SupabaseError::Network(format!("Failed to read location: {:?}", e)))?;
+        let tokens = parse_oauth_fragment(&hash, js_sys::Date::now() as i64 / 1000)
+            .map_err(SupabaseError::Auth)?;
+
+        // The fragment only carries tokens; the user comes from the user endpoint
+        let url = format!("{}/auth/v1/user", self.url);
+        let resp = self.send("GET", &url, None, Some(&tokens.access_token)).await?;
+        let json = JsFuture::from(resp.json().map_err(|e| {
+            SupabaseError::Parse(format!("Failed to get JSON: {:?}", e))
+        })?)
+            .await
+            .map_err(|e| SupabaseError::Parse(format!("Failed to parse JSON: {:?}", e)))?;
+        let user: User = serde_wasm_bindgen::from_value(json)
+            .map_err(|e| SupabaseError::Parse(format!("Failed to deserialize user: {}", e)))?;
+
+        let session = Session {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            expires_at: tokens.expires_at,
+            user,
+        };
+
+        self.set_session(session.clone())?;
+        Ok(session)
+    }
+
     pub async fn sign_out(&self) -> Result<(), SupabaseError> {
         let session = self.get_session()?;
         if let Some(session) = session {
@@ -341,6 +849,10 @@ impl SupabaseClient {
         Err(SupabaseError::Storage("Supabase client not available on server".to_string()))
     }
 
+    pub async fn refresh_session(&self) -> Result<Session, SupabaseError> {
+        Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
+    }
+
     pub async fn sign_up(&self, _email: String, _password: String) -> Result<Session, SupabaseError> {
         Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
     }
@@ -349,6 +861,26 @@ impl SupabaseClient {
         Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
     }
 
+    pub async fn request_password_reset(&self, _email: String) -> Result<(), SupabaseError> {
+        Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
+    }
+
+    pub async fn resend_confirmation(&self, _email: String) -> Result<(), SupabaseError> {
+        Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
+    }
+
+    pub async fn update_password(&self, _access_token: &str, _new_password: String) -> Result<(), SupabaseError> {
+        Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
+    }
+
+    pub fn sign_in_with_oauth(&self, _provider: &str) -> Result<(), SupabaseError> {
+        Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
+    }
+
+    pub async fn handle_oauth_callback(&self) -> Result<Session, SupabaseError> {
+        Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
+    }
+
     pub async fn sign_out(&self) -> Result<(), SupabaseError> {
         Err(SupabaseError::Auth("Supabase client not available on server".to_string()))
     }
@@ -53,4 +53,148 @@ pub fn get_supabase_anon_key() -> String {
     // Server-side: read from environment variable or return default
     std::env::var("VITE_SUPABASE_ANON_KEY")
         .unwrap_or_else(|_| "your-anon-key".to_string())
-}
\ No newline at end of file
+}
+/// OAuth providers with a sign-in button, in display order
+pub const OAUTH_PROVIDERS: &[(&str, &str)] = &[("google", "Google"), ("github", "GitHub")];
+
+/// Keep the providers from a comma-separated list that have a sign-in button
+fn parse_oauth_providers(list: &str) -> Vec<String> {
+    let enabled: Vec<String> = list
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    OAUTH_PROVIDERS
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| enabled.contains(name))
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get_oauth_providers() -> Vec<String> {
+    // Try to get from window.__ENV__; no providers unless configured
+    if let Some(win) = window() {
+        if let Ok(env) = js_sys::Reflect::get(&win, &JsValue::from_str("__ENV__")) {
+            if !env.is_undefined() && !env.is_null() {
+                if let Ok(list) = js_sys::Reflect::get(&env, &JsValue::from_str("VITE_SUPABASE_OAUTH_PROVIDERS")) {
+                    if let Some(list_str) = list.as_string() {
+                        return parse_oauth_providers(&list_str);
+                    }
+                }
+            }
+        }
+    }
+    
+    Vec::new()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_oauth_providers() -> Vec<String> {
+    // Server-side: read from environment variable, e.g. "google,github"
+    std::env::var("VITE_SUPABASE_OAUTH_PROVIDERS")
+        .map(|list| parse_oauth_providers(&list))
+        .unwrap_or_default()
+}
+
+/// Normalize an origin for comparison: lower-case, without a trailing slash
+fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_lowercase()
+}
+
+/// Origins from a comma-separated list, normalized
+fn parse_redirect_origins(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(normalize_origin)
+        .filter(|origin| !origin.is_empty())
+        .collect()
+}
+
+/// Check that an OAuth sign-in may start from, and return tokens to, `origin`.
+///
+/// Fails closed: with an empty allowlist no origin is accepted.
+///
+/// ```
+/// use cron_jobs::config::check_redirect_origin;
+///
+/// let allowed = vec!["https://cron.example.com".to_string()];
+/// assert!(check_redirect_origin("https://cron.example.com", &allowed).is_ok());
+/// assert!(check_redirect_origin("https://CRON.example.com/", &allowed).is_ok());
+/// assert!(check_redirect_origin("https://evil.example.net", &allowed).is_err());
+/// assert!(check_redirect_origin("http://cron.example.com", &allowed).is_err());
+/// assert!(check_redirect_origin("https://cron.example.com", &[]).is_err());
+/// ```
+pub fn check_redirect_origin(origin: &str, allowed: &[String]) -> Result<(), String> {
+    let origin = normalize_origin(origin);
+    if allowed.is_empty() {
+        return Err("OAuth sign-in is disabled: VITE_OAUTH_REDIRECT_ORIGINS is not set".to_string());
+    }
+    if allowed.iter().any(|allowed| normalize_origin(allowed) == origin) {
+        Ok(())
+    } else {
+        Err(format!("Sign-in redirect to {} is not allowed", origin))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get_oauth_redirect_origins() -> Vec<String> {
+    // Try to get from window.__ENV__; no origin unless configured
+    if let Some(win) = window() {
+        if let Ok(env) = js_sys::Reflect::get(&win, &JsValue::from_str("__ENV__")) {
+            if !env.is_undefined() && !env.is_null() {
+                if let Ok(list) = js_sys::Reflect::get(&env, &JsValue::from_str("VITE_OAUTH_REDIRECT_ORIGINS")) {
+                    if let Some(list_str) = list.as_string() {
+                        return parse_redirect_origins(&list_str);
+                    }
+                }
+            }
+        }
+    }
+    
+    Vec::new()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_oauth_redirect_origins() -> Vec<String> {
+    // Server-side: read from environment variable, e.g. "https://cron.example.com"
+    std::env::var("VITE_OAUTH_REDIRECT_ORIGINS")
+        .map(|list| parse_redirect_origins(&list))
+        .unwrap_or_default()
+}
+
+/// Seconds before `expires_at` a session is already treated as expired
+pub const DEFAULT_SESSION_EXPIRY_BUFFER_SECS: i64 = 60;
+
+fn parse_expiry_buffer(value: &str) -> i64 {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|secs| *secs >= 0)
+        .unwrap_or(DEFAULT_SESSION_EXPIRY_BUFFER_SECS)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get_session_expiry_buffer_secs() -> i64 {
+    // Try to get from window.__ENV__ or use default
+    if let Some(win) = window() {
+        if let Ok(env) = js_sys::Reflect::get(&win, &JsValue::from_str("__ENV__")) {
+            if !env.is_undefined() && !env.is_null() {
+                if let Ok(secs) = js_sys::Reflect::get(&env, &JsValue::from_str("VITE_SESSION_EXPIRY_BUFFER_SECS")) {
+                    if let Some(secs_str) = secs.as_string() {
+                        return parse_expiry_buffer(&secs_str);
+                    }
+                }
+            }
+        }
+    }
+
+    DEFAULT_SESSION_EXPIRY_BUFFER_SECS
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_session_expiry_buffer_secs() -> i64 {
+    // Server-side: read from environment variable or return default
+    std::env::var("VITE_SESSION_EXPIRY_BUFFER_SECS")
+        .map(|secs| parse_expiry_buffer(&secs))
+        .unwrap_or(DEFAULT_SESSION_EXPIRY_BUFFER_SECS)
+}
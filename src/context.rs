@@ -1,14 +1,32 @@
-use crate::client::{Session, SupabaseClient, User, SupabaseError};
-use crate::config::{get_supabase_url, get_supabase_anon_key};
+use crate::client::{AuthClient, Session, SupabaseClient, User, SupabaseError};
+use crate::config::{get_supabase_url, get_supabase_anon_key, get_session_expiry_buffer_secs};
 use leptos::prelude::*;
+use leptos::server_fn::codec::GetUrl;
 use leptos::task::spawn_local;
+use std::future::Future;
+
+/// Start of the message of every server function error answered with 401, so the browser
+/// can tell a missing, expired or rejected session from other failures
+pub const UNAUTHORIZED_PREFIX: &str = "Unauthorized: ";
+
+/// Shown on the login page after an expired session has been signed out
+pub const SESSION_EXPIRED_NOTICE: &str = "Session expired, please log in again";
+
+/// Whether a server function failed because its access token was not accepted
+pub fn is_unauthorized(error: &ServerFnError) -> bool {
+    matches!(error, ServerFnError::ServerError(msg) if msg.starts_with(UNAUTHORIZED_PREFIX))
+}
 
 // Server function definition - simplified macro
-#[server(CreateUserDatabase, "/api")]
-pub async fn create_user_database_action(email: String, access_token: String) -> Result<String, ServerFnError> {
+// The email recorded for a new database comes from the verified token, not the caller.
+// Sent with `AuthClient` for its CSRF header.
+#[server(name = CreateUserDatabase, prefix = "/api", client = AuthClient)]
+pub async fn create_user_database_action(access_token: String) -> Result<String, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use crate::server::turso::{get_supabase_user_id, TursoClient, TursoConfig};
+        use crate::server::turso::{get_supabase_claims, ProvisionStatus, TursoClient, TursoConfig};
+        use crate::server::actions::helpers::{auth_error, server_fn_error, unauthorized_error};
+        use crate::server::error::ServerError;
         use crate::server::service::create_user_database;
         use leptos_actix::extract;
         use log::{info, error};
@@ -28,15 +46,18 @@ pub async fn create_user_database_action(email: String, access_token: String) ->
         let config = TursoConfig::from_env()
             .map_err(|e| ServerFnError::new(format!("Config error: {}", e)))?;
         
-        let user_id = get_supabase_user_id(&access_token, &config.supabase)
+        let claims = get_supabase_claims(&access_token, &config.supabase)
             .await
-            .map_err(|e| ServerFnError::new(format!("JWT validation failed: {}", e)))?;
+            .map_err(|e| auth_error("JWT validation failed", e))?;
+        let user_id = claims.sub;
+        let email = claims.email
+            .ok_or_else(|| unauthorized_error("Access token has no email"))?;
         
         info!("[Database Setup] Checking database for user: {} ({})", user_id, email);
         
         // Check if user database already exists
         match client.get_user_database_entry(&user_id).await {
-            Ok(_) => {
+            Ok(entry) if entry.provision_status == ProvisionStatus::Ready => {
                 info!("[Database Setup] Database exists for user {}, syncing schema...", user_id);
                 client.sync_user_database_schema(&user_id)
                     .await
@@ -47,9 +68,11 @@ pub async fn create_user_database_action(email: String, access_token: String) ->
                 info!("[Database Setup] Schema sync completed successfully for user {}", user_id);
                 Ok("Database schema updated".to_string())
             }
-            Err(_) => {
+            Ok(_) | Err(ServerError::NotFound(_)) => {
+                // No database yet, or an earlier attempt was interrupted; provisioning
+                // resumes from the last step recorded in the registry
                 info!("[Database Setup] Creating new database for user: {} ({})", user_id, email);
-                create_user_database(&client, &user_id, &email)
+                create_user_database(&client, &user_id, &email, None)
                     .await
                     .map_err(|e| {
                         error!("[Database Setup] Failed to create database for user {}: {}", user_id, e);
@@ -58,6 +81,12 @@ pub async fn create_user_database_action(email: String, access_token: String) ->
                 info!("[Database Setup] Database created successfully for user: {} ({})", user_id, email);
                 Ok("Database created".to_string())
             }
+            // A registry failure says nothing about whether the database exists, and
+            // provisioning on top of it could replace a ready entry
+            Err(e) => {
+                error!("[Database Setup] Failed to look up database for user {}: {}", user_id, e);
+                Err(server_fn_error("Failed to look up user database", e))
+            }
         }
     }
     #[cfg(not(feature = "ssr"))]
@@ -68,6 +97,184 @@ pub async fn create_user_database_action(email: String, access_token: String) ->
     }
 }
 
+/// What the signed-in user may see about their own database (no URL or token)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DatabaseInfo {
+    pub db_name: String,
+    /// Primary Turso region, when the Turso API could be reached
+    pub region: Option<String>,
+    pub storage_used_bytes: Option<i64>,
+    pub created_at: String,
+}
+
+// Looks up the signed-in user's database for the home page
+#[server(name = GetDatabaseInfo, prefix = "/api", input = GetUrl, client = AuthClient)]
+pub async fn get_database_info_action() -> Result<DatabaseInfo, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server::actions::helpers::{get_turso_client, get_user_id_from_request, server_fn_error};
+        use crate::server::service::get_database_info;
+        use leptos_actix::extract;
+        
+        let req = extract::<actix_web::HttpRequest>().await
+            .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+        let user_id = get_user_id_from_request(&req).await?;
+        let client = get_turso_client(&req)?;
+        
+        get_database_info(&client, &user_id)
+            .await
+            .map_err(|e| server_fn_error("Failed to get database info", e))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Server function should not be called directly on client"))
+    }
+}
+
+/// One row of the dashboard job list
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DashboardJob {
+    pub id: String,
+    pub name: String,
+    pub schedule: String,
+    /// English description of the schedule, when it can be described
+    pub schedule_human: Option<String>,
+    pub enabled: bool,
+}
+
+// Lists the signed-in user's jobs for the dashboard, newest first
+#[server(name = GetDashboardJobs, prefix = "/api", input = GetUrl, client = AuthClient)]
+pub async fn get_dashboard_jobs_action() -> Result<Vec<DashboardJob>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server::actions::helpers::{get_turso_client, get_user_id_from_request, server_fn_error};
+        use crate::server::models::{ListCronJobsFilter, Pagination, MAX_PAGE_SIZE};
+        use crate::server::service::get_user_cron_jobs;
+        use leptos_actix::extract;
+        
+        let req = extract::<actix_web::HttpRequest>().await
+            .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+        let client = get_turso_client(&req)?;
+        let user_id = get_user_id_from_request(&req).await?;
+        
+        let pagination = Pagination {
+            limit: Some(MAX_PAGE_SIZE),
+            offset: None,
+        };
+        let page = get_user_cron_jobs(&client, &user_id, ListCronJobsFilter::default(), pagination)
+            .await
+            .map_err(|e| server_fn_error("Failed to get cron jobs", e))?;
+        
+        Ok(page
+            .jobs
+            .into_iter()
+            .map(|job| DashboardJob {
+                id: job.id,
+                name: job.name,
+                schedule: job.schedule,
+                schedule_human: job.schedule_human,
+                enabled: job.enabled,
+            })
+            .collect())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Server function should not be called directly on client"))
+    }
+}
+
+// Moves one of the signed-in user's jobs to the trash from the dashboard
+#[server(name = DeleteDashboardJob, prefix = "/api", client = AuthClient)]
+pub async fn delete_dashboard_job_action(job_id: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server::actions::helpers::{get_turso_client, get_user_id_from_request, server_fn_error};
+        use crate::server::service::delete_cron_job;
+        use leptos_actix::extract;
+        
+        let req = extract::<actix_web::HttpRequest>().await
+            .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+        let client = get_turso_client(&req)?;
+        let user_id = get_user_id_from_request(&req).await?;
+        
+        delete_cron_job(&client, &user_id, &job_id)
+            .await
+            .map_err(|e| server_fn_error("Failed to delete cron job", e))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Server function should not be called directly on client"))
+    }
+}
+
+// Enables or disables one of the signed-in user's jobs from the dashboard
+#[server(name = SetDashboardJobEnabled, prefix = "/api", client = AuthClient)]
+pub async fn set_dashboard_job_enabled_action(
+    job_id: String,
+    enabled: bool,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server::actions::helpers::{get_turso_client, get_user_id_from_request, server_fn_error};
+        use crate::server::models::UpdateCronJobRequest;
+        use crate::server::service::update_cron_job;
+        use leptos_actix::extract;
+        
+        let req = extract::<actix_web::HttpRequest>().await
+            .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+        let client = get_turso_client(&req)?;
+        let user_id = get_user_id_from_request(&req).await?;
+        
+        let request = UpdateCronJobRequest {
+            name: None,
+            schedule: None,
+            command: None,
+            enabled: Some(enabled),
+            timezone: None,
+            job_type: None,
+            http_method: None,
+            http_body: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            run_history_limit: None,
+            misfire_policy: None,
+            max_catchup: None,
+        };
+        update_cron_job(&client, &user_id, &job_id, request)
+            .await
+            .map(|_| ())
+            .map_err(|e| server_fn_error("Failed to update cron job", e))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Server function should not be called directly on client"))
+    }
+}
+
+// Issues a double-submit CSRF token: set as a cookie and returned for use in the
+// X-CSRF-Token header on mutating server function calls. A GET, so the CSRF check
+// doesn't apply to it.
+#[server(name = IssueCsrfToken, prefix = "/api", input = GetUrl)]
+pub async fn issue_csrf_token() -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server::middleware::csrf::csrf_cookie;
+        use actix_web::http::header::{HeaderValue, SET_COOKIE};
+        use leptos_actix::ResponseOptions;
+        
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let cookie = HeaderValue::from_str(&csrf_cookie(&token))
+            .map_err(|e| ServerFnError::new(format!("Failed to build CSRF cookie: {}", e)))?;
+        
+        expect_context::<ResponseOptions>().insert_header(SET_COOKIE, cookie);
+        Ok(token)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Server function should not be called directly on client"))
+    }
+}
+
 #[derive(Clone)]
 pub struct AuthContext {
     pub user: RwSignal<Option<User>>,
@@ -75,6 +282,9 @@ pub struct AuthContext {
     pub client: SupabaseClient,
     pub is_loading: RwSignal<bool>,
     pub db_status: RwSignal<Option<String>>,
+    pub csrf_token: RwSignal<Option<String>>,
+    /// Why the user was signed out, for the login page to show
+    pub session_notice: RwSignal<Option<String>>,
 }
 
 impl AuthContext {
@@ -82,12 +292,15 @@ impl AuthContext {
         let url = get_supabase_url();
         let anon_key = get_supabase_anon_key();
 
-        let client = SupabaseClient::new(url, anon_key);
+        let client = SupabaseClient::new(url, anon_key)
+            .with_expiry_buffer(get_session_expiry_buffer_secs());
         
         let user = RwSignal::new(None);
         let session = RwSignal::new(None);
         let is_loading = RwSignal::new(true);
         let db_status = RwSignal::new(None);
+        let csrf_token = RwSignal::new(None);
+        let session_notice = RwSignal::new(None);
 
         let context = Self {
             user,
@@ -95,6 +308,8 @@ impl AuthContext {
             client,
             is_loading,
             db_status,
+            csrf_token,
+            session_notice,
         };
 
         // Initialize from localStorage
@@ -112,8 +327,18 @@ impl AuthContext {
                     context.user.set(Some(session.user));
                 }
                 Ok(None) => {
-                    context.session.set(None);
-                    context.user.set(None);
+                    // An expired session with a refresh token is renewed instead of
+                    // logging the user out; without one this fails and stays logged out
+                    match context.client.refresh_session().await {
+                        Ok(session) => {
+                            context.session.set(Some(session.clone()));
+                            context.user.set(Some(session.user));
+                        }
+                        Err(_) => {
+                            context.session.set(None);
+                            context.user.set(None);
+                        }
+                    }
                 }
                 Err(_) => {
                     // Silently fail on init - might be first visit
@@ -131,38 +356,16 @@ impl AuthContext {
 
     pub async fn login(&self, email: String, password: String) -> Result<(), SupabaseError> {
         self.is_loading.set(true);
-        let result = self.client.sign_in(email.clone(), password).await;
+        let result = self.client.sign_in(email, password).await;
         
         match result {
             Ok(session) => {
                 self.session.set(Some(session.clone()));
                 self.user.set(Some(session.user.clone()));
+                self.session_notice.set(None);
                 
                 // Trigger database creation/update after successful login
-                let email_for_db = email.clone();
-                let access_token = session.access_token.clone();
-                let db_status = self.db_status.clone();
-                
-                db_status.set(Some("Initializing database...".to_string()));
-                
-                spawn_local(async move {
-                    match create_user_database_action(email_for_db, access_token).await {
-                        Ok(status) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("[Database] {}", status).into());
-                            #[cfg(not(target_arch = "wasm32"))]
-                            log::info!("[Database] {}", status);
-                            db_status.set(Some(format!("✓ {}", status)));
-                        }
-                        Err(e) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::error_1(&format!("[Database] Setup failed: {}", e).into());
-                            #[cfg(not(target_arch = "wasm32"))]
-                            log::error!("[Database] Setup failed: {}", e);
-                            db_status.set(Some(format!("⚠ Database setup failed: {}", e)));
-                        }
-                    }
-                });
+                self.start_database_setup(session.access_token);
                 
                 self.is_loading.set(false);
                 Ok(())
@@ -176,7 +379,7 @@ impl AuthContext {
 
     pub async fn signup(&self, email: String, password: String) -> Result<(), SupabaseError> {
         self.is_loading.set(true);
-        let result = self.client.sign_up(email.clone(), password).await;
+        let result = self.client.sign_up(email, password).await;
         
         match result {
             Ok(session) => {
@@ -184,30 +387,66 @@ impl AuthContext {
                 self.user.set(Some(session.user.clone()));
                 
                 // Trigger database creation/update after successful signup
-                let email_for_db = email.clone();
-                let access_token = session.access_token.clone();
-                let db_status = self.db_status.clone();
-                
-                db_status.set(Some("Initializing database...".to_string()));
+                self.start_database_setup(session.access_token);
                 
-                spawn_local(async move {
-                    match create_user_database_action(email_for_db, access_token).await {
-                        Ok(status) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("[Database] {}", status).into());
-                            #[cfg(not(target_arch = "wasm32"))]
-                            log::info!("[Database] {}", status);
-                            db_status.set(Some(format!("✓ {}", status)));
-                        }
-                        Err(e) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::error_1(&format!("[Database] Setup failed: {}", e).into());
-                            #[cfg(not(target_arch = "wasm32"))]
-                            log::error!("[Database] Setup failed: {}", e);
-                            db_status.set(Some(format!("⚠ Database setup failed: {}", e)));
-                        }
-                    }
-                });
+                self.is_loading.set(false);
+                Ok(())
+            }
+            Err(e) => {
+                self.is_loading.set(false);
+                Err(e)
+            }
+        }
+    }
+
+    /// Issue a CSRF token and create or update the user's database in the background
+    fn start_database_setup(&self, access_token: String) {
+        let db_status = self.db_status;
+        let csrf_token = self.csrf_token;
+        
+        db_status.set(Some("Initializing database...".to_string()));
+        
+        spawn_local(async move {
+            match issue_csrf_token().await {
+                Ok(token) => csrf_token.set(Some(token)),
+                Err(e) => {
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::error_1(&format!("[CSRF] Failed to issue token: {}", e).into());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    log::error!("[CSRF] Failed to issue token: {}", e);
+                }
+            }
+            
+            match create_user_database_action(access_token).await {
+                Ok(status) => {
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::log_1(&format!("[Database] {}", status).into());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    log::info!("[Database] {}", status);
+                    db_status.set(Some(format!("✓ {}", status)));
+                }
+                Err(e) => {
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::error_1(&format!("[Database] Setup failed: {}", e).into());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    log::error!("[Database] Setup failed: {}", e);
+                    db_status.set(Some(format!("⚠ Database setup failed: {}", e)));
+                }
+            }
+        });
+    }
+
+    /// Finish an OAuth sign-in from the tokens in the callback URL
+    pub async fn complete_oauth_sign_in(&self) -> Result<(), SupabaseError> {
+        self.is_loading.set(true);
+        let result = self.client.handle_oauth_callback().await;
+        
+        match result {
+            Ok(session) => {
+                self.session.set(Some(session.clone()));
+                self.user.set(Some(session.user.clone()));
+                self.session_notice.set(None);
+                self.start_database_setup(session.access_token);
                 
                 self.is_loading.set(false);
                 Ok(())
@@ -225,8 +464,46 @@ impl AuthContext {
         
         self.session.set(None);
         self.user.set(None);
+        self.csrf_token.set(None);
         self.is_loading.set(false);
         
         result
     }
-}
\ No newline at end of file
+
+    /// Make a server function call, recovering from an expired session
+    ///
+    /// When the call's access token is rejected the session is refreshed and the call
+    /// made once more, so `call` has to read the access token each time it runs. If
+    /// Supabase rejects the refresh token, or the retry's token is rejected too, the user
+    /// is logged out and [`session_notice`](Self::session_notice) says why;
+    /// `ProtectedRoute` then sends them to the login page. Other failures, including a
+    /// refresh that couldn't reach Supabase, are returned and the session kept.
+    pub async fn call<T, F, Fut>(&self, call: F) -> Result<T, ServerFnError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, ServerFnError>>,
+    {
+        let error = match call().await {
+            Err(e) if is_unauthorized(&e) => e,
+            result => return result,
+        };
+
+        match self.client.refresh_session().await {
+            Ok(session) => {
+                self.session.set(Some(session.clone()));
+                self.user.set(Some(session.user));
+                match call().await {
+                    Err(e) if is_unauthorized(&e) => {}
+                    result => return result,
+                }
+            }
+            // Supabase couldn't be reached; the refresh token may still be good
+            Err(e) if !matches!(e, SupabaseError::Auth(_)) => return Err(error),
+            Err(_) => {}
+        }
+
+        let _ = self.logout().await;
+        self.session_notice.set(Some(SESSION_EXPIRED_NOTICE.to_string()));
+        Err(error)
+    }
+}
@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// One of the five fields of a cron expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronFieldKind {
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+}
+
+impl CronFieldKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CronFieldKind::Minute => "minute",
+            CronFieldKind::Hour => "hour",
+            CronFieldKind::DayOfMonth => "day-of-month",
+            CronFieldKind::Month => "month",
+            CronFieldKind::DayOfWeek => "day-of-week",
+        }
+    }
+
+    /// Inclusive range of accepted values (day-of-week accepts 7 as Sunday)
+    pub fn range(&self) -> (u32, u32) {
+        match self {
+            CronFieldKind::Minute => (0, 59),
+            CronFieldKind::Hour => (0, 23),
+            CronFieldKind::DayOfMonth => (1, 31),
+            CronFieldKind::Month => (1, 12),
+            CronFieldKind::DayOfWeek => (0, 7),
+        }
+    }
+
+    fn names(&self) -> Option<&'static [&'static str]> {
+        match self {
+            CronFieldKind::Month => Some(&MONTH_NAMES),
+            CronFieldKind::DayOfWeek => Some(&DAY_NAMES),
+            _ => None,
+        }
+    }
+
+    /// Parse a field into a bitmask where bit `n` is set when value `n` matches.
+    ///
+    /// Day-of-week 7 is folded into 0 (Sunday).
+    pub fn mask(&self, field: &str) -> Result<u64, String> {
+        let (min, max) = self.range();
+        let mut mask = parse_field_mask(field, self.label(), min, max, self.names())?;
+
+        if *self == CronFieldKind::DayOfWeek && mask & (1 << 7) != 0 {
+            mask = (mask & !(1 << 7)) | 1;
+        }
+
+        Ok(mask)
+    }
+}
+
+/// Parse a cron field into a bitmask of the values in `min..=max` it matches
+pub(crate) fn parse_field_mask(
+    field: &str,
+    label: &str,
+    min: u32,
+    max: u32,
+    names: Option<&[&str]>,
+) -> Result<u64, String> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid step '{}' in {} field", step, label))?;
+                if step == 0 {
+                    return Err(format!("Step must be greater than zero in {} field", label));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_field_value(start, label, min, max, names)?,
+                parse_field_value(end, label, min, max, names)?,
+            )
+        } else {
+            let value = parse_field_value(range, label, min, max, names)?;
+            // "5/15" means "from 5 through the end of the range, every 15"
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if start > end {
+            return Err(format!("Invalid range {}-{} in {} field", start, end, label));
+        }
+
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step;
+        }
+    }
+
+    Ok(mask)
+}
+
+/// Parse a single numeric or named value within a cron field
+fn parse_field_value(
+    value: &str,
+    label: &str,
+    min: u32,
+    max: u32,
+    names: Option<&[&str]>,
+) -> Result<u32, String> {
+    if let Some(names) = names {
+        let upper = value.to_uppercase();
+        if let Some(index) = names.iter().position(|name| *name == upper) {
+            // Month names start at 1, day names start at 0
+            return Ok(index as u32 + min);
+        }
+    }
+
+    let parsed: u32 = value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' in {} field", value, label))?;
+
+    if parsed < min || parsed > max {
+        return Err(format!(
+            "Value {} out of range ({}-{}) in {} field",
+            parsed, min, max, label
+        ));
+    }
+
+    Ok(parsed)
+}
+
+/// Editable five-field cron expression
+///
+/// Shared with the client so a schedule builder can split an expression into its
+/// segments, edit them individually and serialize the result back. Every field is
+/// validated on parse and on mutation, and `to_string` yields a canonical expression
+/// (single spaces, upper-case month and day names) that parses back to the same value.
+/// Fields are private and serde goes through [`CronExpr::parse`], so an invalid
+/// expression can't be built or deserialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronExpr {
+    minute: String,
+    hour: String,
+    day_of_month: String,
+    month: String,
+    day_of_week: String,
+}
+
+impl CronExpr {
+    /// Parse and validate a five-field cron expression
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Expected 5 fields (minute hour day-of-month month day-of-week), found {}",
+                fields.len()
+            ));
+        }
+
+        let mut parsed = CronExpr {
+            minute: String::new(),
+            hour: String::new(),
+            day_of_month: String::new(),
+            month: String::new(),
+            day_of_week: String::new(),
+        };
+        parsed.set(CronFieldKind::Minute, fields[0])?;
+        parsed.set(CronFieldKind::Hour, fields[1])?;
+        parsed.set(CronFieldKind::DayOfMonth, fields[2])?;
+        parsed.set(CronFieldKind::Month, fields[3])?;
+        parsed.set(CronFieldKind::DayOfWeek, fields[4])?;
+        Ok(parsed)
+    }
+
+    /// Get the raw text of a field
+    pub fn get(&self, kind: CronFieldKind) -> &str {
+        match kind {
+            CronFieldKind::Minute => &self.minute,
+            CronFieldKind::Hour => &self.hour,
+            CronFieldKind::DayOfMonth => &self.day_of_month,
+            CronFieldKind::Month => &self.month,
+            CronFieldKind::DayOfWeek => &self.day_of_week,
+        }
+    }
+
+    /// Validate and replace a field, leaving the expression unchanged on error
+    pub fn set(&mut self, kind: CronFieldKind, value: &str) -> Result<(), String> {
+        let value = value.trim().to_uppercase();
+        if value.is_empty() {
+            return Err(format!("The {} field must not be empty", kind.label()));
+        }
+        kind.mask(&value)?;
+
+        let slot = match kind {
+            CronFieldKind::Minute => &mut self.minute,
+            CronFieldKind::Hour => &mut self.hour,
+            CronFieldKind::DayOfMonth => &mut self.day_of_month,
+            CronFieldKind::Month => &mut self.month,
+            CronFieldKind::DayOfWeek => &mut self.day_of_week,
+        };
+        *slot = value;
+        Ok(())
+    }
+
+    pub fn minute(&self) -> &str {
+        &self.minute
+    }
+
+    pub fn hour(&self) -> &str {
+        &self.hour
+    }
+
+    pub fn day_of_month(&self) -> &str {
+        &self.day_of_month
+    }
+
+    pub fn month(&self) -> &str {
+        &self.month
+    }
+
+    pub fn day_of_week(&self) -> &str {
+        &self.day_of_week
+    }
+
+    pub fn set_minute(&mut self, value: &str) -> Result<(), String> {
+        self.set(CronFieldKind::Minute, value)
+    }
+
+    pub fn set_hour(&mut self, value: &str) -> Result<(), String> {
+        self.set(CronFieldKind::Hour, value)
+    }
+
+    pub fn set_day_of_month(&mut self, value: &str) -> Result<(), String> {
+        self.set(CronFieldKind::DayOfMonth, value)
+    }
+
+    pub fn set_month(&mut self, value: &str) -> Result<(), String> {
+        self.set(CronFieldKind::Month, value)
+    }
+
+    pub fn set_day_of_week(&mut self, value: &str) -> Result<(), String> {
+        self.set(CronFieldKind::DayOfWeek, value)
+    }
+
+    /// Bitmask of matching values for a field
+    pub fn mask(&self, kind: CronFieldKind) -> Result<u64, String> {
+        kind.mask(self.get(kind))
+    }
+}
+
+impl Default for CronExpr {
+    /// Every minute (`* * * * *`)
+    fn default() -> Self {
+        CronExpr {
+            minute: "*".to_string(),
+            hour: "*".to_string(),
+            day_of_month: "*".to_string(),
+            month: "*".to_string(),
+            day_of_week: "*".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.minute, self.hour, self.day_of_month, self.month, self.day_of_week
+        )
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CronExpr::parse(s)
+    }
+}
+
+impl TryFrom<String> for CronExpr {
+    type Error = String;
+
+    fn try_from(expr: String) -> Result<Self, Self::Error> {
+        CronExpr::parse(&expr)
+    }
+}
+
+impl From<CronExpr> for String {
+    fn from(expr: CronExpr) -> Self {
+        expr.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_serializes_canonically() {
+        for (input, canonical) in [
+            ("*/15 * * * *", "*/15 * * * *"),
+            ("  0   9  *  *  mon-fri ", "0 9 * * MON-FRI"),
+            ("30 2 1,15 jan,jul *", "30 2 1,15 JAN,JUL *"),
+            ("5/10 0-6 * * 7", "5/10 0-6 * * 7"),
+        ] {
+            let expr = CronExpr::parse(input).unwrap();
+            assert_eq!(expr.to_string(), canonical);
+            assert_eq!(CronExpr::parse(&expr.to_string()).unwrap(), expr);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for input in ["", "* * * *", "* * * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "*/0 * * * *", "5-1 * * * *", "* * * FOO *"] {
+            assert!(CronExpr::parse(input).is_err(), "{:?} should be rejected", input);
+        }
+    }
+
+    #[test]
+    fn a_rejected_edit_leaves_the_expression_unchanged() {
+        let mut expr = CronExpr::parse("0 9 * * *").unwrap();
+        expr.set_hour("*/2").unwrap();
+        assert_eq!(expr.to_string(), "0 */2 * * *");
+        assert!(expr.set_minute("75").is_err());
+        assert!(expr.set_day_of_week(" ").is_err());
+        assert_eq!(expr.to_string(), "0 */2 * * *");
+        assert_eq!(expr.mask(CronFieldKind::Hour).unwrap().count_ones(), 12);
+    }
+
+    #[test]
+    fn serde_uses_the_string_form_and_validates_it() {
+        let expr = CronExpr::parse("0 9 * * mon").unwrap();
+        assert_eq!(serde_json::to_string(&expr).unwrap(), "\"0 9 * * MON\"");
+        assert_eq!(serde_json::from_str::<CronExpr>("\"0 9 * * mon\"").unwrap(), expr);
+        assert!(serde_json::from_str::<CronExpr>("\"99 * * * *\"").is_err());
+    }
+}
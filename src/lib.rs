@@ -2,6 +2,7 @@ pub mod app;
 pub mod context;
 pub mod client;
 pub mod config;
+pub mod cron_expr;
 pub mod ui;
 
 // #[cfg(not(target_arch = "wasm32"))]
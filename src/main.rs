@@ -46,11 +46,19 @@ async fn main() -> std::io::Result<()> {
     use leptos::prelude::*;
     use leptos::config::get_configuration;
     use leptos_meta::MetaTags;
-    use leptos_actix::{generate_route_list, LeptosRoutes, handle_server_fns};
+    use leptos_actix::{generate_route_list, LeptosRoutes};
     use ::cron_jobs::app::*;
+    use ::cron_jobs::server::middleware::{
+        auth_rate_limit_middleware, csrf_middleware, deadline_middleware, init_logging,
+        trace_middleware, CsrfConfig,
+    };
+    use ::cron_jobs::server::routes::{configure_api, configure_routes};
     use ::cron_jobs::server::turso::{TursoClient, TursoConfig};
+    use actix_web::middleware::{from_fn, Condition};
     use std::sync::Arc;
 
+    init_logging();
+
     let conf = get_configuration(None).unwrap();
     let addr = conf.leptos_options.site_addr;
 
@@ -65,12 +73,23 @@ async fn main() -> std::io::Result<()> {
     );
 
     // Health check
-    if let Err(e) = turso_client.health_check().await {
-        eprintln!("Warning: Registry database health check failed: {}", e);
+    let report = turso_client.health_check().await;
+    for (name, component) in [("Registry database", &report.registry), ("Turso API", &report.turso_api)] {
+        if let Some(e) = &component.error {
+            eprintln!("Warning: {} health check failed: {}", name, e);
+        }
     }
 
+    #[cfg(feature = "scheduler")]
+    ::cron_jobs::server::scheduler::Scheduler::new(turso_client.clone()).spawn();
+
     let turso_client_data = web::Data::from(turso_client.clone());
 
+    let csrf_config = CsrfConfig::from_env();
+    if !csrf_config.enabled {
+        println!("CSRF protection disabled (set CSRF_PROTECTION=true to enable)");
+    }
+
     println!("listening on http://{}", &addr);
 
     HttpServer::new(move || {
@@ -83,8 +102,10 @@ async fn main() -> std::io::Result<()> {
             // Add TursoClient to app data - MUST be before routes
             .app_data(turso_client_data.clone())
             .app_data(web::Data::new(leptos_options.to_owned()))
-            // Register server function handler with .route() instead of .service()
-            .route("/api/{tail:.*}", handle_server_fns())
+            // Server functions under /api, with a JSON 404 for unknown ones
+            .configure(configure_api)
+            // Liveness and readiness probes for container orchestrators, and /health
+            .configure(configure_routes)
             // serve JS/WASM/CSS from `pkg`
             .service(Files::new("/pkg", format!("{site_root}/pkg")))
             // serve other assets from the `assets` directory
@@ -100,6 +121,9 @@ async fn main() -> std::io::Result<()> {
                         .unwrap_or_else(|_| "https://your-project.supabase.co".to_string());
                     let supabase_anon_key = std::env::var("VITE_SUPABASE_ANON_KEY")
                         .unwrap_or_else(|_| "your-anon-key".to_string());
+                    let oauth_providers = ::cron_jobs::config::get_oauth_providers().join(",");
+                    let redirect_origins = ::cron_jobs::config::get_oauth_redirect_origins().join(",");
+                    let expiry_buffer = ::cron_jobs::config::get_session_expiry_buffer_secs();
                     
                     view! {
                         <!DOCTYPE html>
@@ -115,11 +139,17 @@ async fn main() -> std::io::Result<()> {
                                         r#"
                                         window.__ENV__ = {{
                                             VITE_SUPABASE_URL: "{}",
-                                            VITE_SUPABASE_ANON_KEY: "{}"
+                                            VITE_SUPABASE_ANON_KEY: "{}",
+                                            VITE_SUPABASE_OAUTH_PROVIDERS: "{}",
+                                            VITE_OAUTH_REDIRECT_ORIGINS: "{}",
+                                            VITE_SESSION_EXPIRY_BUFFER_SECS: "{}"
                                         }};
                                         "#,
                                         supabase_url.replace('"', "\\\""),
-                                        supabase_anon_key.replace('"', "\\\"")
+                                        supabase_anon_key.replace('"', "\\\""),
+                                        oauth_providers,
+                                        redirect_origins.replace('"', "\\\""),
+                                        expiry_buffer
                                     )}
                                 </script>
                             </head>
@@ -130,6 +160,14 @@ async fn main() -> std::io::Result<()> {
                     }
                 }
             })
+            // Double-submit CSRF check for mutating server functions
+            .wrap(Condition::new(csrf_config.enabled, from_fn(csrf_middleware)))
+            // Answer 429 to sign-in server functions after repeated failed attempts
+            .wrap(from_fn(auth_rate_limit_middleware))
+            // Time budget for the Turso, Supabase and outbound calls a request makes
+            .wrap(from_fn(deadline_middleware))
+            // Outermost, so requests rejected by the middleware above still get a trace id
+            .wrap(from_fn(trace_middleware))
         //.wrap(middleware::Compress::default())
     })
     .bind(&addr)?
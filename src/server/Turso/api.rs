@@ -0,0 +1,679 @@
+use crate::server::error::ServerError;
+use crate::server::middleware::within_deadline;
+use crate::server::turso::config::TursoConfig;
+use futures::future::BoxFuture;
+use libsql::{Builder, Database};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+#[cfg(feature = "testing")]
+use crate::server::turso::row::get_i64;
+#[cfg(feature = "testing")]
+use libsql::Connection;
+#[cfg(feature = "testing")]
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Longest wait to establish a connection to the Turso API
+const API_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest a whole Turso API request may take
+const API_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Calls a [`TursoClient`](super::TursoClient) makes to create, open and delete user databases
+///
+/// [`TursoHttpApi`] is the Turso Platform API. Tests use `InMemoryTursoApi`, which keeps
+/// databases in memory, so provisioning goes through the same steps with or without Turso.
+pub trait TursoApi: Send + Sync {
+    /// Create `db_name` in the group for `region` and return its URL. Creating a database
+    /// that already exists returns its URL, so this is safe to repeat.
+    fn create_database<'a>(&'a self, db_name: &'a str, region: Option<&'a str>) -> BoxFuture<'a, Result<String, ServerError>>;
+
+    /// Create a full-access token for `db_name`
+    fn create_database_token<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<String, ServerError>>;
+
+    /// Delete `db_name`, treating a missing database as deleted
+    fn delete_database<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<(), ServerError>>;
+
+    /// Primary region of `db_name` (e.g. `iad`)
+    fn database_region<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<Option<String>, ServerError>>;
+
+    /// Size of `db_name` in bytes
+    fn storage_usage<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<u64, ServerError>>;
+
+    /// Check that the API answers and accepts our credentials
+    fn check(&self) -> BoxFuture<'_, Result<(), ServerError>>;
+
+    /// Open the database at `url` with `token`
+    fn open_database<'a>(&'a self, url: &'a str, token: &'a str) -> BoxFuture<'a, Result<Database, ServerError>>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TursoCreateDbResponse {
+    database: TursoDatabaseInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TursoDatabaseInfo {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Hostname")]
+    hostname: String,
+    #[serde(rename = "primaryRegion")]
+    primary_region: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TursoTokenResponse {
+    jwt: String,
+}
+
+/// Send a request, retrying up to `retries` times on 429, 5xx and network errors.
+///
+/// Waits `backoff` before the first retry and doubles it for each further one. The
+/// final response is returned whatever its status, so callers keep their own handling
+/// of error bodies. Requests whose body can't be cloned are sent once.
+async fn send_with_retry(
+    request: RequestBuilder,
+    retries: u32,
+    backoff: Duration,
+) -> Result<Response, reqwest::Error> {
+    let mut attempt = 0;
+    loop {
+        let retry = match request.try_clone() {
+            Some(retry) if attempt < retries => retry,
+            _ => return request.send().await,
+        };
+
+        match retry.send().await {
+            Ok(response)
+                if !(response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || response.status().is_server_error()) =>
+            {
+                return Ok(response)
+            }
+            Ok(response) => {
+                log::warn!(
+                    "[Turso API] {} returned {}, retrying",
+                    response.url(),
+                    response.status()
+                );
+            }
+            Err(e) => log::warn!("[Turso API] Request failed, retrying: {}", e),
+        }
+
+        actix_web::rt::time::sleep(backoff.saturating_mul(2u32.saturating_pow(attempt))).await;
+        attempt += 1;
+    }
+}
+
+/// [`TursoApi`] backed by the Turso Platform API
+pub struct TursoHttpApi {
+    http_client: Client,
+    config: Arc<TursoConfig>,
+    /// Groups known to exist, so each is looked up once
+    ready_groups: Mutex<HashSet<String>>,
+}
+
+impl TursoHttpApi {
+    pub fn new(config: Arc<TursoConfig>) -> Result<Self, ServerError> {
+        let http_client = Client::builder()
+            .connect_timeout(API_CONNECT_TIMEOUT)
+            .timeout(API_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ServerError::TursoApi(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            config,
+            ready_groups: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Delete a database via the Turso API, treating a missing database as deleted
+    async fn delete_database_via_api(&self, db_name: &str) -> Result<(), ServerError> {
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/databases/{}",
+            self.config.turso_org, db_name
+        );
+
+        let request = self
+            .http_client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token));
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to delete database: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ServerError::TursoApi(format!("Failed to delete database: {}", error_text)));
+        }
+
+        Ok(())
+    }
+
+    /// Make sure `group` exists, creating it at `location` when `TURSO_AUTOCREATE_GROUP`
+    /// is set. Without auto-creation the group is assumed to exist.
+    async fn ensure_group(&self, group: &str, location: Option<&str>) -> Result<(), ServerError> {
+        let ready = self
+            .ready_groups
+            .lock()
+            .map(|groups| groups.contains(group))
+            .unwrap_or(false);
+        if !self.config.autocreate_group || ready {
+            return Ok(());
+        }
+
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/groups/{}",
+            self.config.turso_org, group
+        );
+
+        let request = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token));
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to look up group '{}': {}", group, e)))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            self.create_group_via_api(group, location).await?;
+        } else if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ServerError::TursoApi(format!("Failed to look up group '{}': {}", group, error_text)));
+        }
+
+        if let Ok(mut groups) = self.ready_groups.lock() {
+            groups.insert(group.to_string());
+        }
+        Ok(())
+    }
+
+    /// Create a group via Turso API. A group created concurrently by another
+    /// provisioning request counts as success.
+    async fn create_group_via_api(&self, group: &str, location: Option<&str>) -> Result<(), ServerError> {
+        let location = location.ok_or_else(|| {
+            ServerError::TursoApi(format!(
+                "Group '{}' does not exist and TURSO_GROUP_LOCATION is not set to create it",
+                group
+            ))
+        })?;
+
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/groups",
+            self.config.turso_org
+        );
+
+        let mut payload = HashMap::new();
+        payload.insert("name", group);
+        payload.insert("location", location);
+
+        let request = self
+            .http_client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
+            .json(&payload);
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to send group creation request: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+
+            if status == reqwest::StatusCode::CONFLICT || error_text.contains("already exists") {
+                return Ok(());
+            }
+
+            return Err(ServerError::TursoApi(format!("Failed to create group '{}': {}", group, error_text)));
+        }
+
+        log::info!("[Provisioning] Created Turso group '{}' in {}", group, location);
+        Ok(())
+    }
+
+    /// Create database via Turso API, in the group for `region`
+    async fn create_database_via_api(
+        &self,
+        db_name: &str,
+        region: Option<&str>,
+    ) -> Result<TursoDatabaseInfo, ServerError> {
+        let group = self.config.group_for_region(region);
+        let location = region.or(self.config.turso_group_location.as_deref());
+        self.ensure_group(&group, location).await?;
+
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/databases",
+            self.config.turso_org
+        );
+
+        let mut payload = HashMap::new();
+        payload.insert("name", db_name);
+        payload.insert("group", group.as_str());
+
+        let request = self
+            .http_client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
+            .json(&payload);
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to send database creation request: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_default();
+
+            // Check if the error is because database already exists
+            if error_text.contains("already exists") {
+                return self.get_existing_database_info(db_name).await;
+            }
+
+            return Err(ServerError::TursoApi(format!("Failed to create database: {}", error_text)));
+        }
+
+        let create_response: TursoCreateDbResponse = response
+            .json()
+            .await
+            .map_err(|e| ServerError::TursoApi(format!("Failed to parse database creation response: {}", e)))?;
+
+        Ok(create_response.database)
+    }
+
+    /// Get existing database info from Turso API
+    async fn get_existing_database_info(&self, db_name: &str) -> Result<TursoDatabaseInfo, ServerError> {
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/databases/{}",
+            self.config.turso_org, db_name
+        );
+
+        let request = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token));
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to get existing database info: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ServerError::TursoApi(format!("Failed to get existing database info: {}", error_text)));
+        }
+
+        #[derive(Deserialize)]
+        struct GetDbResponse {
+            database: TursoDatabaseInfo,
+        }
+
+        let db_response: GetDbResponse = response
+            .json()
+            .await
+            .map_err(|e| ServerError::TursoApi(format!("Failed to parse existing database response: {}", e)))?;
+
+        Ok(db_response.database)
+    }
+
+    /// Create a database token for the given database
+    async fn create_token_via_api(&self, db_name: &str) -> Result<String, ServerError> {
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/databases/{}/auth/tokens",
+            self.config.turso_org, db_name
+        );
+
+        let mut payload = HashMap::new();
+        payload.insert("expiration", "never");
+        payload.insert("authorization", "full-access");
+
+        let request = self
+            .http_client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
+            .json(&payload);
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to create database token: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ServerError::TursoApi(format!("Failed to create database token: {}", error_text)));
+        }
+
+        let token_response: TursoTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| ServerError::TursoApi(format!("Failed to parse token response: {}", e)))?;
+
+        Ok(token_response.jwt)
+    }
+
+    /// Primary region of a database (e.g. `iad`) from the Turso API
+    async fn get_database_region(&self, db_name: &str) -> Result<Option<String>, ServerError> {
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/databases/{}",
+            self.config.turso_org, db_name
+        );
+
+        let request = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token));
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to get database: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ServerError::TursoApi(format!("Failed to get database: {}", error_text)));
+        }
+
+        #[derive(Deserialize)]
+        struct DatabaseResponse {
+            database: DatabaseRegion,
+        }
+
+        #[derive(Deserialize)]
+        struct DatabaseRegion {
+            #[serde(rename = "primaryRegion")]
+            primary_region: Option<String>,
+        }
+
+        let database: DatabaseResponse = response
+            .json()
+            .await
+            .map_err(|e| ServerError::TursoApi(format!("Failed to parse database response: {}", e)))?;
+        Ok(database.database.primary_region)
+    }
+
+    /// Size in bytes of `db_name` from the Turso usage API
+    async fn fetch_storage_usage(&self, db_name: &str) -> Result<u64, ServerError> {
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/databases/{}/usage",
+            self.config.turso_org, db_name
+        );
+
+        let request = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token));
+        let response = within_deadline(
+            "Turso API request",
+            send_with_retry(request, self.config.api_max_retries, self.config.api_retry_backoff),
+        )
+        .await?
+        .map_err(|e| ServerError::TursoApi(format!("Failed to get database usage: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ServerError::TursoApi(format!("Failed to get database usage: {}", error_text)));
+        }
+
+        #[derive(Deserialize)]
+        struct UsageResponse {
+            database: DatabaseUsage,
+        }
+
+        #[derive(Deserialize)]
+        struct DatabaseUsage {
+            usage: UsageTotals,
+        }
+
+        #[derive(Deserialize)]
+        struct UsageTotals {
+            storage_bytes: u64,
+        }
+
+        let usage: UsageResponse = response
+            .json()
+            .await
+            .map_err(|e| ServerError::TursoApi(format!("Failed to parse database usage response: {}", e)))?;
+        Ok(usage.database.usage.storage_bytes)
+    }
+
+    /// Check that the Turso API accepts our token, by listing the organization's groups.
+    /// Sent once without retries so a probe fails fast.
+    async fn check_turso_api(&self) -> Result<(), ServerError> {
+        let url = format!(
+            "https://api.turso.tech/v1/organizations/{}/groups",
+            self.config.turso_org
+        );
+        let response = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
+            .send()
+            .await
+            .map_err(|e| ServerError::TursoApi(format!("Turso API health check failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ServerError::TursoApi(format!(
+                "Turso API health check returned {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl TursoApi for TursoHttpApi {
+    fn create_database<'a>(&'a self, db_name: &'a str, region: Option<&'a str>) -> BoxFuture<'a, Result<String, ServerError>> {
+        Box::pin(async move {
+            let db_info = self.create_database_via_api(db_name, region).await?;
+            Ok(format!("libsql://{}", db_info.hostname))
+        })
+    }
+
+    fn create_database_token<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<String, ServerError>> {
+        Box::pin(self.create_token_via_api(db_name))
+    }
+
+    fn delete_database<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<(), ServerError>> {
+        Box::pin(self.delete_database_via_api(db_name))
+    }
+
+    fn database_region<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<Option<String>, ServerError>> {
+        Box::pin(self.get_database_region(db_name))
+    }
+
+    fn storage_usage<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<u64, ServerError>> {
+        Box::pin(self.fetch_storage_usage(db_name))
+    }
+
+    fn check(&self) -> BoxFuture<'_, Result<(), ServerError>> {
+        Box::pin(self.check_turso_api())
+    }
+
+    fn open_database<'a>(&'a self, url: &'a str, token: &'a str) -> BoxFuture<'a, Result<Database, ServerError>> {
+        Box::pin(async move {
+            Builder::new_remote(url.to_string(), token.to_string())
+                .build()
+                .await
+                .map_err(|e| ServerError::Database(format!("Failed to connect to user database: {}", e)))
+        })
+    }
+}
+
+/// [`TursoApi`] that keeps every database in memory, for tests without credentials or network
+///
+/// Databases are shared-cache in-memory SQLite databases. Token creation can be made to
+/// fail, to interrupt provisioning half-way.
+#[cfg(feature = "testing")]
+pub struct InMemoryTursoApi {
+    /// Unique per API, so clients of parallel tests don't share databases
+    namespace: String,
+    /// One open connection per database; a shared in-memory database is dropped when its
+    /// last connection closes
+    keepalive: Mutex<HashMap<String, Connection>>,
+    /// Region each existing database was created for, by name
+    regions: Mutex<HashMap<String, Option<String>>>,
+    /// Names `create_database` was called with, in order
+    create_calls: Mutex<Vec<String>>,
+    /// Token requests still to fail
+    token_failures: AtomicU32,
+}
+
+#[cfg(feature = "testing")]
+impl Default for InMemoryTursoApi {
+    fn default() -> Self {
+        Self {
+            namespace: uuid::Uuid::new_v4().simple().to_string(),
+            keepalive: Mutex::new(HashMap::new()),
+            regions: Mutex::new(HashMap::new()),
+            create_calls: Mutex::new(Vec::new()),
+            token_failures: AtomicU32::new(0),
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl InMemoryTursoApi {
+    /// Make the next `times` token requests fail, as if the Turso API were down
+    pub fn fail_token_creation(&self, times: u32) {
+        self.token_failures.store(times, Ordering::SeqCst);
+    }
+
+    /// Names `create_database` was called with, in order
+    pub fn create_calls(&self) -> Vec<String> {
+        self.create_calls.lock().map(|calls| calls.clone()).unwrap_or_default()
+    }
+
+    /// Open the in-memory registry database
+    pub(crate) async fn open_registry(&self) -> Result<Database, ServerError> {
+        self.open(&self.url("registry")).await
+    }
+
+    /// URL of the in-memory database called `name`
+    fn url(&self, name: &str) -> String {
+        format!("file:{}-{}?mode=memory&cache=shared", self.namespace, name)
+    }
+    async fn open(&self, url: &str) -> Result<Database, ServerError> {
+        let database = Builder::new_local(url)
+            .build()
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to open in-memory database: {}", e)))?;
+
+        let mut keepalive = self
+            .keepalive
+            .lock()
+            .map_err(|_| ServerError::Database("In-memory database store is poisoned".to_string()))?;
+        if !keepalive.contains_key(url) {
+            let conn = database
+                .connect()
+                .map_err(|e| ServerError::Database(format!("Failed to connect to in-memory database: {}", e)))?;
+            keepalive.insert(url.to_string(), conn);
+        }
+        Ok(database)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl TursoApi for InMemoryTursoApi {
+    fn create_database<'a>(&'a self, db_name: &'a str, region: Option<&'a str>) -> BoxFuture<'a, Result<String, ServerError>> {
+        Box::pin(async move {
+            if let Ok(mut calls) = self.create_calls.lock() {
+                calls.push(db_name.to_string());
+            }
+            let url = self.url(db_name);
+            self.open(&url).await?;
+            if let Ok(mut regions) = self.regions.lock() {
+                regions.entry(db_name.to_string()).or_insert(region.map(str::to_string));
+            }
+            Ok(url)
+        })
+    }
+
+    fn create_database_token<'a>(&'a self, _db_name: &'a str) -> BoxFuture<'a, Result<String, ServerError>> {
+        Box::pin(async move {
+            let failing = self
+                .token_failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1))
+                .is_ok();
+            if failing {
+                return Err(ServerError::TursoApi("Failed to create database token: service unavailable".to_string()));
+            }
+            // In-memory databases don't check tokens
+            Ok(String::new())
+        })
+    }
+
+    fn delete_database<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<(), ServerError>> {
+        Box::pin(async move {
+            if let Ok(mut regions) = self.regions.lock() {
+                regions.remove(db_name);
+            }
+            // Let the database be dropped once nothing uses it
+            if let Ok(mut keepalive) = self.keepalive.lock() {
+                keepalive.remove(&self.url(db_name));
+            }
+            Ok(())
+        })
+    }
+
+    fn database_region<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<Option<String>, ServerError>> {
+        Box::pin(async move {
+            self.regions
+                .lock()
+                .ok()
+                .and_then(|regions| regions.get(db_name).cloned())
+                .ok_or_else(|| ServerError::TursoApi(format!("Failed to get database: {} does not exist", db_name)))
+        })
+    }
+
+    fn storage_usage<'a>(&'a self, db_name: &'a str) -> BoxFuture<'a, Result<u64, ServerError>> {
+        Box::pin(async move {
+            let conn = self
+                .open(&self.url(db_name))
+                .await?
+                .connect()
+                .map_err(|e| ServerError::Database(format!("Failed to connect to in-memory database: {}", e)))?;
+            let mut rows = conn
+                .query(
+                    "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                    libsql::params![],
+                )
+                .await
+                .map_err(|e| ServerError::Database(format!("Failed to measure database size: {}", e)))?;
+            let row = rows
+                .next()
+                .await
+                .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+                .ok_or_else(|| ServerError::Database("Database size query returned no rows".to_string()))?;
+            let bytes = get_i64(&row, 0, "size").map_err(ServerError::Database)?;
+            Ok(bytes.max(0) as u64)
+        })
+    }
+
+    fn check(&self) -> BoxFuture<'_, Result<(), ServerError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn open_database<'a>(&'a self, url: &'a str, _token: &'a str) -> BoxFuture<'a, Result<Database, ServerError>> {
+        Box::pin(self.open(url))
+    }
+}
@@ -1,51 +1,262 @@
-use crate::server::turso::config::TursoConfig;
+use crate::server::error::ServerError;
+use crate::server::middleware::within_deadline;
+#[cfg(feature = "testing")]
+use crate::server::turso::api::InMemoryTursoApi;
+use crate::server::turso::api::{TursoApi, TursoHttpApi};
+use crate::server::turso::config::{validate_region, SupabaseConfig, TursoConfig};
+use crate::server::turso::crypto::EncryptionKey;
+use crate::server::turso::row::{get_optional_i64, get_timestamp_text, sqlite_timestamp};
 use crate::server::turso::schema;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
 use libsql::{Builder, Connection, Database};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Column names of user tables, keyed by `(user_id, table)`
+type ColumnCache = HashMap<(String, String), Arc<HashSet<String>>>;
+
+/// An opened user database and when it was opened
+struct CachedDatabase {
+    database: Arc<Database>,
+    opened_at: Instant,
+}
+
+/// A storage usage figure and when it was measured
+struct CachedUsage {
+    storage_bytes: u64,
+    measured_at: Instant,
+}
 
 pub struct TursoClient {
     registry_db: Database,
-    http_client: Client,
+    /// Creates, opens and deletes user databases
+    api: Arc<dyn TursoApi>,
     config: Arc<TursoConfig>,
+    /// Cleared for a user whenever their schema is synchronized
+    column_cache: Mutex<ColumnCache>,
+    /// User database handles keyed by user id, reused until `user_db_cache_ttl` expires
+    database_cache: Mutex<HashMap<String, CachedDatabase>>,
+    /// Storage usage keyed by user id, reused until `storage_usage_ttl` expires
+    usage_cache: Mutex<HashMap<String, CachedUsage>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Registry entry of a user database, including its credentials. Server-internal: API
+/// responses use [`UserDatabaseSummary`] or `DatabaseInfo`.
+///
+/// The token is never serialized and is redacted from `{:?}`, so it can't leak through a
+/// response or a log line even if an entry ends up in one:
+///
+/// ```
+/// use cron_jobs::server::turso::{ProvisionStatus, UserDatabaseEntry};
+///
+/// let entry = UserDatabaseEntry {
+///     user_id: "user-1".to_string(),
+///     email: "user@example.com".to_string(),
+///     db_name: "user-1".to_string(),
+///     db_url: "libsql://user-1-org.turso.io".to_string(),
+///     db_token: "secret-db-token".to_string(),
+///     storage_used_bytes: Some(0),
+///     provision_status: ProvisionStatus::Ready,
+///     created_at: "2025-01-01T00:00:00Z".to_string(),
+///     updated_at: "2025-01-01T00:00:00Z".to_string(),
+/// };
+/// assert!(!serde_json::to_string(&entry).unwrap().contains("secret-db-token"));
+/// assert!(!format!("{:?}", entry).contains("secret-db-token"));
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
 pub struct UserDatabaseEntry {
     pub user_id: String,
     pub email: String,
     pub db_name: String,
     pub db_url: String,
+    #[serde(skip_serializing, default)]
     pub db_token: String,
     pub storage_used_bytes: Option<i64>,
+    pub provision_status: ProvisionStatus,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl fmt::Debug for UserDatabaseEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserDatabaseEntry")
+            .field("user_id", &self.user_id)
+            .field("email", &self.email)
+            .field("db_name", &self.db_name)
+            .field("db_url", &self.db_url)
+            .field("db_token", &"<redacted>")
+            .field("storage_used_bytes", &self.storage_used_bytes)
+            .field("provision_status", &self.provision_status)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .finish()
+    }
+}
+
+/// Provisioning progress of a user database, stored in the registry
+///
+/// Each step is recorded once it completes, so an interrupted provisioning run can be
+/// resumed from the last completed step instead of starting over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvisionStatus {
+    /// Registry entry reserved, database not yet created
+    Pending,
+    /// Database exists in Turso, no auth token yet
+    DatabaseCreated,
+    /// Auth token issued, schema not yet initialized
+    TokenCreated,
+    /// Schema initialized, database ready for use
+    Ready,
+}
+
+impl ProvisionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProvisionStatus::Pending => "pending",
+            ProvisionStatus::DatabaseCreated => "database_created",
+            ProvisionStatus::TokenCreated => "token_created",
+            ProvisionStatus::Ready => "ready",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "pending" => Ok(ProvisionStatus::Pending),
+            "database_created" => Ok(ProvisionStatus::DatabaseCreated),
+            "token_created" => Ok(ProvisionStatus::TokenCreated),
+            "ready" => Ok(ProvisionStatus::Ready),
+            other => Err(format!("Unknown provision status: {}", other)),
+        }
+    }
+}
+
+/// Filter for listing user databases in the registry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserDatabaseFilter {
+    pub provision_status: Option<ProvisionStatus>,
+    /// Only databases created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only databases created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Maximum number of entries to return (`None` for all)
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+/// Registry entry without the database credentials, safe to show to staff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserDatabaseSummary {
+    pub user_id: String,
+    pub email: String,
+    pub db_name: String,
+    pub storage_used_bytes: Option<i64>,
+    pub provision_status: ProvisionStatus,
     pub created_at: String,
     pub updated_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct TursoCreateDbResponse {
-    database: TursoDatabaseInfo,
+impl From<UserDatabaseEntry> for UserDatabaseSummary {
+    fn from(entry: UserDatabaseEntry) -> Self {
+        UserDatabaseSummary {
+            user_id: entry.user_id,
+            email: entry.email,
+            db_name: entry.db_name,
+            storage_used_bytes: entry.storage_used_bytes,
+            provision_status: entry.provision_status,
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+        }
+    }
+}
+
+/// Outcome of probing one dependency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub ok: bool,
+    /// How long the probe took
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+impl ComponentHealth {
+    fn from_result(result: Result<(), ServerError>, started: Instant) -> Self {
+        ComponentHealth {
+            ok: result.is_ok(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct TursoDatabaseInfo {
-    #[serde(rename = "Name")]
-    name: String,
-    #[serde(rename = "Hostname")]
-    hostname: String,
-    #[serde(rename = "primaryRegion")]
-    primary_region: String,
+/// Reachability of the services the server depends on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// Registry database; every authenticated request needs it
+    pub registry: ComponentHealth,
+    /// Turso Platform API; only needed to provision and delete user databases
+    pub turso_api: ComponentHealth,
 }
 
-#[derive(Debug, Deserialize)]
-struct TursoTokenResponse {
-    jwt: String,
+impl HealthStatus {
+    /// Whether every dependency answered
+    pub fn is_healthy(&self) -> bool {
+        self.registry.ok && self.turso_api.ok
+    }
+}
+
+/// Build a `UserDatabaseEntry` from a row selected as `user_id, email, db_name, db_url,
+/// db_token, storage_used_bytes, provision_status, created_at, updated_at`
+fn user_database_entry_from_row(row: &libsql::Row) -> Result<UserDatabaseEntry, ServerError> {
+    Ok(UserDatabaseEntry {
+        user_id: row.get(0).map_err(|e| ServerError::Database(format!("Failed to get user_id: {}", e)))?,
+        email: row.get(1).map_err(|e| ServerError::Database(format!("Failed to get email: {}", e)))?,
+        db_name: row.get(2).map_err(|e| ServerError::Database(format!("Failed to get db_name: {}", e)))?,
+        db_url: row.get(3).map_err(|e| ServerError::Database(format!("Failed to get db_url: {}", e)))?,
+        db_token: row.get(4).map_err(|e| ServerError::Database(format!("Failed to get db_token: {}", e)))?,
+        storage_used_bytes: get_optional_i64(row, 5, "storage_used_bytes").map_err(ServerError::Database)?,
+        provision_status: ProvisionStatus::parse(
+            &row.get::<String>(6).map_err(|e| ServerError::Database(format!("Failed to get provision_status: {}", e)))?,
+        ).map_err(ServerError::Database)?,
+        created_at: get_timestamp_text(row, 7, "created_at").map_err(ServerError::Database)?,
+        updated_at: get_timestamp_text(row, 8, "updated_at").map_err(ServerError::Database)?,
+    })
+}
+
+/// Extra attempts for a registry read that failed, so a momentary registry blip doesn't
+/// fail the request. Kept small because it sits on every request's path.
+const REGISTRY_READ_RETRIES: u32 = 2;
+
+/// Delay before the first registry retry; doubled for the next
+const REGISTRY_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Run a registry read, retrying it on database errors up to [`REGISTRY_READ_RETRIES`]
+/// times. Other errors, such as `NotFound`, are returned at once.
+async fn retry_registry_read<T, F, Fut>(mut read: F) -> Result<T, ServerError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ServerError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match within_deadline("Registry read", read()).await.and_then(|result| result) {
+            Err(ServerError::Database(e)) if attempt < REGISTRY_READ_RETRIES => {
+                log::warn!("[Registry] Read failed, retrying: {}", e);
+                actix_web::rt::time::sleep(REGISTRY_RETRY_BACKOFF * 2u32.pow(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
 }
 
 impl TursoClient {
-    pub async fn new(config: TursoConfig) -> Result<Self, String> {
+    pub async fn new(config: TursoConfig) -> Result<Self, ServerError> {
         // Connect to the central registry database
         let registry_db = Builder::new_remote(
             config.registry_db_url.clone(),
@@ -53,19 +264,81 @@ impl TursoClient {
         )
         .build()
         .await
-        .map_err(|e| format!("Failed to connect to registry database: {}", e))?;
+        .map_err(|e| ServerError::Database(format!("Failed to connect to registry database: {}", e)))?;
 
-        let http_client = Client::new();
+        let config = Arc::new(config);
+        let api = Arc::new(TursoHttpApi::new(config.clone())?);
+        Self::with_registry(registry_db, config, api).await
+    }
+
+    /// Client backed by in-memory databases instead of Turso, for end-to-end tests of the
+    /// service and action layers without credentials or network.
+    ///
+    /// Turso API calls go to an [`InMemoryTursoApi`], which creates a local database per
+    /// user; provisioning, the registry and everything else behave as with Turso.
+    ///
+    /// ```
+    /// use cron_jobs::server::models::{CreateCronJobRequest, ListCronJobsFilter, Pagination};
+    /// use cron_jobs::server::service::{
+    ///     create_cron_job, create_user_database, delete_cron_job, get_user_cron_jobs,
+    /// };
+    /// use cron_jobs::server::turso::TursoClient;
+    ///
+    /// actix_web::rt::System::new().block_on(async {
+    ///     let client = TursoClient::new_in_memory().await.unwrap();
+    ///     create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    ///
+    ///     let request = CreateCronJobRequest::builder()
+    ///         .name("Nightly backup")
+    ///         .schedule("0 3 * * *")
+    ///         .http("POST", "https://example.com/backup")
+    ///         .build()
+    ///         .unwrap();
+    ///     let job = create_cron_job(&client, "user-1", request).await.unwrap();
+    ///
+    ///     let list = |client| get_user_cron_jobs(client, "user-1", ListCronJobsFilter::default(), Pagination::default());
+    ///     let page = list(&client).await.unwrap();
+    ///     assert_eq!(page.total, 1);
+    ///     assert_eq!(page.jobs[0].id, job.id);
+    ///
+    ///     delete_cron_job(&client, "user-1", &job.id).await.unwrap();
+    ///     assert_eq!(list(&client).await.unwrap().total, 0);
+    /// });
+    /// ```
+    #[cfg(feature = "testing")]
+    pub async fn new_in_memory() -> Result<Self, ServerError> {
+        Self::new_in_memory_with_config(TursoConfig::for_testing()).await
+    }
+
+    /// In-memory client with settings other than [`TursoConfig::for_testing`], e.g. a
+    /// database name prefix. Credentials in `config` are ignored.
+    #[cfg(feature = "testing")]
+    pub async fn new_in_memory_with_config(config: TursoConfig) -> Result<Self, ServerError> {
+        Self::new_in_memory_with_api(config, Arc::new(InMemoryTursoApi::default())).await
+    }
+
+    /// In-memory client using `api`, so a test can inspect its calls or make them fail
+    #[cfg(feature = "testing")]
+    pub async fn new_in_memory_with_api(config: TursoConfig, api: Arc<InMemoryTursoApi>) -> Result<Self, ServerError> {
+        let registry_db = api.open_registry().await?;
+        Self::with_registry(registry_db, Arc::new(config), api).await
+    }
 
+    /// Finish building a client around an opened registry, migrating its schema
+    async fn with_registry(
+        registry_db: Database,
+        config: Arc<TursoConfig>,
+        api: Arc<dyn TursoApi>,
+    ) -> Result<Self, ServerError> {
         // Run registry database migration
         let conn = registry_db
             .connect()
-            .map_err(|e| format!("Failed to get registry database connection for migration: {}", e))?;
+            .map_err(|e| ServerError::Database(format!("Failed to get registry database connection for migration: {}", e)))?;
         
         // Initialize registry schema
         schema::initialize_registry_schema(&conn)
             .await
-            .map_err(|e| format!("Failed to initialize registry schema: {}", e))?;
+            .map_err(|e| ServerError::Database(format!("Failed to initialize registry schema: {}", e)))?;
         
         // Add storage_used_bytes column if it doesn't exist (migration)
         conn.execute(
@@ -73,182 +346,147 @@ impl TursoClient {
             libsql::params![],
         ).await.ok(); // Ignore error if column already exists
 
-        let config = Arc::new(config);
+        // Add provision_status column if it doesn't exist (migration).
+        // Rows created before provisioning was tracked were fully provisioned.
+        conn.execute(
+            "ALTER TABLE user_databases ADD COLUMN provision_status TEXT NOT NULL DEFAULT 'ready'",
+            libsql::params![],
+        ).await.ok(); // Ignore error if column already exists
 
         Ok(Self {
             registry_db,
-            http_client,
+            api,
             config,
+            column_cache: Mutex::new(HashMap::new()),
+            database_cache: Mutex::new(HashMap::new()),
+            usage_cache: Mutex::new(HashMap::new()),
         })
     }
 
     /// Get a connection to the registry database
-    pub async fn get_registry_connection(&self) -> Result<Connection, String> {
+    pub async fn get_registry_connection(&self) -> Result<Connection, ServerError> {
         self.registry_db
             .connect()
-            .map_err(|e| format!("Failed to get registry database connection: {}", e))
+            .map_err(|e| ServerError::Database(format!("Failed to get registry database connection: {}", e)))
     }
 
-    /// Create a new user database in Turso
+    /// Create a new user database in Turso, resuming an interrupted attempt if needed.
+    ///
+    /// Provisioning is keyed on the user id: every completed step is written to the
+    /// registry before the next one starts, so calling this again after the caller was
+    /// dropped mid-way continues from the last recorded `provision_status`.
+    ///
+    /// `region` overrides `TURSO_REGION` for this database; it only matters until the
+    /// database has been created.
     pub async fn create_user_database(
         &self,
         user_id: &str,
         email: &str,
-    ) -> Result<UserDatabaseEntry, String> {
-        // Sanitize user_id for Turso requirements (numbers, lowercase letters, and dashes only)
-        let sanitized_id = user_id
-            .to_lowercase()
-            .replace("_", "-");
-        let db_name = format!("user-{}", sanitized_id);
-
-        // Create database via Turso API
-        let db_info = self.create_database_via_api(&db_name).await?;
-
-        // Create auth token for the database
-        let token = self.create_database_token(&db_name).await?;
-
-        // Construct the database URL
-        let db_url = format!("libsql://{}", db_info.hostname);
-
-        // Initialize the database schema
-        schema::initialize_user_database_schema(&db_url, &token)
-            .await
-            .map_err(|e| format!("Failed to initialize user database schema: {}", e))?;
-
-        // Create user database entry
-        let user_db_entry = UserDatabaseEntry {
-            user_id: user_id.to_string(),
-            email: email.to_string(),
-            db_name: db_name.clone(),
-            db_url: db_url.clone(),
-            db_token: token,
-            storage_used_bytes: Some(0),
-            created_at: chrono::Utc::now().to_rfc3339(),
-            updated_at: chrono::Utc::now().to_rfc3339(),
+        region: Option<&str>,
+    ) -> Result<UserDatabaseEntry, ServerError> {
+        if let Some(region) = region {
+            validate_region(region).map_err(ServerError::Validation)?;
+        }
+        let region = region.or(self.config.turso_region.as_deref());
+
+        let mut entry = match self.get_user_database_entry(user_id).await {
+            Ok(entry) => entry,
+            // Any other failure must not fall through to a new entry, which would
+            // replace the stored one along with its database name and status
+            Err(ServerError::NotFound(_)) => {
+                let now = chrono::Utc::now().to_rfc3339();
+
+                let entry = UserDatabaseEntry {
+                    user_id: user_id.to_string(),
+                    email: email.to_string(),
+                    // Later steps, lookups and deletion use the stored name, so changing
+                    // TURSO_DB_PREFIX only affects databases created afterwards
+                    db_name: self.config.user_database_name(user_id),
+                    db_url: String::new(),
+                    db_token: String::new(),
+                    storage_used_bytes: Some(0),
+                    provision_status: ProvisionStatus::Pending,
+                    created_at: now.clone(),
+                    updated_at: now,
+                };
+                self.store_user_database_entry(&entry).await?;
+                entry
+            }
+            Err(e) => return Err(e),
         };
 
-        // Store in registry
-        self.store_user_database_entry(&user_db_entry).await?;
-
-        Ok(user_db_entry)
-    }
-
-    /// Create database via Turso API
-    async fn create_database_via_api(&self, db_name: &str) -> Result<TursoDatabaseInfo, String> {
-        let url = format!(
-            "https://api.turso.tech/v1/organizations/{}/databases",
-            self.config.turso_org
-        );
-
-        let mut payload = HashMap::new();
-        payload.insert("name", db_name);
-        payload.insert("group", "default");
-
-        let response = self
-            .http_client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send database creation request: {}", e))?;
-
-        if !response.status().is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_default();
-
-            // Check if the error is because database already exists
-            if error_text.contains("already exists") {
-                return self.get_existing_database_info(db_name).await;
+        loop {
+            match entry.provision_status {
+                ProvisionStatus::Pending => {
+                    // Creating an existing database returns its URL, so this is safe to repeat
+                    entry.db_url = self.api.create_database(&entry.db_name, region).await?;
+                    entry.provision_status = ProvisionStatus::DatabaseCreated;
+                }
+                ProvisionStatus::DatabaseCreated => {
+                    entry.db_token = self.api.create_database_token(&entry.db_name).await?;
+                    entry.provision_status = ProvisionStatus::TokenCreated;
+                }
+                ProvisionStatus::TokenCreated => {
+                    // Table, index and trigger creation all use IF NOT EXISTS
+                    let conn = self
+                        .api
+                        .open_database(&entry.db_url, &entry.db_token)
+                        .await?
+                        .connect()
+                        .map_err(|e| ServerError::Database(format!("Failed to connect to user database: {}", e)))?;
+                    schema::initialize_user_schema(&conn)
+                        .await
+                        .map_err(|e| ServerError::Database(format!("Failed to initialize user database schema: {}", e)))?;
+                    entry.provision_status = ProvisionStatus::Ready;
+                }
+                ProvisionStatus::Ready => return Ok(entry),
             }
 
-            return Err(format!("Failed to create database: {}", error_text));
-        }
-
-        let create_response: TursoCreateDbResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse database creation response: {}", e))?;
-
-        Ok(create_response.database)
-    }
-
-    /// Get existing database info from Turso API
-    async fn get_existing_database_info(&self, db_name: &str) -> Result<TursoDatabaseInfo, String> {
-        let url = format!(
-            "https://api.turso.tech/v1/organizations/{}/databases/{}",
-            self.config.turso_org, db_name
-        );
-
-        let response = self
-            .http_client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
-            .send()
-            .await
-            .map_err(|e| format!("Failed to get existing database info: {}", e))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Failed to get existing database info: {}", error_text));
-        }
-
-        #[derive(Deserialize)]
-        struct GetDbResponse {
-            database: TursoDatabaseInfo,
+            entry.updated_at = chrono::Utc::now().to_rfc3339();
+            self.store_user_database_entry(&entry).await?;
+            log::info!(
+                "[Provisioning] Database {} reached step '{}'",
+                entry.db_name,
+                entry.provision_status.as_str()
+            );
         }
-
-        let db_response: GetDbResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse existing database response: {}", e))?;
-
-        Ok(db_response.database)
     }
 
-    /// Create a database token for the given database
-    pub async fn create_database_token(&self, db_name: &str) -> Result<String, String> {
-        let url = format!(
-            "https://api.turso.tech/v1/organizations/{}/databases/{}/auth/tokens",
-            self.config.turso_org, db_name
-        );
-
-        let mut payload = HashMap::new();
-        payload.insert("expiration", "never");
-        payload.insert("authorization", "full-access");
-
-        let response = self
-            .http_client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.turso_api_token))
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to create database token: {}", e))?;
+    /// Delete a user's Turso database and remove it from the registry.
+    ///
+    /// Deleting a database that is already gone is not an error, so a request that
+    /// failed after the API call can simply be repeated.
+    pub async fn delete_user_database(&self, user_id: &str) -> Result<(), ServerError> {
+        let entry = match self.get_user_database_entry(user_id).await {
+            Ok(entry) => entry,
+            // Nothing registered, so there is nothing left to delete
+            Err(ServerError::NotFound(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Failed to create database token: {}", error_text));
-        }
+        self.api.delete_database(&entry.db_name).await?;
 
-        let token_response: TursoTokenResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse token response: {}", e))?;
+        let conn = self.get_registry_connection().await?;
+        conn.execute(
+            "DELETE FROM user_databases WHERE user_id = ?",
+            libsql::params![user_id],
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to remove user database entry: {}", e)))?;
 
-        Ok(token_response.jwt)
+        self.invalidate_user_database(user_id);
+        self.invalidate_table_columns(user_id);
+        Ok(())
     }
 
     /// Store user database entry in registry
-    async fn store_user_database_entry(&self, entry: &UserDatabaseEntry) -> Result<(), String> {
+    async fn store_user_database_entry(&self, entry: &UserDatabaseEntry) -> Result<(), ServerError> {
         let conn = self.get_registry_connection().await?;
 
         conn.execute(
             "INSERT OR REPLACE INTO user_databases
-             (user_id, email, db_name, db_url, db_token, storage_used_bytes, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+             (user_id, email, db_name, db_url, db_token, storage_used_bytes, provision_status, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
             libsql::params![
                 entry.user_id.as_str(),
                 entry.email.as_str(),
@@ -256,90 +494,521 @@ impl TursoClient {
                 entry.db_url.as_str(),
                 entry.db_token.as_str(),
                 entry.storage_used_bytes.unwrap_or(0),
+                entry.provision_status.as_str(),
                 entry.created_at.as_str(),
                 entry.updated_at.as_str(),
             ],
         )
         .await
-        .map_err(|e| format!("Failed to store user database entry: {}", e))?;
+        .map_err(|e| ServerError::Database(format!("Failed to store user database entry: {}", e)))?;
 
         Ok(())
     }
 
     /// Get user database entry by user ID
+    ///
+    /// A failed query is retried a couple of times after a short delay, since every request
+    /// for a user's data starts here.
     pub async fn get_user_database_entry(
         &self,
         user_id: &str,
-    ) -> Result<UserDatabaseEntry, String> {
+    ) -> Result<UserDatabaseEntry, ServerError> {
+        retry_registry_read(|| self.query_user_database_entry(user_id)).await
+    }
+
+    /// Single attempt of [`TursoClient::get_user_database_entry`]
+    async fn query_user_database_entry(&self, user_id: &str) -> Result<UserDatabaseEntry, ServerError> {
         let conn = self.get_registry_connection().await?;
 
         let mut rows = conn
-            .prepare("SELECT user_id, email, db_name, db_url, db_token, storage_used_bytes, created_at, updated_at FROM user_databases WHERE user_id = ?")
+            .prepare("SELECT user_id, email, db_name, db_url, db_token, storage_used_bytes, provision_status, created_at, updated_at FROM user_databases WHERE user_id = ?")
             .await
-            .map_err(|e| format!("Failed to prepare query: {}", e))?
+            .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
             .query(libsql::params![user_id])
             .await
-            .map_err(|e| format!("Failed to execute query: {}", e))?;
+            .map_err(|e| ServerError::Database(format!("Failed to execute query: {}", e)))?;
 
         if let Some(row) = rows
             .next()
             .await
-            .map_err(|e| format!("Failed to get row: {}", e))?
+            .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
         {
-            Ok(UserDatabaseEntry {
-                user_id: row.get(0).map_err(|e| format!("Failed to get user_id: {}", e))?,
-                email: row.get(1).map_err(|e| format!("Failed to get email: {}", e))?,
-                db_name: row.get(2).map_err(|e| format!("Failed to get db_name: {}", e))?,
-                db_url: row.get(3).map_err(|e| format!("Failed to get db_url: {}", e))?,
-                db_token: row.get(4).map_err(|e| format!("Failed to get db_token: {}", e))?,
-                storage_used_bytes: row.get(5).map_err(|e| format!("Failed to get storage_used_bytes: {}", e))?,
-                created_at: row.get(6).map_err(|e| format!("Failed to get created_at: {}", e))?,
-                updated_at: row.get(7).map_err(|e| format!("Failed to get updated_at: {}", e))?,
-            })
+            user_database_entry_from_row(&row)
         } else {
-            Err(format!("User database not found for user_id: {}", user_id))
+            Err(ServerError::NotFound(format!("User database not found for user_id: {}", user_id)))
+        }
+    }
+
+    /// Run `visit` on a connection to every ready user database, in registry order.
+    ///
+    /// Each database is a separate remote connection, so at most
+    /// `config.user_db_concurrency` are visited at once. A database that can't be
+    /// opened or whose `visit` fails gets an error in its slot of the result; only a
+    /// failure to list the registry fails the whole call.
+    pub async fn visit_user_databases<T, F, Fut>(
+        &self,
+        visit: F,
+    ) -> Result<Vec<(String, Result<T, ServerError>)>, ServerError>
+    where
+        F: Fn(Connection) -> Fut,
+        Fut: Future<Output = Result<T, ServerError>>,
+    {
+        let filter = UserDatabaseFilter {
+            provision_status: Some(ProvisionStatus::Ready),
+            ..Default::default()
+        };
+        let entries = self.list_user_databases(&filter).await?;
+
+        let visit = &visit;
+        Ok(stream::iter(entries)
+            .map(|entry| async move {
+                let result = match self.get_user_database_connection(&entry.user_id).await {
+                    Ok(conn) => visit(conn).await,
+                    Err(e) => Err(e),
+                };
+                (entry.user_id, result)
+            })
+            .buffered(self.config.user_db_concurrency.max(1))
+            .collect()
+            .await)
+    }
+
+    /// List user databases in the registry matching `filter`, oldest first
+    pub async fn list_user_databases(
+        &self,
+        filter: &UserDatabaseFilter,
+    ) -> Result<Vec<UserDatabaseEntry>, ServerError> {
+        let conn = self.get_registry_connection().await?;
+
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        if let Some(status) = filter.provision_status {
+            conditions.push("provision_status = ?");
+            params.push(libsql::Value::from(status.as_str().to_string()));
+        }
+        // Entries store RFC 3339 text; datetime() brings it to the format of the bounds
+        if let Some(after) = &filter.created_after {
+            conditions.push("datetime(created_at) >= ?");
+            params.push(libsql::Value::from(sqlite_timestamp(after)));
+        }
+        if let Some(before) = &filter.created_before {
+            conditions.push("datetime(created_at) < ?");
+            params.push(libsql::Value::from(sqlite_timestamp(before)));
+        }
+
+        let mut sql = "SELECT user_id, email, db_name, db_url, db_token, storage_used_bytes, provision_status, created_at, updated_at FROM user_databases".to_string();
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
         }
+        sql.push_str(" ORDER BY created_at");
+        if let Some(limit) = filter.limit {
+            // SQLite needs a LIMIT before an OFFSET
+            sql.push_str(" LIMIT ? OFFSET ?");
+            params.push(libsql::Value::from(limit));
+            params.push(libsql::Value::from(filter.offset.unwrap_or(0)));
+        }
+
+        let mut rows = conn
+            .prepare(&sql)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+            .query(libsql::params_from_iter(params))
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to execute query: {}", e)))?;
+
+        let mut entries = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+        {
+            entries.push(user_database_entry_from_row(&row)?);
+        }
+
+        Ok(entries)
+    }
+
+    /// Fail when the user's recorded storage usage has reached `MAX_STORAGE_BYTES`.
+    ///
+    /// Compares against the usage stored in the registry, which
+    /// [`refresh_storage_usage`](Self::refresh_storage_usage) keeps up to date.
+    pub async fn check_quota(&self, user_id: &str) -> Result<(), ServerError> {
+        let Some(max_bytes) = self.config.max_storage_bytes else {
+            return Ok(());
+        };
+
+        let entry = self.get_user_database_entry(user_id).await?;
+        let used_bytes = entry.storage_used_bytes.unwrap_or(0).max(0) as u64;
+        if used_bytes >= max_bytes {
+            return Err(ServerError::QuotaExceeded(format!(
+                "Storage quota exceeded: {} of {} bytes used",
+                used_bytes, max_bytes
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Primary region of a database (e.g. `iad`) from the Turso API
+    pub async fn get_database_region(&self, db_name: &str) -> Result<Option<String>, ServerError> {
+        self.api.database_region(db_name).await
+    }
+
+    /// Most live jobs a user may have, from `MAX_JOBS_PER_USER`
+    pub fn max_jobs_per_user(&self) -> Option<u64> {
+        self.config.max_jobs_per_user
+    }
+
+    /// Whether shell jobs may be created and run, from `ALLOW_SHELL_JOBS`
+    pub fn allow_shell_jobs(&self) -> bool {
+        self.config.allow_shell_jobs
+    }
+
+    /// Storage limit per user in bytes, from `MAX_STORAGE_BYTES`
+    pub fn max_storage_bytes(&self) -> Option<u64> {
+        self.config.max_storage_bytes
+    }
+
+    /// Size of the user's database, refreshed through
+    /// [`refresh_storage_usage`](Self::refresh_storage_usage) at most once per
+    /// `STORAGE_USAGE_CACHE_TTL_SECS` so repeated calls don't hit the Turso usage API
+    pub async fn storage_usage(&self, user_id: &str) -> Result<u64, ServerError> {
+        let ttl = self.config.storage_usage_ttl;
+        if let Some(storage_bytes) = self.usage_cache.lock().ok().and_then(|cache| {
+            cache
+                .get(user_id)
+                .filter(|cached| cached.measured_at.elapsed() < ttl)
+                .map(|cached| cached.storage_bytes)
+        }) {
+            return Ok(storage_bytes);
+        }
+
+        let storage_bytes = self.refresh_storage_usage(user_id).await?;
+        if let Ok(mut cache) = self.usage_cache.lock() {
+            cache.retain(|_, cached| cached.measured_at.elapsed() < ttl);
+            cache.insert(
+                user_id.to_string(),
+                CachedUsage {
+                    storage_bytes,
+                    measured_at: Instant::now(),
+                },
+            );
+        }
+        Ok(storage_bytes)
+    }
+
+    /// Fetch the current size of the user's database from the Turso usage API and
+    /// store it in the registry. Returns the new usage in bytes.
+    pub async fn refresh_storage_usage(&self, user_id: &str) -> Result<u64, ServerError> {
+        let entry = self.get_user_database_entry(user_id).await?;
+        let storage_bytes = self.api.storage_usage(&entry.db_name).await?;
+
+        let conn = self.get_registry_connection().await?;
+        conn.execute(
+            "UPDATE user_databases SET storage_used_bytes = ?, updated_at = ? WHERE user_id = ?",
+            libsql::params![
+                storage_bytes as i64,
+                chrono::Utc::now().to_rfc3339(),
+                user_id
+            ],
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to update storage usage: {}", e)))?;
+
+        Ok(storage_bytes)
     }
 
     /// Get user database connection
+    ///
+    /// The underlying `Database` is opened once per user and reused for
+    /// `USER_DB_CACHE_TTL_SECS`, so repeated calls only open a new connection.
     pub async fn get_user_database_connection(
         &self,
         user_id: &str,
-    ) -> Result<Connection, String> {
+    ) -> Result<Connection, ServerError> {
+        let conn = self
+            .get_user_database(user_id)
+            .await?
+            .connect()
+            .map_err(|e| ServerError::Database(format!("Failed to get user database connection: {}", e)))?;
+
+        // SQLite leaves foreign keys off per connection; job_runs relies on them to
+        // be deleted with their job
+        within_deadline("User database connection", conn.execute("PRAGMA foreign_keys = ON", libsql::params![]))
+            .await?
+            .map_err(|e| ServerError::Database(format!("Failed to enable foreign keys: {}", e)))?;
+        Ok(conn)
+    }
+
+    /// Cached handle to the user's database, opening it if there is none or it expired
+    async fn get_user_database(&self, user_id: &str) -> Result<Arc<Database>, ServerError> {
+        let ttl = self.config.user_db_cache_ttl;
+        if let Some(database) = self.database_cache.lock().ok().and_then(|cache| {
+            cache
+                .get(user_id)
+                .filter(|cached| cached.opened_at.elapsed() < ttl)
+                .map(|cached| cached.database.clone())
+        }) {
+            return Ok(database);
+        }
+
         let entry = self.get_user_database_entry(user_id).await?;
+        if entry.provision_status != ProvisionStatus::Ready {
+            return Err(ServerError::NotFound(format!(
+                "User database for user_id {} is not ready (provision status: {})",
+                user_id,
+                entry.provision_status.as_str()
+            )));
+        }
         
-        let user_db = Builder::new_remote(entry.db_url, entry.db_token)
-            .build()
-            .await
-            .map_err(|e| format!("Failed to connect to user database: {}", e))?;
+        let database = Arc::new(self.api.open_database(&entry.db_url, &entry.db_token).await?);
+
+        if let Ok(mut cache) = self.database_cache.lock() {
+            // Drop other expired handles while we hold the lock
+            cache.retain(|_, cached| cached.opened_at.elapsed() < ttl);
+            cache.insert(
+                user_id.to_string(),
+                CachedDatabase {
+                    database: database.clone(),
+                    opened_at: Instant::now(),
+                },
+            );
+        }
 
-        user_db
-            .connect()
-            .map_err(|e| format!("Failed to get user database connection: {}", e))
+        Ok(database)
     }
 
-    /// Health check for registry database
-    pub async fn health_check(&self) -> Result<(), String> {
+    /// Forget the cached database handle for a user, e.g. after their database was deleted
+    pub fn invalidate_user_database(&self, user_id: &str) {
+        if let Ok(mut cache) = self.database_cache.lock() {
+            cache.remove(user_id);
+        }
+    }
+
+    /// Supabase project the client was configured with, for checking tokens and JWKS
+    pub fn supabase(&self) -> &SupabaseConfig {
+        &self.config.supabase
+    }
+
+    /// Key job secrets are encrypted with, if `ENCRYPTION_KEY` is configured
+    pub fn encryption_key(&self) -> Option<&EncryptionKey> {
+        self.config.encryption_key.as_ref()
+    }
+
+    /// Probe the registry database and the Turso API. Failures are reported in the
+    /// status rather than returned, so callers can tell partial degradation apart.
+    pub async fn health_check(&self) -> HealthStatus {
+        let started = Instant::now();
+        let registry = ComponentHealth::from_result(self.check_registry().await, started);
+        let started = Instant::now();
+        let turso_api = ComponentHealth::from_result(self.api.check().await, started);
+        HealthStatus { registry, turso_api }
+    }
+
+    /// Check that the registry database answers a query
+    pub async fn check_registry(&self) -> Result<(), ServerError> {
         let conn = self.get_registry_connection().await?;
-        conn.execute("SELECT 1", libsql::params![])
+        // Read a row of the registry table, so a locked or missing table counts as down.
+        // `execute` can't be used: local databases refuse statements that return rows.
+        let mut rows = conn
+            .query("SELECT 1 FROM user_databases LIMIT 1", libsql::params![])
             .await
-            .map_err(|e| format!("Registry database health check failed: {}", e))?;
+            .map_err(|e| ServerError::Database(format!("Registry database health check failed: {}", e)))?;
+        rows.next()
+            .await
+            .map_err(|e| ServerError::Database(format!("Registry database health check failed: {}", e)))?;
         Ok(())
     }
 
     /// Get current schema version from user database
-    pub async fn get_user_schema_version(&self, user_id: &str) -> Result<Option<schema::SchemaVersion>, String> {
+    pub async fn get_user_schema_version(&self, user_id: &str) -> Result<Option<schema::SchemaVersion>, ServerError> {
         let conn = self.get_user_database_connection(user_id).await?;
         schema::get_user_schema_version(&conn)
             .await
-            .map_err(|e| format!("Failed to get schema version: {}", e))
+            .map_err(|e| ServerError::Database(format!("Failed to get schema version: {}", e)))
+    }
+
+    /// Every schema version recorded in a user's database, oldest first
+    pub async fn get_user_schema_history(&self, user_id: &str) -> Result<Vec<schema::SchemaVersion>, ServerError> {
+        let conn = self.get_user_database_connection(user_id).await?;
+        schema::get_schema_version_history(&conn)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to get schema history: {}", e)))
     }
 
     /// Synchronize user database schema with current application schema
-    pub async fn sync_user_database_schema(&self, user_id: &str) -> Result<(), String> {
+    pub async fn sync_user_database_schema(&self, user_id: &str) -> Result<schema::SyncReport, ServerError> {
+        let conn = self.get_user_database_connection(user_id).await?;
+        let report = schema::sync_user_database_schema(&conn, self.config.migration_policy)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to sync schema: {}", e)));
+        self.invalidate_table_columns(user_id);
+        report
+    }
+
+    /// Move a user's database to a specific schema version, e.g. before rolling a
+    /// deploy back to a build with an older schema
+    pub async fn migrate_user_database(
+        &self,
+        user_id: &str,
+        target: &schema::SchemaVersion,
+    ) -> Result<schema::SyncReport, ServerError> {
+        let conn = self.get_user_database_connection(user_id).await?;
+        let report = schema::migrate_to_version(&conn, target)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to migrate schema: {}", e)));
+        self.invalidate_table_columns(user_id);
+        report
+    }
+
+    /// Names of the columns a user's table currently has, cached until the next schema sync
+    pub async fn get_table_columns(
+        &self,
+        user_id: &str,
+        table: &str,
+    ) -> Result<Arc<HashSet<String>>, ServerError> {
+        let key = (user_id.to_string(), table.to_string());
+        if let Some(columns) = self.column_cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+            return Ok(columns);
+        }
+
         let conn = self.get_user_database_connection(user_id).await?;
-        schema::sync_user_database_schema(&conn)
+        let columns: Arc<HashSet<String>> = Arc::new(
+            schema::get_table_columns(&conn, table)
+                .await
+                .map_err(ServerError::Database)?
+                .into_iter()
+                .map(|column| column.name)
+                .collect(),
+        );
+
+        if let Ok(mut cache) = self.column_cache.lock() {
+            cache.insert(key, columns.clone());
+        }
+        Ok(columns)
+    }
+
+    /// Forget cached column lists for a user, e.g. after their schema changed
+    pub fn invalidate_table_columns(&self, user_id: &str) {
+        if let Ok(mut cache) = self.column_cache.lock() {
+            cache.retain(|(cached_user, _), _| cached_user != user_id);
+        }
+    }
+
+    /// Recreate anything missing from the registry schema without touching its data.
+    /// See [`schema::repair_registry_schema`].
+    pub async fn repair_registry_schema(&self) -> Result<schema::SyncReport, ServerError> {
+        let conn = self.get_registry_connection().await?;
+        schema::repair_registry_schema(&conn)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to repair registry schema: {}", e)))
+    }
+
+    /// Record an admin action in the registry audit log
+    pub async fn record_admin_event(
+        &self,
+        admin_user_id: &str,
+        action: &str,
+        target_user_id: Option<&str>,
+        details: Option<&str>,
+    ) -> Result<(), ServerError> {
+        let conn = self.get_registry_connection().await?;
+        conn.execute(
+            "INSERT INTO admin_events (admin_user_id, action, target_user_id, details) VALUES (?, ?, ?, ?)",
+            libsql::params![admin_user_id, action, target_user_id, details],
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to record admin event: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[actix_web::test]
+    async fn a_transient_registry_error_is_retried() {
+        let attempts = Cell::new(0);
+
+        let result = retry_registry_read(|| {
+            attempts.set(attempts.get() + 1);
+            async {
+                match attempts.get() {
+                    1 => Err(ServerError::Database("database is locked".to_string())),
+                    _ => Ok("entry"),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "entry");
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[actix_web::test]
+    async fn registry_retries_are_bounded_and_skip_other_errors() {
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = retry_registry_read(|| {
+            attempts.set(attempts.get() + 1);
+            async { Err(ServerError::Database("registry unreachable".to_string())) }
+        })
+        .await;
+        assert!(matches!(result, Err(ServerError::Database(_))));
+        assert_eq!(attempts.get(), 1 + REGISTRY_READ_RETRIES);
+
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = retry_registry_read(|| {
+            attempts.set(attempts.get() + 1);
+            async { Err(ServerError::NotFound("no database for user-1".to_string())) }
+        })
+        .await;
+        assert!(matches!(result, Err(ServerError::NotFound(_))));
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[actix_web::test]
+    async fn the_database_handle_is_reused_until_it_expires() {
+        let client = TursoClient::new_in_memory().await.unwrap();
+        client.create_user_database("user-1", "user@example.com", None).await.unwrap();
+
+        let first = client.get_user_database("user-1").await.unwrap();
+        let second = client.get_user_database("user-1").await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        client.invalidate_user_database("user-1");
+        let reopened = client.get_user_database("user-1").await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &reopened));
+
+        let uncached = TursoClient::new_in_memory_with_config(TursoConfig {
+            user_db_cache_ttl: Duration::ZERO,
+            ..TursoConfig::for_testing()
+        })
+        .await
+        .unwrap();
+        uncached.create_user_database("user-1", "user@example.com", None).await.unwrap();
+        let first = uncached.get_user_database("user-1").await.unwrap();
+        let second = uncached.get_user_database("user-1").await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+    }
+
+    #[actix_web::test]
+    async fn provisioning_resumes_after_a_failure_without_creating_the_database_again() {
+        let api = Arc::new(InMemoryTursoApi::default());
+        let client = TursoClient::new_in_memory_with_api(TursoConfig::for_testing(), api.clone())
             .await
-            .map_err(|e| format!("Failed to sync schema: {}", e))
+            .unwrap();
+
+        api.fail_token_creation(1);
+        let failed = client.create_user_database("user-1", "user@example.com", None).await;
+        assert!(matches!(failed, Err(ServerError::TursoApi(_))), "{:?}", failed);
+        let entry = client.get_user_database_entry("user-1").await.unwrap();
+        assert_eq!(entry.provision_status, ProvisionStatus::DatabaseCreated);
+
+        let entry = client.create_user_database("user-1", "user@example.com", None).await.unwrap();
+        assert_eq!(entry.provision_status, ProvisionStatus::Ready);
+        assert_eq!(api.create_calls(), [entry.db_name]);
+        client.get_user_database_connection("user-1").await.unwrap();
     }
 }
@@ -1,5 +1,43 @@
+use crate::server::turso::crypto::EncryptionKey;
+use crate::server::turso::schema::MigrationPolicy;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::fmt;
+use std::time::Duration;
+
+/// Longest DNS label Turso hostnames can use; a database is served as `<name>-<org>`
+const MAX_DB_HOST_LABEL: usize = 63;
+
+/// Length of a Supabase user id (a UUID), the variable part of a database name
+const USER_ID_LEN: usize = 36;
+
+/// Location codes Turso can place a group in
+pub const TURSO_LOCATIONS: &[&str] = &[
+    "ams", "arn", "atl", "bog", "bom", "bos", "cdg", "den", "dfw", "ewr", "eze", "fra", "gdl",
+    "gig", "gru", "hkg", "iad", "jnb", "lax", "lhr", "mad", "mia", "nrt", "ord", "otp", "phx",
+    "qro", "scl", "sea", "sin", "sjc", "syd", "waw", "yul", "yyz", "aws-ap-northeast-1",
+    "aws-ap-south-1", "aws-eu-west-1", "aws-us-east-1", "aws-us-east-2", "aws-us-west-2",
+];
+
+/// Check that `region` is one of [`TURSO_LOCATIONS`]
+///
+/// ```
+/// use cron_jobs::server::turso::validate_region;
+///
+/// assert!(validate_region("fra").is_ok());
+/// assert!(validate_region("mars").is_err());
+/// ```
+pub fn validate_region(region: &str) -> Result<(), String> {
+    if TURSO_LOCATIONS.contains(&region) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown Turso region '{}'; expected one of: {}",
+            region,
+            TURSO_LOCATIONS.join(", ")
+        ))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TursoConfig {
@@ -7,18 +45,176 @@ pub struct TursoConfig {
     pub registry_db_token: String,
     pub turso_api_token: String,
     pub turso_org: String,
+    /// Prepended to new user database names so environments can share an org
+    pub db_prefix: Option<String>,
+    /// Turso group user databases are created in
+    pub turso_group: String,
+    /// Create `turso_group` if the org doesn't have it yet
+    pub autocreate_group: bool,
+    /// Primary location for an auto-created group (e.g. `iad`)
+    pub turso_group_location: Option<String>,
+    /// Region new user databases are placed in unless the request picks one; `None`
+    /// keeps them in `turso_group`
+    pub turso_region: Option<String>,
+    /// Hard cap on a user's database size in bytes; `None` means unlimited
+    pub max_storage_bytes: Option<u64>,
+    /// Most live jobs a user may have; `None` means unlimited
+    pub max_jobs_per_user: Option<u64>,
+    /// How long a storage usage figure from the Turso usage API is reused
+    pub storage_usage_ttl: Duration,
+    /// How long an opened user database handle is reused before it is rebuilt
+    pub user_db_cache_ttl: Duration,
+    /// Most user databases queried at once by operations that visit all of them
+    pub user_db_concurrency: usize,
+    /// How many times a Turso API call is retried after a 429, a 5xx or a network error
+    pub api_max_retries: u32,
+    /// Delay before the first retry of a Turso API call; doubled for each further attempt
+    pub api_retry_backoff: Duration,
+    /// Whether schema syncs may drop tables and columns the app no longer expects
+    pub migration_policy: MigrationPolicy,
+    /// Key job secrets are encrypted with; `None` disables job secrets
+    pub encryption_key: Option<EncryptionKey>,
+    /// Whether users may create and run shell jobs, which execute on this server
+    pub allow_shell_jobs: bool,
     pub supabase: SupabaseConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct SupabaseConfig {
     pub url: String,
-    pub anon_key: String,
-    pub service_role_key: String,
+    pub anon_key: AnonKey,
+    pub service_role_key: ServiceRoleKey,
     pub jwks_url: String,
+    /// How long fetched JWKS keys are trusted before they are fetched again
+    pub jwks_cache_ttl: Duration,
+    /// Seconds of clock skew allowed when checking a token's `exp`. Absorbs drift between
+    /// this server and Supabase, at the cost of accepting tokens that long after expiry.
+    pub jwt_leeway_secs: u64,
+}
+
+/// Supabase public (anon) API key, safe to send on requests made for end users.
+///
+/// Kept as a distinct type from [`ServiceRoleKey`] so one can't be passed where the
+/// other is expected:
+///
+/// ```compile_fail
+/// use cron_jobs::server::turso::config::{AnonKey, ServiceRoleKey};
+///
+/// fn fetch_public(_key: &AnonKey) {}
+///
+/// let service = ServiceRoleKey::new("service-role".to_string());
+/// fetch_public(&service);
+/// ```
+#[derive(Clone)]
+pub struct AnonKey(String);
+
+/// Supabase service role key. Bypasses row level security, so it must only be used
+/// for server-side admin calls.
+#[derive(Clone)]
+pub struct ServiceRoleKey(String);
+
+impl AnonKey {
+    pub fn new(key: String) -> Self {
+        AnonKey(key)
+    }
+
+    /// Raw key for request headers
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ServiceRoleKey {
+    pub fn new(key: String) -> Self {
+        ServiceRoleKey(key)
+    }
+
+    /// Raw key for request headers
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+// Keys are redacted so they never end up in logs through `{:?}`
+impl fmt::Debug for AnonKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AnonKey(<redacted>)")
+    }
+}
+
+impl fmt::Debug for ServiceRoleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ServiceRoleKey(<redacted>)")
+    }
+}
+
+/// Name of a user's Turso database: `user-<id>`, preceded by `<prefix>-` when a prefix
+/// is configured. Turso names only allow lowercase letters, digits and dashes.
+///
+/// ```
+/// use cron_jobs::server::turso::config::user_database_name;
+///
+/// assert_eq!(user_database_name(None, "Ab_12"), "user-ab-12");
+/// assert_eq!(user_database_name(Some("prod"), "Ab_12"), "prod-user-ab-12");
+/// ```
+pub fn user_database_name(prefix: Option<&str>, user_id: &str) -> String {
+    let sanitized_id = user_id.to_lowercase().replace('_', "-");
+    match prefix {
+        Some(prefix) => format!("{}-user-{}", prefix, sanitized_id),
+        None => format!("user-{}", sanitized_id),
+    }
 }
 
 impl TursoConfig {
+    /// Database name for a new user, see [`user_database_name`]
+    pub fn user_database_name(&self, user_id: &str) -> String {
+        user_database_name(self.db_prefix.as_deref(), user_id)
+    }
+
+    /// Placeholder configuration for a client that never calls Turso or Supabase
+    #[cfg(feature = "testing")]
+    pub fn for_testing() -> Self {
+        TursoConfig {
+            registry_db_url: String::new(),
+            registry_db_token: String::new(),
+            turso_api_token: String::new(),
+            turso_org: "testing".to_string(),
+            db_prefix: None,
+            turso_group: "default".to_string(),
+            autocreate_group: false,
+            turso_group_location: None,
+            turso_region: None,
+            max_storage_bytes: None,
+            max_jobs_per_user: None,
+            storage_usage_ttl: Duration::from_secs(60),
+            user_db_cache_ttl: Duration::from_secs(300),
+            user_db_concurrency: 8,
+            api_max_retries: 0,
+            api_retry_backoff: Duration::ZERO,
+            migration_policy: MigrationPolicy::default(),
+            encryption_key: None,
+            allow_shell_jobs: false,
+            supabase: SupabaseConfig {
+                url: "http://localhost".to_string(),
+                anon_key: AnonKey::new(String::new()),
+                service_role_key: ServiceRoleKey::new(String::new()),
+                jwks_url: "http://localhost/auth/v1/.well-known/jwks.json".to_string(),
+                jwks_cache_ttl: Duration::from_secs(600),
+                jwt_leeway_secs: 30,
+            },
+        }
+    }
+
+    /// Group for databases placed in `region`. Turso places databases where their group
+    /// is, so each region gets its own `<turso_group>-<region>` group; without a region
+    /// databases go to `turso_group`.
+    pub fn group_for_region(&self, region: Option<&str>) -> String {
+        match region {
+            Some(region) => format!("{}-{}", self.turso_group, region),
+            None => self.turso_group.clone(),
+        }
+    }
+
     pub fn from_env() -> Result<Self, String> {
         let registry_db_url = env::var("REGISTRY_DB_URL")
             .map_err(|_| "REGISTRY_DB_URL environment variable not set")?;
@@ -32,6 +228,114 @@ impl TursoConfig {
         let turso_org = env::var("TURSO_ORG")
             .map_err(|_| "TURSO_ORG environment variable not set")?;
         
+        let db_prefix = match env::var("TURSO_DB_PREFIX") {
+            Ok(v) if !v.trim().is_empty() => {
+                let prefix = v.trim().trim_end_matches('-').to_lowercase();
+                if prefix.is_empty()
+                    || !prefix.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+                {
+                    return Err(format!(
+                        "Invalid TURSO_DB_PREFIX '{}': use lowercase letters, digits and dashes",
+                        v.trim()
+                    ));
+                }
+                let longest = user_database_name(Some(&prefix), &"0".repeat(USER_ID_LEN));
+                if longest.len() + 1 + turso_org.len() > MAX_DB_HOST_LABEL {
+                    return Err(format!(
+                        "TURSO_DB_PREFIX '{}' is too long: database names like '{}' must fit in {} characters together with '-{}'",
+                        prefix, longest, MAX_DB_HOST_LABEL, turso_org
+                    ));
+                }
+                Some(prefix)
+            }
+            _ => None,
+        };
+        
+        let turso_group = env::var("TURSO_GROUP").unwrap_or_else(|_| "default".to_string());
+        
+        let autocreate_group = env::var("TURSO_AUTOCREATE_GROUP")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1"))
+            .unwrap_or(false);
+        
+        let turso_group_location = env::var("TURSO_GROUP_LOCATION").ok();
+        
+        let turso_region = match env::var("TURSO_REGION") {
+            Ok(v) if !v.trim().is_empty() => {
+                let region = v.trim().to_lowercase();
+                validate_region(&region).map_err(|e| format!("Invalid TURSO_REGION: {}", e))?;
+                Some(region)
+            }
+            _ => None,
+        };
+        
+        let max_storage_bytes = match env::var("MAX_STORAGE_BYTES") {
+            Ok(v) if !v.trim().is_empty() => Some(
+                v.trim()
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid MAX_STORAGE_BYTES: {}", e))?,
+            ),
+            _ => None,
+        };
+        
+        let max_jobs_per_user = match env::var("MAX_JOBS_PER_USER") {
+            Ok(v) if !v.trim().is_empty() => Some(
+                v.trim()
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid MAX_JOBS_PER_USER: {}", e))?,
+            ),
+            _ => None,
+        };
+        
+        let storage_usage_ttl = Duration::from_secs(
+            env::var("STORAGE_USAGE_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(60),
+        );
+        
+        let user_db_cache_ttl = Duration::from_secs(
+            env::var("USER_DB_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(300),
+        );
+        
+        let user_db_concurrency = env::var("USER_DB_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(8);
+        
+        let api_max_retries = env::var("TURSO_API_MAX_RETRIES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(3);
+        
+        let api_retry_backoff = Duration::from_millis(
+            env::var("TURSO_API_RETRY_BACKOFF_MS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(500),
+        );
+        
+        let migration_policy = match env::var("SCHEMA_MIGRATION_POLICY") {
+            Ok(v) if !v.trim().is_empty() => MigrationPolicy::parse(&v.trim().to_lowercase())
+                .map_err(|e| format!("Invalid SCHEMA_MIGRATION_POLICY: {}", e))?,
+            _ => MigrationPolicy::default(),
+        };
+        
+        let encryption_key = match env::var("ENCRYPTION_KEY") {
+            Ok(v) if !v.trim().is_empty() => Some(
+                EncryptionKey::from_base64(&v)
+                    .map_err(|e| format!("Invalid ENCRYPTION_KEY: {}", e))?,
+            ),
+            _ => None,
+        };
+        
+        let allow_shell_jobs = env::var("ALLOW_SHELL_JOBS")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1"))
+            .unwrap_or(false);
+        
         let supabase_url = env::var("VITE_SUPABASE_URL")
             .map_err(|_| "VITE_SUPABASE_URL environment variable not set")?;
         
@@ -44,16 +348,45 @@ impl TursoConfig {
         // Fixed: Added .json extension to JWKS endpoint
         let jwks_url = format!("{}/auth/v1/.well-known/jwks.json", supabase_url.trim_end_matches('/'));
         
+        let jwks_cache_ttl = Duration::from_secs(
+            env::var("SUPABASE_JWKS_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(600),
+        );
+        
+        let jwt_leeway_secs = env::var("SUPABASE_JWT_LEEWAY_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(30);
+        
         Ok(TursoConfig {
             registry_db_url,
             registry_db_token,
             turso_api_token,
             turso_org,
+            db_prefix,
+            turso_group,
+            autocreate_group,
+            turso_group_location,
+            turso_region,
+            max_storage_bytes,
+            max_jobs_per_user,
+            storage_usage_ttl,
+            user_db_cache_ttl,
+            user_db_concurrency,
+            api_max_retries,
+            api_retry_backoff,
+            migration_policy,
+            encryption_key,
+            allow_shell_jobs,
             supabase: SupabaseConfig {
                 url: supabase_url,
-                anon_key: supabase_anon_key,
-                service_role_key: supabase_service_role_key,
+                anon_key: AnonKey::new(supabase_anon_key),
+                service_role_key: ServiceRoleKey::new(supabase_service_role_key),
                 jwks_url,
+                jwks_cache_ttl,
+                jwt_leeway_secs,
             },
         })
     }
@@ -67,4 +400,14 @@ pub struct SupabaseClaims {
     pub role: String,
     pub exp: i64,
     pub iat: i64,
+    // Only writable with the service role key, so safe to use for authorization
+    #[serde(default)]
+    pub app_metadata: serde_json::Value,
+}
+
+impl SupabaseClaims {
+    /// Whether the user has been granted the admin role via `app_metadata.role`
+    pub fn is_admin(&self) -> bool {
+        self.app_metadata.get("role").and_then(|r| r.as_str()) == Some("admin")
+    }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fmt;
+
+/// Length of an AES-GCM nonce in bytes
+const NONCE_LEN: usize = 12;
+
+/// Server-held AES-256-GCM key for values stored encrypted at rest
+///
+/// Ciphertexts are stored as base64 of `nonce || ciphertext`, with a fresh random
+/// nonce per value.
+///
+/// ```
+/// use cron_jobs::server::turso::EncryptionKey;
+///
+/// let key = EncryptionKey::from_base64("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").unwrap();
+/// let stored = key.encrypt("s3cret").unwrap();
+/// assert!(!stored.contains("s3cret"));
+/// assert_ne!(stored, key.encrypt("s3cret").unwrap());
+/// assert_eq!(key.decrypt(&stored).unwrap(), "s3cret");
+/// ```
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Parse a base64-encoded 32-byte key
+    pub fn from_base64(value: &str) -> Result<Self, String> {
+        let bytes = STANDARD
+            .decode(value.trim())
+            .map_err(|e| format!("Failed to decode encryption key: {}", e))?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| format!("Encryption key must be 32 bytes, got {}", bytes.len()))?;
+        Ok(EncryptionKey(key))
+    }
+
+    /// Encrypt `plaintext` for storage
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        let cipher = Aes256Gcm::new(&self.0.into());
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| format!("Failed to encrypt value: {}", e))?;
+
+        let mut stored = nonce.to_vec();
+        stored.extend_from_slice(&ciphertext);
+        Ok(STANDARD.encode(stored))
+    }
+
+    /// Decrypt a value produced by [`EncryptionKey::encrypt`]
+    pub fn decrypt(&self, stored: &str) -> Result<String, String> {
+        let bytes = STANDARD
+            .decode(stored)
+            .map_err(|e| format!("Failed to decode encrypted value: {}", e))?;
+        if bytes.len() < NONCE_LEN {
+            return Err("Encrypted value is too short".to_string());
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+
+        let cipher = Aes256Gcm::new(&self.0.into());
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|e| format!("Failed to decrypt value: {}", e))?;
+        String::from_utf8(plaintext).map_err(|e| format!("Decrypted value is not UTF-8: {}", e))
+    }
+}
+
+// Redacted so the key never ends up in logs through `{:?}`
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
@@ -0,0 +1,39 @@
+use libsql::params::IntoParams;
+use libsql::Connection;
+
+/// Run an `INSERT`, `UPDATE` or `DELETE` and return how many rows it changed.
+///
+/// Not every libsql connection reports the count from `execute` (some remote and
+/// replicated paths return 0), so a zero result falls back to `changes()`, which the
+/// connection updates after every data-changing statement. Rows changed by triggers are
+/// not counted. Transactions deref to their connection and can be passed directly.
+///
+/// ```
+/// use cron_jobs::server::turso::execute_with_count;
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+///     let conn = db.connect().unwrap();
+///     conn.execute("CREATE TABLE jobs (id INTEGER, enabled BOOLEAN)", ()).await.unwrap();
+///     conn.execute("INSERT INTO jobs VALUES (1, 1), (2, 0), (3, 0)", ()).await.unwrap();
+///
+///     let count = execute_with_count(&conn, "UPDATE jobs SET enabled = 1 WHERE enabled = ?", [0])
+///         .await
+///         .unwrap();
+///     assert_eq!(count, 2);
+///
+///     let count = execute_with_count(&conn, "DELETE FROM jobs WHERE id > ?", [5]).await.unwrap();
+///     assert_eq!(count, 0);
+/// });
+/// ```
+pub async fn execute_with_count(
+    conn: &Connection,
+    sql: &str,
+    params: impl IntoParams,
+) -> Result<u64, String> {
+    let reported = conn.execute(sql, params).await.map_err(|e| e.to_string())?;
+    if reported > 0 {
+        return Ok(reported);
+    }
+    Ok(conn.changes())
+}
@@ -1,18 +1,34 @@
 #[cfg(not(target_arch = "wasm32"))]
+pub mod api;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod auth;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod client;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod config;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod crypto;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod exec;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod row;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod schema;
 
 #[cfg(not(target_arch = "wasm32"))]
-pub use auth::{AuthError, get_supabase_user_id, validate_supabase_jwt_token};
+pub use api::{TursoApi, TursoHttpApi};
+#[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
+pub use api::InMemoryTursoApi;
+#[cfg(not(target_arch = "wasm32"))]
+pub use auth::{AUTHENTICATED_ROLE, AuthError, SupabaseUser, bearer_token, check_jwks, get_supabase_claims, get_supabase_user_by_email, get_supabase_user_id, validate_supabase_jwt_token};
+#[cfg(not(target_arch = "wasm32"))]
+pub use client::{ComponentHealth, HealthStatus, ProvisionStatus, TursoClient, UserDatabaseEntry, UserDatabaseFilter, UserDatabaseSummary};
+#[cfg(not(target_arch = "wasm32"))]
+pub use config::{AnonKey, ServiceRoleKey, SupabaseConfig, SupabaseClaims, TursoConfig, TURSO_LOCATIONS, validate_region};
 #[cfg(not(target_arch = "wasm32"))]
-pub use client::{TursoClient, UserDatabaseEntry};
+pub use crypto::EncryptionKey;
 #[cfg(not(target_arch = "wasm32"))]
-pub use config::{SupabaseConfig, SupabaseClaims, TursoConfig};
+pub use exec::execute_with_count;
 #[cfg(not(target_arch = "wasm32"))]
-pub use schema::{SchemaVersion, TableSchema, sync_user_database_schema, get_user_schema_version};
+pub use schema::{ForeignKey, MIGRATIONS, Migration, MigrationPolicy, SchemaVersion, SyncReport, TableSchema, migrate_to_version, repair_registry_schema, sync_user_database_schema, get_schema_version_history, get_user_schema_version};
 
@@ -0,0 +1,171 @@
+use libsql::{Row, Value};
+
+/// Describe a raw column value for error messages
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(i) => format!("integer {}", i),
+        Value::Real(r) => format!("real {}", r),
+        Value::Text(t) => format!("text '{}'", t),
+        Value::Blob(b) => format!("blob of {} bytes", b.len()),
+    }
+}
+
+/// Read the raw value of a column, naming the column on failure
+fn get_raw(row: &Row, idx: i32, column: &str) -> Result<Value, String> {
+    row.get_value(idx)
+        .map_err(|e| format!("Failed to get {}: {}", column, e))
+}
+
+/// Read an integer column.
+///
+/// Text holding a valid integer is accepted so a bad migration that stored numbers as
+/// text still reads back; anything else reports the column name and the actual value.
+pub fn get_i64(row: &Row, idx: i32, column: &str) -> Result<i64, String> {
+    match get_raw(row, idx, column)? {
+        Value::Integer(i) => Ok(i),
+        Value::Text(t) => t.trim().parse::<i64>().map_err(|_| {
+            format!("Column '{}' expected an integer, found text '{}'", column, t)
+        }),
+        other => Err(format!(
+            "Column '{}' expected an integer, found {}",
+            column,
+            describe_value(&other)
+        )),
+    }
+}
+
+/// Read a nullable integer column (e.g. counts that may not have been populated yet)
+pub fn get_optional_i64(row: &Row, idx: i32, column: &str) -> Result<Option<i64>, String> {
+    match get_raw(row, idx, column)? {
+        Value::Null => Ok(None),
+        _ => get_i64(row, idx, column).map(Some),
+    }
+}
+
+/// Read a boolean column stored as an integer flag.
+///
+/// Also accepts the text forms `"0"`, `"1"`, `"true"` and `"false"`.
+pub fn get_bool(row: &Row, idx: i32, column: &str) -> Result<bool, String> {
+    match get_raw(row, idx, column)? {
+        Value::Integer(i) => Ok(i != 0),
+        Value::Text(t) => match t.trim().to_lowercase().as_str() {
+            "1" | "true" => Ok(true),
+            "0" | "false" => Ok(false),
+            _ => Err(format!(
+                "Column '{}' expected a boolean, found text '{}'",
+                column, t
+            )),
+        },
+        other => Err(format!(
+            "Column '{}' expected a boolean, found {}",
+            column,
+            describe_value(&other)
+        )),
+    }
+}
+
+/// Read a timestamp column as text.
+///
+/// SQLite timestamps are normally stored as text; integer values are treated as Unix
+/// seconds and converted to RFC 3339.
+pub fn get_timestamp_text(row: &Row, idx: i32, column: &str) -> Result<String, String> {
+    match get_raw(row, idx, column)? {
+        Value::Text(t) => Ok(t),
+        Value::Integer(secs) => chrono::DateTime::from_timestamp(secs, 0)
+            .map(|dt| dt.to_rfc3339())
+            .ok_or_else(|| format!("Column '{}' holds an out-of-range timestamp {}", column, secs)),
+        other => Err(format!(
+            "Column '{}' expected a timestamp, found {}",
+            column,
+            describe_value(&other)
+        )),
+    }
+}
+
+/// Format a time the way SQLite's `CURRENT_TIMESTAMP` stores it, so stored timestamps
+/// compare correctly as text and work with SQLite's date functions
+pub fn sqlite_timestamp(time: &chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Read a nullable timestamp column as UTC.
+///
+/// Accepts SQLite's `CURRENT_TIMESTAMP` text (`YYYY-MM-DD HH:MM:SS`, always UTC),
+/// RFC 3339 text and integer Unix seconds.
+pub fn get_optional_timestamp(
+    row: &Row,
+    idx: i32,
+    column: &str,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    match get_raw(row, idx, column)? {
+        Value::Null => Ok(None),
+        Value::Integer(secs) => chrono::DateTime::from_timestamp(secs, 0)
+            .map(Some)
+            .ok_or_else(|| format!("Column '{}' holds an out-of-range timestamp {}", column, secs)),
+        Value::Text(t) => chrono::NaiveDateTime::parse_from_str(t.trim(), "%Y-%m-%d %H:%M:%S%.f")
+            .map(|naive| naive.and_utc())
+            .or_else(|_| {
+                chrono::DateTime::parse_from_rfc3339(t.trim()).map(|dt| dt.with_timezone(&chrono::Utc))
+            })
+            .map(Some)
+            .map_err(|_| format!("Column '{}' expected a timestamp, found text '{}'", column, t)),
+        other => Err(format!(
+            "Column '{}' expected a timestamp, found {}",
+            column,
+            describe_value(&other)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The single row `sql` selects from an empty in-memory database
+    async fn select(sql: &str) -> Row {
+        let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+        let conn = db.connect().unwrap();
+        conn.query(sql, ()).await.unwrap().next().await.unwrap().unwrap()
+    }
+
+    #[actix_web::test]
+    async fn text_where_an_integer_is_expected_names_the_column() {
+        let row = select("SELECT 'yes', 'abc', 'soon', ' 42 ', '1'").await;
+
+        assert_eq!(
+            get_bool(&row, 0, "enabled").unwrap_err(),
+            "Column 'enabled' expected a boolean, found text 'yes'"
+        );
+        assert_eq!(
+            get_i64(&row, 1, "run_count").unwrap_err(),
+            "Column 'run_count' expected an integer, found text 'abc'"
+        );
+        assert_eq!(
+            get_optional_timestamp(&row, 2, "created_at").unwrap_err(),
+            "Column 'created_at' expected a timestamp, found text 'soon'"
+        );
+        // Numbers a bad migration stored as text still read back
+        assert_eq!(get_i64(&row, 3, "run_count"), Ok(42));
+        assert_eq!(get_bool(&row, 4, "enabled"), Ok(true));
+    }
+
+    #[actix_web::test]
+    async fn other_mismatches_describe_the_actual_value() {
+        let row = select("SELECT 1.5, NULL, x'0102', 1700000000, '2024-01-02 03:04:05'").await;
+
+        assert_eq!(
+            get_i64(&row, 0, "run_count").unwrap_err(),
+            "Column 'run_count' expected an integer, found real 1.5"
+        );
+        assert_eq!(get_optional_i64(&row, 1, "run_count"), Ok(None));
+        assert_eq!(
+            get_bool(&row, 2, "enabled").unwrap_err(),
+            "Column 'enabled' expected a boolean, found blob of 2 bytes"
+        );
+        let expected = chrono::DateTime::from_timestamp(1_700_000_000, 0);
+        assert_eq!(get_optional_timestamp(&row, 3, "created_at"), Ok(expected));
+        let stored = get_optional_timestamp(&row, 4, "created_at").unwrap().unwrap();
+        assert_eq!(sqlite_timestamp(&stored), "2024-01-02 03:04:05");
+    }
+}
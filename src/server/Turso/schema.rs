@@ -1,6 +1,7 @@
-use libsql::{Builder, Connection};
-use log::info;
-use std::collections::{HashMap, HashSet};
+use crate::server::turso::row::{get_i64, get_timestamp_text};
+use libsql::Connection;
+use log::{info, warn};
+use std::collections::BTreeSet;
 
 /// Schema version information
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -17,6 +18,8 @@ pub struct TableSchema {
     pub columns: Vec<ColumnInfo>,
     pub indexes: Vec<IndexInfo>,
     pub triggers: Vec<TriggerInfo>,
+    #[serde(default)]
+    pub foreign_keys: Vec<ForeignKey>,
 }
 
 /// Column information for schema comparison
@@ -38,6 +41,16 @@ pub struct IndexInfo {
     pub is_unique: bool,
 }
 
+/// Foreign key constraint emitted when a table is created
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    pub references_table: String,
+    pub references_columns: Vec<String>,
+    /// `ON DELETE` action, e.g. `CASCADE`
+    pub on_delete: Option<String>,
+}
+
 /// Trigger information for schema comparison
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct TriggerInfo {
@@ -48,21 +61,46 @@ pub struct TriggerInfo {
     pub action: String,
 }
 
-/// Initialize user database with cron jobs schema
-pub async fn initialize_user_database_schema(db_url: &str, token: &str) -> Result<(), String> {
-    info!("Initializing cron jobs schema for database: {}", db_url);
+/// Outcome of a schema synchronization run
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct SyncReport {
+    /// Version recorded before the sync (`None` for databases without version tracking)
+    pub previous_version: Option<String>,
+    /// Version recorded after the sync
+    pub current_version: String,
+    /// Whether migrations were applied
+    pub migrated: bool,
+    /// Missing tables, columns, indexes and triggers that were created. Only filled in
+    /// by [`repair_registry_schema`]; user syncs leave it empty.
+    #[serde(default)]
+    pub repaired: Vec<String>,
+}
 
-    let user_db = Builder::new_remote(db_url.to_string(), token.to_string())
-        .build()
-        .await
-        .map_err(|e| format!("Failed to build user database connection: {}", e))?;
-    
-    let conn = user_db
-        .connect()
-        .map_err(|e| format!("Failed to connect to user database: {}", e))?;
+/// How a schema sync treats tables and columns the expected schema doesn't have
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationPolicy {
+    /// Only add what is missing; unexpected tables and columns are left alone
+    #[default]
+    Safe,
+    /// Also drop unexpected tables and columns, losing their data
+    Destructive,
+}
 
+impl MigrationPolicy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "safe" => Ok(MigrationPolicy::Safe),
+            "destructive" => Ok(MigrationPolicy::Destructive),
+            other => Err(format!("Unknown migration policy: {}", other)),
+        }
+    }
+}
+
+/// Create the current cron jobs schema on an open user database connection
+pub async fn initialize_user_schema(conn: &Connection) -> Result<(), String> {
     // Initialize schema version table first
-    initialize_schema_version_table(&conn)
+    initialize_schema_version_table(conn)
         .await
         .map_err(|e| format!("Failed to initialize schema version table: {}", e))?;
 
@@ -72,23 +110,23 @@ pub async fn initialize_user_database_schema(db_url: &str, token: &str) -> Resul
 
     // Create all tables
     for table_schema in &expected_schema {
-        create_table(&conn, table_schema)
+        create_table(conn, table_schema)
             .await
             .map_err(|e| format!("Failed to create table {}: {}", table_schema.name, e))?;
         
         // Ensure indexes
-        ensure_indexes(&conn, table_schema)
+        ensure_indexes(conn, table_schema)
             .await
             .map_err(|e| format!("Failed to ensure indexes for {}: {}", table_schema.name, e))?;
         
         // Ensure triggers
-        ensure_triggers(&conn, table_schema)
+        ensure_triggers(conn, table_schema)
             .await
             .map_err(|e| format!("Failed to ensure triggers for {}: {}", table_schema.name, e))?;
     }
 
     // Set initial schema version
-    update_schema_version(&conn, &expected_version)
+    update_schema_version(conn, &expected_version)
         .await
         .map_err(|e| format!("Failed to set schema version: {}", e))?;
 
@@ -96,15 +134,172 @@ pub async fn initialize_user_database_schema(db_url: &str, token: &str) -> Resul
     Ok(())
 }
 
-/// Current schema version (increment this when schema changes)
-pub fn get_current_schema_version() -> SchemaVersion {
-    SchemaVersion {
-        version: "0.0.1".to_string(),
-        description: "Initial cron jobs schema with version tracking".to_string(),
-        created_at: chrono::Utc::now().to_rfc3339(),
+/// One schema version and how to move a database to it from the previous version
+/// and back
+#[derive(Debug)]
+pub struct Migration {
+    pub version: &'static str,
+    pub description: &'static str,
+    /// Columns this version adds, as `(table, column, definition)`. They are only added
+    /// when missing and are kept on downgrade, since older code ignores extra columns.
+    pub add_columns: &'static [(&'static str, &'static str, &'static str)],
+    /// SQL run after the columns are added
+    pub up: &'static [&'static str],
+    /// SQL undoing `up` when downgrading to the previous version
+    pub down: &'static [&'static str],
+}
+
+impl Migration {
+    fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion {
+            version: self.version.to_string(),
+            description: self.description.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
     }
 }
 
+/// Every schema version in order. Append a migration here (and update
+/// [`get_expected_schema`]) when the schema changes; never edit a released one.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: "0.0.1",
+        description: "Initial cron jobs schema with version tracking",
+        add_columns: &[],
+        up: &[
+            "CREATE TABLE IF NOT EXISTS cron_jobs (id TEXT PRIMARY KEY, user_id TEXT NOT NULL, name TEXT NOT NULL, schedule TEXT NOT NULL, command TEXT NOT NULL, enabled BOOLEAN NOT NULL DEFAULT 1, created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            "CREATE INDEX IF NOT EXISTS idx_cron_jobs_user_id ON cron_jobs (user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_cron_jobs_enabled ON cron_jobs (enabled)",
+            "CREATE TRIGGER IF NOT EXISTS update_cron_jobs_timestamp AFTER UPDATE ON cron_jobs FOR EACH ROW BEGIN UPDATE cron_jobs SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id; END",
+        ],
+        down: &[],
+    },
+    Migration {
+        version: "0.0.2",
+        description: "Add per-job timezone to cron jobs",
+        add_columns: &[("cron_jobs", "timezone", "TEXT NOT NULL DEFAULT 'UTC'")],
+        up: &[],
+        down: &[],
+    },
+    Migration {
+        version: "0.0.3",
+        description: "Add idempotency keys for cron job imports",
+        add_columns: &[("cron_jobs", "idempotency_key", "TEXT")],
+        up: &["CREATE INDEX IF NOT EXISTS idx_cron_jobs_idempotency_key ON cron_jobs (user_id, idempotency_key)"],
+        down: &["DROP INDEX IF EXISTS idx_cron_jobs_idempotency_key"],
+    },
+    Migration {
+        version: "0.0.4",
+        description: "Add soft delete for cron jobs",
+        add_columns: &[("cron_jobs", "deleted_at", "TIMESTAMP")],
+        up: &[],
+        // Older code doesn't know about the trash, so keep trashed jobs from running
+        down: &["UPDATE cron_jobs SET enabled = 0 WHERE deleted_at IS NOT NULL"],
+    },
+    Migration {
+        version: "0.0.5",
+        description: "Guard the updated_at trigger against recursion",
+        add_columns: &[],
+        up: &[
+            "DROP TRIGGER IF EXISTS update_cron_jobs_timestamp",
+            "CREATE TRIGGER update_cron_jobs_timestamp AFTER UPDATE ON cron_jobs FOR EACH ROW BEGIN UPDATE cron_jobs SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id AND updated_at IS NOT CURRENT_TIMESTAMP; END",
+        ],
+        down: &[
+            "DROP TRIGGER IF EXISTS update_cron_jobs_timestamp",
+            "CREATE TRIGGER update_cron_jobs_timestamp AFTER UPDATE ON cron_jobs FOR EACH ROW BEGIN UPDATE cron_jobs SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id; END",
+        ],
+    },
+    Migration {
+        version: "0.0.6",
+        description: "Add HTTP webhook jobs",
+        add_columns: &[
+            ("cron_jobs", "job_type", "TEXT NOT NULL DEFAULT 'shell'"),
+            ("cron_jobs", "http_method", "TEXT"),
+            ("cron_jobs", "http_body", "TEXT"),
+        ],
+        up: &[],
+        // Older code would run an HTTP job's URL as a shell command
+        down: &["UPDATE cron_jobs SET enabled = 0 WHERE job_type <> 'shell'"],
+    },
+    Migration {
+        version: "0.0.7",
+        description: "Add job run history",
+        add_columns: &[],
+        up: &[
+            "CREATE TABLE IF NOT EXISTS job_runs (id TEXT PRIMARY KEY, job_id TEXT NOT NULL, started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, finished_at TIMESTAMP, status TEXT NOT NULL, exit_code INTEGER, output_excerpt TEXT)",
+            "CREATE INDEX IF NOT EXISTS idx_job_runs_job_id ON job_runs (job_id)",
+        ],
+        // The run history table is kept so it survives a downgrade and upgrade
+        down: &[],
+    },
+    Migration {
+        version: "0.0.8",
+        description: "Add job retry policy",
+        add_columns: &[
+            ("cron_jobs", "max_retries", "INTEGER NOT NULL DEFAULT 0"),
+            ("cron_jobs", "retry_backoff_secs", "INTEGER NOT NULL DEFAULT 0"),
+        ],
+        up: &[],
+        down: &[],
+    },
+    Migration {
+        version: "0.0.9",
+        description: "Add per-job run history limit",
+        add_columns: &[("cron_jobs", "run_history_limit", "INTEGER")],
+        up: &[],
+        down: &[],
+    },
+    Migration {
+        version: "0.0.10",
+        description: "Delete job runs together with their cron job",
+        add_columns: &[],
+        // SQLite can't add a foreign key to an existing table, so job_runs is rebuilt.
+        // Runs of jobs that were already purged can't satisfy the key and are dropped.
+        up: &[
+            "CREATE TABLE job_runs_new (id TEXT PRIMARY KEY, job_id TEXT NOT NULL, started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, finished_at TIMESTAMP, status TEXT NOT NULL, exit_code INTEGER, output_excerpt TEXT, FOREIGN KEY (job_id) REFERENCES cron_jobs (id) ON DELETE CASCADE)",
+            "INSERT INTO job_runs_new SELECT id, job_id, started_at, finished_at, status, exit_code, output_excerpt FROM job_runs WHERE job_id IN (SELECT id FROM cron_jobs)",
+            "DROP TABLE job_runs",
+            "ALTER TABLE job_runs_new RENAME TO job_runs",
+            "CREATE INDEX IF NOT EXISTS idx_job_runs_job_id ON job_runs (job_id)",
+        ],
+        down: &[
+            "CREATE TABLE job_runs_old (id TEXT PRIMARY KEY, job_id TEXT NOT NULL, started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, finished_at TIMESTAMP, status TEXT NOT NULL, exit_code INTEGER, output_excerpt TEXT)",
+            "INSERT INTO job_runs_old SELECT id, job_id, started_at, finished_at, status, exit_code, output_excerpt FROM job_runs",
+            "DROP TABLE job_runs",
+            "ALTER TABLE job_runs_old RENAME TO job_runs",
+            "CREATE INDEX IF NOT EXISTS idx_job_runs_job_id ON job_runs (job_id)",
+        ],
+    },
+    Migration {
+        version: "0.0.11",
+        description: "Add encrypted per-job environment variables",
+        add_columns: &[],
+        up: &[
+            "CREATE TABLE IF NOT EXISTS cron_job_env (job_id TEXT NOT NULL, key TEXT NOT NULL, value_encrypted TEXT NOT NULL, updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, PRIMARY KEY (job_id, key), FOREIGN KEY (job_id) REFERENCES cron_jobs (id) ON DELETE CASCADE)",
+        ],
+        // Older code can't decrypt the values, so they are not worth keeping around
+        down: &["DROP TABLE IF EXISTS cron_job_env"],
+    },
+    Migration {
+        version: "0.0.12",
+        description: "Add job misfire policy and catch-up bound",
+        add_columns: &[
+            ("cron_jobs", "misfire_policy", "TEXT NOT NULL DEFAULT 'skip'"),
+            ("cron_jobs", "max_catchup", "INTEGER"),
+        ],
+        up: &[],
+        down: &[],
+    },
+];
+
+/// Current schema version, the last entry of [`MIGRATIONS`]
+pub fn get_current_schema_version() -> SchemaVersion {
+    MIGRATIONS
+        .last()
+        .expect("MIGRATIONS must not be empty")
+        .schema_version()
+}
+
 /// Expected schema for synchronization
 pub fn get_expected_schema() -> Vec<TableSchema> {
     vec![
@@ -119,6 +314,17 @@ pub fn get_expected_schema() -> Vec<TableSchema> {
                 ColumnInfo { name: "enabled".to_string(), data_type: "BOOLEAN".to_string(), is_nullable: false, default_value: Some("1".to_string()), is_primary_key: false },
                 ColumnInfo { name: "created_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: false, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
                 ColumnInfo { name: "updated_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: false, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
+                ColumnInfo { name: "timezone".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: Some("'UTC'".to_string()), is_primary_key: false },
+                ColumnInfo { name: "idempotency_key".to_string(), data_type: "TEXT".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "deleted_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "job_type".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: Some("'shell'".to_string()), is_primary_key: false },
+                ColumnInfo { name: "http_method".to_string(), data_type: "TEXT".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "http_body".to_string(), data_type: "TEXT".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "max_retries".to_string(), data_type: "INTEGER".to_string(), is_nullable: false, default_value: Some("0".to_string()), is_primary_key: false },
+                ColumnInfo { name: "retry_backoff_secs".to_string(), data_type: "INTEGER".to_string(), is_nullable: false, default_value: Some("0".to_string()), is_primary_key: false },
+                ColumnInfo { name: "run_history_limit".to_string(), data_type: "INTEGER".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "misfire_policy".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: Some("'skip'".to_string()), is_primary_key: false },
+                ColumnInfo { name: "max_catchup".to_string(), data_type: "INTEGER".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
             ],
             indexes: vec![
                 IndexInfo {
@@ -133,6 +339,12 @@ pub fn get_expected_schema() -> Vec<TableSchema> {
                     columns: vec!["enabled".to_string()],
                     is_unique: false,
                 },
+                IndexInfo {
+                    name: "idx_cron_jobs_idempotency_key".to_string(),
+                    table_name: "cron_jobs".to_string(),
+                    columns: vec!["user_id".to_string(), "idempotency_key".to_string()],
+                    is_unique: false,
+                },
             ],
             triggers: vec![
                 TriggerInfo {
@@ -140,7 +352,57 @@ pub fn get_expected_schema() -> Vec<TableSchema> {
                     table_name: "cron_jobs".to_string(),
                     event: "UPDATE".to_string(),
                     timing: "AFTER".to_string(),
-                    action: "UPDATE cron_jobs SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id".to_string(),
+                    // The updated_at check stops the trigger's own UPDATE from firing it again
+                    action: "UPDATE cron_jobs SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id AND updated_at IS NOT CURRENT_TIMESTAMP".to_string(),
+                },
+            ],
+            foreign_keys: vec![],
+        },
+        TableSchema {
+            name: "job_runs".to_string(),
+            columns: vec![
+                ColumnInfo { name: "id".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: true },
+                ColumnInfo { name: "job_id".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "started_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: false, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
+                ColumnInfo { name: "finished_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "status".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "exit_code".to_string(), data_type: "INTEGER".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "output_excerpt".to_string(), data_type: "TEXT".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+            ],
+            indexes: vec![
+                IndexInfo {
+                    name: "idx_job_runs_job_id".to_string(),
+                    table_name: "job_runs".to_string(),
+                    columns: vec!["job_id".to_string()],
+                    is_unique: false,
+                },
+            ],
+            triggers: vec![],
+            foreign_keys: vec![
+                ForeignKey {
+                    columns: vec!["job_id".to_string()],
+                    references_table: "cron_jobs".to_string(),
+                    references_columns: vec!["id".to_string()],
+                    on_delete: Some("CASCADE".to_string()),
+                },
+            ],
+        },
+        TableSchema {
+            name: "cron_job_env".to_string(),
+            columns: vec![
+                ColumnInfo { name: "job_id".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: true },
+                ColumnInfo { name: "key".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: true },
+                ColumnInfo { name: "value_encrypted".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "updated_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: false, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
+            ],
+            indexes: vec![],
+            triggers: vec![],
+            foreign_keys: vec![
+                ForeignKey {
+                    columns: vec!["job_id".to_string()],
+                    references_table: "cron_jobs".to_string(),
+                    references_columns: vec!["id".to_string()],
+                    on_delete: Some("CASCADE".to_string()),
                 },
             ],
         },
@@ -211,31 +473,101 @@ pub async fn ensure_indexes(conn: &Connection, table_schema: &TableSchema) -> Re
     Ok(())
 }
 
+/// Whether a trigger's action updates the table the trigger is defined on
+fn trigger_updates_own_table(trigger: &TriggerInfo) -> bool {
+    let action = trigger
+        .action
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase();
+    action.starts_with(&format!("UPDATE {} ", trigger.table_name.to_uppercase()))
+}
+
+/// Whether a self-updating trigger limits its UPDATE beyond matching the row.
+///
+/// A bare `WHERE id = NEW.id` matches the row that fired the trigger again, so with
+/// `recursive_triggers` on it would loop. Any extra condition (e.g. only touching rows
+/// whose timestamp is stale) is treated as a guard.
+fn trigger_has_recursion_guard(trigger: &TriggerInfo) -> bool {
+    trigger
+        .action
+        .to_uppercase()
+        .split_once(" WHERE ")
+        .is_some_and(|(_, condition)| condition.contains(" AND "))
+}
+
+/// Current state of `PRAGMA recursive_triggers`
+async fn recursive_triggers_enabled(conn: &Connection) -> Result<bool, String> {
+    let mut rows = conn
+        .query("PRAGMA recursive_triggers", libsql::params![])
+        .await
+        .map_err(|e| format!("Failed to query recursive_triggers: {}", e))?;
+
+    match rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get recursive_triggers row: {}", e))?
+    {
+        Some(row) => Ok(get_i64(&row, 0, "recursive_triggers")? != 0),
+        None => Ok(false),
+    }
+}
+
 /// Ensure triggers for a table
+///
+/// Existing triggers whose definition no longer matches the expected action are dropped
+/// and recreated, so fixes to a trigger reach databases created before the fix.
 pub async fn ensure_triggers(conn: &Connection, table_schema: &TableSchema) -> Result<(), String> {
     for trigger in &table_schema.triggers {
+        if trigger_updates_own_table(trigger) && !trigger_has_recursion_guard(trigger) {
+            warn!(
+                "Trigger {} updates its own table {} without a guarding condition and would recurse \
+                 if recursive triggers were enabled (recursive_triggers is currently {})",
+                trigger.name,
+                trigger.table_name,
+                if recursive_triggers_enabled(conn).await? { "on" } else { "off" }
+            );
+        }
+
         let mut rows = conn
-            .prepare("SELECT name FROM sqlite_master WHERE type='trigger' AND name=?")
+            .prepare("SELECT sql FROM sqlite_master WHERE type='trigger' AND name=?")
             .await
             .map_err(|e| format!("Failed to prepare trigger check query: {}", e))?
             .query(libsql::params![trigger.name.as_str()])
             .await
             .map_err(|e| format!("Failed to check trigger existence: {}", e))?;
 
-        if rows
+        let existing_sql = match rows
             .next()
             .await
             .map_err(|e| format!("Failed to get trigger check result: {}", e))?
-            .is_none()
         {
-            let create_trigger_sql = format!(
-                "CREATE TRIGGER IF NOT EXISTS {} {} {} ON {} FOR EACH ROW BEGIN {}; END",
-                trigger.name, trigger.timing, trigger.event, trigger.table_name, trigger.action
-            );
-            conn.execute(&create_trigger_sql, libsql::params![])
-                .await
-                .map_err(|e| format!("Failed to create trigger {}: {}", trigger.name, e))?;
+            Some(row) => Some(
+                row.get::<String>(0)
+                    .map_err(|e| format!("Failed to get trigger sql: {}", e))?,
+            ),
+            None => None,
+        };
+
+        match existing_sql {
+            Some(sql) if sql.contains(&trigger.action) => continue,
+            Some(_) => {
+                info!("Recreating outdated trigger {}", trigger.name);
+                conn.execute(&format!("DROP TRIGGER IF EXISTS {}", trigger.name), libsql::params![])
+                    .await
+                    .map_err(|e| format!("Failed to drop trigger {}: {}", trigger.name, e))?;
+            }
+            None => {}
         }
+
+        let create_trigger_sql = format!(
+            "CREATE TRIGGER IF NOT EXISTS {} {} {} ON {} FOR EACH ROW BEGIN {}; END",
+            trigger.name, trigger.timing, trigger.event, trigger.table_name, trigger.action
+        );
+        conn.execute(&create_trigger_sql, libsql::params![])
+            .await
+            .map_err(|e| format!("Failed to create trigger {}: {}", trigger.name, e))?;
     }
     Ok(())
 }
@@ -299,6 +631,17 @@ pub async fn create_table(conn: &Connection, table_schema: &TableSchema) -> Resu
     if primary_keys.len() > 1 {
         create_sql.push_str(&format!(", PRIMARY KEY ({})", primary_keys.join(", ")));
     }
+    for foreign_key in &table_schema.foreign_keys {
+        create_sql.push_str(&format!(
+            ", FOREIGN KEY ({}) REFERENCES {} ({})",
+            foreign_key.columns.join(", "),
+            foreign_key.references_table,
+            foreign_key.references_columns.join(", ")
+        ));
+        if let Some(action) = &foreign_key.on_delete {
+            create_sql.push_str(&format!(" ON DELETE {}", action));
+        }
+    }
     create_sql.push(')');
 
     conn.execute(&create_sql, libsql::params![])
@@ -326,193 +669,16 @@ pub async fn get_table_columns(conn: &Connection, table_name: &str) -> Result<Ve
         columns.push(ColumnInfo {
             name: row.get(1).map_err(|e| format!("Failed to get column name: {}", e))?,
             data_type: row.get(2).map_err(|e| format!("Failed to get data type: {}", e))?,
-            is_nullable: row.get::<i32>(3).map_err(|e| format!("Failed to get nullable: {}", e))? == 0,
+            is_nullable: get_i64(&row, 3, "notnull")? == 0,
             default_value: row.get(4).map_err(|e| format!("Failed to get default: {}", e))?,
-            is_primary_key: row.get::<i32>(5).map_err(|e| format!("Failed to get primary key: {}", e))? == 1,
+            is_primary_key: get_i64(&row, 5, "pk")? == 1,
         });
     }
     Ok(columns)
 }
 
-/// Update table schema if needed
-pub async fn update_table_schema(conn: &Connection, table_schema: &TableSchema) -> Result<(), String> {
-    let current_columns = get_table_columns(conn, &table_schema.name)
-        .await
-        .map_err(|e| format!("Failed to get current columns: {}", e))?;
-
-    // Handle column renames: map old column names to new ones
-    let column_rename_map: HashMap<String, String> = HashMap::new();
-    
-    // Add column rename mappings for cron_jobs table
-    if table_schema.name == "cron_jobs" {
-        // Example: If you need to rename a column in the future, add it here:
-        // let has_old_column = current_columns.iter().any(|c| c.name == "old_column_name");
-        // let has_new_column = current_columns.iter().any(|c| c.name == "new_column_name");
-        // if has_old_column && !has_new_column {
-        //     column_rename_map.insert("old_column_name".to_string(), "new_column_name".to_string());
-        // }
-        
-        // Future rename examples (uncomment and modify as needed):
-        // - If renaming "schedule" to "cron_schedule":
-        // let has_schedule = current_columns.iter().any(|c| c.name == "schedule");
-        // let has_cron_schedule = current_columns.iter().any(|c| c.name == "cron_schedule");
-        // if has_schedule && !has_cron_schedule {
-        //     column_rename_map.insert("schedule".to_string(), "cron_schedule".to_string());
-        // }
-    }
-
-    // Add missing columns (skip if they're being renamed from an old column)
-    for expected_col in &table_schema.columns {
-        let is_renamed = column_rename_map
-            .values()
-            .any(|new_name| new_name == &expected_col.name);
-        if !current_columns
-            .iter()
-            .any(|c| c.name == expected_col.name)
-            && !is_renamed
-        {
-            let mut alter_sql = format!(
-                "ALTER TABLE {} ADD COLUMN {} {}",
-                table_schema.name, expected_col.name, expected_col.data_type
-            );
-
-            // For NOT NULL columns without explicit defaults, provide appropriate defaults
-            if !expected_col.is_nullable {
-                if let Some(default) = &expected_col.default_value {
-                    alter_sql.push_str(&format!(" NOT NULL DEFAULT {}", default));
-                } else {
-                    // Provide default values for NOT NULL columns based on data type
-                    match expected_col.data_type.to_uppercase().as_str() {
-                        "TEXT" | "VARCHAR" => alter_sql.push_str(" NOT NULL DEFAULT ''"),
-                        "INTEGER" => alter_sql.push_str(" NOT NULL DEFAULT 0"),
-                        "REAL" | "DECIMAL" => alter_sql.push_str(" NOT NULL DEFAULT 0.0"),
-                        "BOOLEAN" => alter_sql.push_str(" NOT NULL DEFAULT false"),
-                        "DATE" => alter_sql.push_str(" NOT NULL DEFAULT '1970-01-01'"),
-                        "TIME" => alter_sql.push_str(" NOT NULL DEFAULT '00:00:00'"),
-                        "TIMESTAMP" => alter_sql.push_str(" NOT NULL DEFAULT CURRENT_TIMESTAMP"),
-                        _ => alter_sql.push_str(" NOT NULL DEFAULT ''"),
-                    }
-                }
-            } else if let Some(default) = &expected_col.default_value {
-                alter_sql.push_str(&format!(" DEFAULT {}", default));
-            }
-
-            conn.execute(&alter_sql, libsql::params![])
-                .await
-                .map_err(|e| format!("Failed to add column {}: {}", expected_col.name, e))?;
-        }
-    }
-
-    // Remove columns that are not in the expected schema (excluding renamed columns)
-    let expected_names: HashSet<String> = table_schema
-        .columns
-        .iter()
-        .map(|c| c.name.clone())
-        .collect();
-    let renamed_old_names: HashSet<String> = column_rename_map.keys().cloned().collect();
-    let columns_to_remove: Vec<String> = current_columns
-        .iter()
-        .filter(|c| {
-            !expected_names.contains(&c.name)
-                && !renamed_old_names.contains(&c.name)
-                && !c.is_primary_key
-        })
-        .map(|c| c.name.clone())
-        .collect();
-
-    // Recreate table if we need to remove columns OR rename columns
-    if !columns_to_remove.is_empty() || !column_rename_map.is_empty() {
-        if !column_rename_map.is_empty() {
-            info!(
-                "Renaming columns in {}: {:?}",
-                table_schema.name, column_rename_map
-            );
-        }
-        if !columns_to_remove.is_empty() {
-            info!(
-                "Removing obsolete columns from {}: {:?}",
-                table_schema.name, columns_to_remove
-            );
-        }
-
-        // SQLite doesn't support DROP COLUMN or RENAME COLUMN directly, so we need to recreate the table
-        // First, create a backup of existing data
-        let backup_table = format!("{}_backup", table_schema.name);
-        conn.execute(
-            &format!("CREATE TABLE {} AS SELECT * FROM {}", backup_table, table_schema.name),
-            libsql::params![],
-        )
-        .await
-        .map_err(|e| format!("Failed to create backup table: {}", e))?;
-
-        // Drop the original table
-        conn.execute(
-            &format!("DROP TABLE {}", table_schema.name),
-            libsql::params![],
-        )
-        .await
-        .map_err(|e| format!("Failed to drop original table: {}", e))?;
-
-        // Recreate the table with the correct schema
-        create_table(conn, table_schema)
-            .await
-            .map_err(|e| format!("Failed to recreate table: {}", e))?;
-
-        // Copy data back, handling column renames
-        let mut select_columns = Vec::new();
-        let mut insert_columns = Vec::new();
-
-        for current_col in &current_columns {
-            if let Some(new_name) = column_rename_map.get(&current_col.name) {
-                // This column was renamed
-                if expected_names.contains(new_name) {
-                    select_columns.push(current_col.name.clone());
-                    insert_columns.push(new_name.clone());
-                }
-            } else if expected_names.contains(&current_col.name) {
-                // Column exists in both schemas with same name
-                select_columns.push(current_col.name.clone());
-                insert_columns.push(current_col.name.clone());
-            }
-        }
-
-        if !insert_columns.is_empty() {
-            let select_str = select_columns.join(", ");
-            let insert_str = insert_columns.join(", ");
-            conn.execute(
-                &format!(
-                    "INSERT INTO {} ({}) SELECT {} FROM {}",
-                    table_schema.name, insert_str, select_str, backup_table
-                ),
-                libsql::params![],
-            )
-            .await
-            .map_err(|e| format!("Failed to copy data back: {}", e))?;
-        }
-
-        // Drop the backup table
-        conn.execute(
-            &format!("DROP TABLE {}", backup_table),
-            libsql::params![],
-        )
-        .await
-        .map_err(|e| format!("Failed to drop backup table: {}", e))?;
-
-        // Recreate indexes and triggers
-        ensure_indexes(conn, table_schema)
-            .await
-            .map_err(|e| format!("Failed to recreate indexes: {}", e))?;
-        ensure_triggers(conn, table_schema)
-            .await
-            .map_err(|e| format!("Failed to recreate triggers: {}", e))?;
-    }
-
-    Ok(())
-}
-
-/// Get current schema version from user database
-pub async fn get_user_schema_version(conn: &Connection) -> Result<Option<SchemaVersion>, String> {
-    // Check if schema_version table exists
+/// Whether the database has a `schema_version` table
+async fn schema_version_table_exists(conn: &Connection) -> Result<bool, String> {
     let mut rows = conn
         .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='schema_version'")
         .await
@@ -521,18 +687,22 @@ pub async fn get_user_schema_version(conn: &Connection) -> Result<Option<SchemaV
         .await
         .map_err(|e| format!("Failed to execute schema_version check: {}", e))?;
 
-    if rows
+    Ok(rows
         .next()
         .await
         .map_err(|e| format!("Failed to get schema_version check result: {}", e))?
-        .is_none()
-    {
+        .is_some())
+}
+
+/// Get current schema version from user database
+pub async fn get_user_schema_version(conn: &Connection) -> Result<Option<SchemaVersion>, String> {
+    if !schema_version_table_exists(conn).await? {
         return Ok(None); // No schema version table, means old schema
     }
 
     // Get the latest schema version
     let mut rows = conn
-        .prepare("SELECT version, description, created_at FROM schema_version ORDER BY created_at DESC LIMIT 1")
+        .prepare("SELECT version, description, created_at FROM schema_version ORDER BY created_at DESC, id DESC LIMIT 1")
         .await
         .map_err(|e| format!("Failed to prepare schema version query: {}", e))?
         .query(libsql::params![])
@@ -547,193 +717,331 @@ pub async fn get_user_schema_version(conn: &Connection) -> Result<Option<SchemaV
         Ok(Some(SchemaVersion {
             version: row.get(0).map_err(|e| format!("Failed to get version: {}", e))?,
             description: row.get(1).map_err(|e| format!("Failed to get description: {}", e))?,
-            created_at: row.get(2).map_err(|e| format!("Failed to get created_at: {}", e))?,
+            created_at: get_timestamp_text(&row, 2, "created_at")?,
         }))
     } else {
         Ok(None)
     }
 }
 
+/// Every version recorded in the user database, oldest first, including downgrades.
+/// Empty when the database has no `schema_version` table yet.
+pub async fn get_schema_version_history(conn: &Connection) -> Result<Vec<SchemaVersion>, String> {
+    if !schema_version_table_exists(conn).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut rows = conn
+        .prepare("SELECT version, description, created_at FROM schema_version ORDER BY created_at, id")
+        .await
+        .map_err(|e| format!("Failed to prepare schema history query: {}", e))?
+        .query(libsql::params![])
+        .await
+        .map_err(|e| format!("Failed to execute schema history query: {}", e))?;
+
+    let mut history = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get schema history row: {}", e))?
+    {
+        history.push(SchemaVersion {
+            version: row.get(0).map_err(|e| format!("Failed to get version: {}", e))?,
+            description: row.get(1).map_err(|e| format!("Failed to get description: {}", e))?,
+            created_at: get_timestamp_text(&row, 2, "created_at")?,
+        });
+    }
+    Ok(history)
+}
+
 /// Synchronize user database schema with current application schema
-pub async fn sync_user_database_schema(conn: &Connection) -> Result<(), String> {
+///
+/// Applies the migrations after the recorded version, then reconciles any drift from
+/// the expected schema under `policy`. A database recorded at a version this build
+/// doesn't know (written by a newer deploy) is left untouched.
+pub async fn sync_user_database_schema(conn: &Connection, policy: MigrationPolicy) -> Result<SyncReport, String> {
     info!("Starting schema synchronization");
 
     let current_version = get_user_schema_version(conn)
         .await
         .map_err(|e| format!("Failed to get current schema version: {}", e))?;
-    let expected_version = get_current_schema_version();
-    let expected_schema = get_expected_schema();
 
-    // If no version exists, this is a new database or very old one
-    if current_version.is_none() {
-        info!("No schema version found, initializing with current schema");
-        initialize_schema_version_table(conn)
-            .await
-            .map_err(|e| format!("Failed to initialize schema version table: {}", e))?;
-        apply_schema_migrations(conn, &expected_schema)
-            .await
-            .map_err(|e| format!("Failed to apply schema migrations: {}", e))?;
-        update_schema_version(conn, &expected_version)
-            .await
-            .map_err(|e| format!("Failed to update schema version: {}", e))?;
-        return Ok(());
+    if let Some(current_version) = current_version {
+        if migration_index(&current_version.version).is_none() {
+            warn!(
+                "Schema version {} is newer than this build knows; leaving the schema as is",
+                current_version.version
+            );
+            return Ok(SyncReport {
+                previous_version: Some(current_version.version.clone()),
+                current_version: current_version.version,
+                migrated: false,
+                repaired: Vec::new(),
+            });
+        }
     }
 
-    let current_version = current_version.unwrap();
-
-    // Compare versions
-    if current_version.version != expected_version.version {
-        info!(
-            "Schema version mismatch: current={}, expected={}",
-            current_version.version, expected_version.version
-        );
-
-        // Apply schema migrations
-        apply_schema_migrations(conn, &expected_schema)
+    let report = migrate_to_version(conn, &get_current_schema_version()).await?;
+    if report.migrated {
+        apply_schema_migrations(conn, &get_expected_schema(), policy)
             .await
             .map_err(|e| format!("Failed to apply schema migrations: {}", e))?;
-        update_schema_version(conn, &expected_version)
-            .await
-            .map_err(|e| format!("Failed to update schema version: {}", e))?;
-
         info!("Schema synchronized successfully");
     } else {
         info!("Schema is up to date");
     }
-
-    Ok(())
+    Ok(report)
 }
 
-/// Apply schema migrations to bring database up to current schema
-/// This function makes schema.rs the source of truth - it will drop any tables
-/// that exist in the database but are not in the expected schema
-pub async fn apply_schema_migrations(
-    conn: &Connection,
-    expected_schema: &[TableSchema],
-) -> Result<(), String> {
-    info!("Applying schema migrations");
-
-    // Get list of expected table names (source of truth)
-    let expected_table_names: HashSet<String> = expected_schema
-        .iter()
-        .map(|s| s.name.clone())
-        .collect();
+/// Position of `version` in [`MIGRATIONS`]
+fn migration_index(version: &str) -> Option<usize> {
+    MIGRATIONS.iter().position(|migration| migration.version == version)
+}
 
-    // Also include system tables that should never be dropped
-    let protected_tables: std::collections::HashSet<String> = [
-        "schema_version".to_string(),
-        "sqlite_sequence".to_string(), // SQLite internal table
-    ]
-    .iter()
-    .cloned()
-    .collect();
+/// Move a user database to `target`, applying migrations up or reverting them down
+///
+/// A database without a recorded version gets every migration, which is
+/// non-destructive for tables and columns that already exist. Each step runs in its
+/// own transaction together with recording the version it reaches.
+pub async fn migrate_to_version(conn: &Connection, target: &SchemaVersion) -> Result<SyncReport, String> {
+    let target_index = migration_index(&target.version)
+        .ok_or_else(|| format!("Unknown schema version {}", target.version))?;
 
-    // Get current tables in database
-    let current_tables = get_current_tables(conn)
+    initialize_schema_version_table(conn)
         .await
-        .map_err(|e| format!("Failed to get current tables: {}", e))?;
-
-    // Drop tables that exist in database but are not in expected schema
-    // Temporarily disable foreign key constraints to allow dropping tables with dependencies
-    conn.execute("PRAGMA foreign_keys = OFF", libsql::params![])
+        .map_err(|e| format!("Failed to initialize schema version table: {}", e))?;
+    let previous_version = get_user_schema_version(conn)
         .await
-        .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+        .map_err(|e| format!("Failed to get current schema version: {}", e))?
+        .map(|version| version.version);
+    let current_index = previous_version
+        .as_deref()
+        .map(|version| {
+            migration_index(version).ok_or_else(|| format!("Unknown schema version {} in database", version))
+        })
+        .transpose()?;
+
+    match current_index {
+        Some(current) if current == target_index => {
+            return Ok(SyncReport {
+                previous_version,
+                current_version: target.version.clone(),
+                migrated: false,
+                repaired: Vec::new(),
+            });
+        }
+        Some(current) if current > target_index => {
+            info!("Downgrading schema from {} to {}", MIGRATIONS[current].version, target.version);
+            for index in (target_index + 1..=current).rev() {
+                revert_migration(conn, &MIGRATIONS[index], &MIGRATIONS[index - 1]).await?;
+            }
+        }
+        _ => {
+            let start = current_index.map_or(0, |current| current + 1);
+            for migration in &MIGRATIONS[start..=target_index] {
+                apply_migration(conn, migration).await?;
+            }
+        }
+    }
 
-    for table_name in &current_tables {
-        if !expected_table_names.contains(table_name) && !protected_tables.contains(table_name) {
-            info!(
-                "Dropping table '{}' - not in expected schema (schema.rs is source of truth)",
-                table_name
-            );
+    Ok(SyncReport {
+        previous_version,
+        current_version: target.version.clone(),
+        migrated: true,
+        repaired: Vec::new(),
+    })
+}
 
-            // Drop all indexes for this table first
-            let mut index_rows = conn
-                .prepare("SELECT name FROM sqlite_master WHERE type='index' AND tbl_name=? AND name NOT LIKE 'sqlite_%'")
-                .await
-                .map_err(|e| format!("Failed to prepare index drop query: {}", e))?
-                .query(libsql::params![table_name.as_str()])
-                .await
-                .map_err(|e| format!("Failed to get indexes: {}", e))?;
+/// Apply one migration and record its version
+async fn apply_migration(conn: &Connection, migration: &Migration) -> Result<(), String> {
+    info!("Applying schema migration {}: {}", migration.version, migration.description);
+    let tx = conn
+        .transaction()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
-            while let Some(index_row) = index_rows
-                .next()
-                .await
-                .map_err(|e| format!("Failed to get index row: {}", e))?
-            {
-                let index_name: String = index_row
-                    .get(0)
-                    .map_err(|e| format!("Failed to get index name: {}", e))?;
-                conn.execute(&format!("DROP INDEX IF EXISTS {}", index_name), libsql::params![])
-                    .await
-                    .map_err(|e| format!("Failed to drop index {}: {}", index_name, e))?;
-            }
+    for (table, column, definition) in migration.add_columns {
+        let exists = get_table_columns(&tx, table)
+            .await?
+            .iter()
+            .any(|c| c.name == *column);
+        if !exists {
+            tx.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                libsql::params![],
+            )
+            .await
+            .map_err(|e| format!("Failed to add column {}.{}: {}", table, column, e))?;
+        }
+    }
+    for sql in migration.up {
+        tx.execute(sql, libsql::params![])
+            .await
+            .map_err(|e| format!("Failed to apply migration {}: {}", migration.version, e))?;
+    }
+    update_schema_version(&tx, &migration.schema_version()).await?;
 
-            // Drop all triggers for this table
-            let mut trigger_rows = conn
-                .prepare("SELECT name FROM sqlite_master WHERE type='trigger' AND tbl_name=?")
-                .await
-                .map_err(|e| format!("Failed to prepare trigger drop query: {}", e))?
-                .query(libsql::params![table_name.as_str()])
-                .await
-                .map_err(|e| format!("Failed to get triggers: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit migration {}: {}", migration.version, e))
+}
 
-            while let Some(trigger_row) = trigger_rows
-                .next()
-                .await
-                .map_err(|e| format!("Failed to get trigger row: {}", e))?
-            {
-                let trigger_name: String = trigger_row
-                    .get(0)
-                    .map_err(|e| format!("Failed to get trigger name: {}", e))?;
-                conn.execute(
-                    &format!("DROP TRIGGER IF EXISTS {}", trigger_name),
-                    libsql::params![],
-                )
-                .await
-                .map_err(|e| format!("Failed to drop trigger {}: {}", trigger_name, e))?;
-            }
+/// Revert one migration, recording `previous` as the database's version
+async fn revert_migration(conn: &Connection, migration: &Migration, previous: &Migration) -> Result<(), String> {
+    info!("Reverting schema migration {}: {}", migration.version, migration.description);
+    let tx = conn
+        .transaction()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
-            // Drop the table
-            conn.execute(&format!("DROP TABLE IF EXISTS {}", table_name), libsql::params![])
-                .await
-                .map_err(|e| format!("Failed to drop table {}: {}", table_name, e))?;
-        }
+    for sql in migration.down {
+        tx.execute(sql, libsql::params![])
+            .await
+            .map_err(|e| format!("Failed to revert migration {}: {}", migration.version, e))?;
     }
+    update_schema_version(&tx, &previous.schema_version()).await?;
 
-    // Re-enable foreign key constraints
-    conn.execute("PRAGMA foreign_keys = ON", libsql::params![])
+    tx.commit()
         .await
-        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+        .map_err(|e| format!("Failed to commit revert of migration {}: {}", migration.version, e))
+}
 
-    // Update or create expected tables
-    for table_schema in expected_schema {
-        // Check if table exists
-        let table_exists = current_tables.contains(&table_schema.name);
+/// Bring the database's tables in line with `expected_schema`
+///
+/// Missing tables, columns, indexes and triggers are always added. Tables and
+/// columns that aren't expected are only dropped under [`MigrationPolicy::Destructive`]
+/// with `SCHEMA_ALLOW_DROPS` set; otherwise they are logged so an operator can review
+/// them before opting in.
+pub async fn apply_schema_migrations(
+    conn: &Connection,
+    expected_schema: &[TableSchema],
+    policy: MigrationPolicy,
+) -> Result<(), String> {
+    // Tables that are never considered unexpected
+    const PROTECTED_TABLES: &[&str] = &["schema_version", "sqlite_sequence"];
+
+    let current_tables = get_current_tables(conn)
+        .await
+        .map_err(|e| format!("Failed to get current tables: {}", e))?;
 
-        if table_exists {
-            // Update existing table schema
-            update_table_schema(conn, table_schema)
+    let unexpected_tables: Vec<String> = current_tables
+        .iter()
+        .filter(|table| {
+            !PROTECTED_TABLES.contains(&table.as_str())
+                && !expected_schema.iter().any(|schema| &schema.name == *table)
+        })
+        .cloned()
+        .collect();
+    let mut unexpected_columns: Vec<(String, String)> = Vec::new();
+
+    for table_schema in expected_schema {
+        if current_tables.contains(&table_schema.name) {
+            let current_columns = get_table_columns(conn, &table_schema.name)
                 .await
-                .map_err(|e| format!("Failed to update table {}: {}", table_schema.name, e))?;
+                .map_err(|e| format!("Failed to get current columns: {}", e))?;
+
+            for expected_col in &table_schema.columns {
+                if !current_columns.iter().any(|c| c.name == expected_col.name) {
+                    add_column(conn, &table_schema.name, expected_col).await?;
+                }
+            }
+            unexpected_columns.extend(
+                current_columns
+                    .iter()
+                    .filter(|c| !c.is_primary_key && !table_schema.columns.iter().any(|e| e.name == c.name))
+                    .map(|c| (table_schema.name.clone(), c.name.clone())),
+            );
         } else {
-            // Create new table
             create_table(conn, table_schema)
                 .await
                 .map_err(|e| format!("Failed to create table {}: {}", table_schema.name, e))?;
         }
 
-        // Ensure indexes are in place
         ensure_indexes(conn, table_schema)
             .await
             .map_err(|e| format!("Failed to ensure indexes for {}: {}", table_schema.name, e))?;
-
-        // Ensure triggers are in place
         ensure_triggers(conn, table_schema)
             .await
             .map_err(|e| format!("Failed to ensure triggers for {}: {}", table_schema.name, e))?;
     }
 
-    info!("Schema migrations applied successfully");
+    if unexpected_tables.is_empty() && unexpected_columns.is_empty() {
+        return Ok(());
+    }
+    let columns: Vec<String> = unexpected_columns
+        .iter()
+        .map(|(table, column)| format!("{}.{}", table, column))
+        .collect();
+
+    match policy {
+        MigrationPolicy::Safe => {
+            warn!(
+                "Schema has tables {:?} and columns {:?} that aren't in the expected schema; \
+                 leaving them in place (a destructive migration would drop them)",
+                unexpected_tables, columns
+            );
+        }
+        MigrationPolicy::Destructive if !drops_allowed() => {
+            warn!(
+                "Not dropping unexpected tables {:?} and columns {:?}: SCHEMA_ALLOW_DROPS is not set",
+                unexpected_tables, columns
+            );
+        }
+        MigrationPolicy::Destructive => {
+            info!("Dropping unexpected tables {:?} and columns {:?}", unexpected_tables, columns);
+            // Dropping a table also drops its indexes and triggers
+            for table in &unexpected_tables {
+                conn.execute(&format!("DROP TABLE IF EXISTS {}", table), libsql::params![])
+                    .await
+                    .map_err(|e| format!("Failed to drop table {}: {}", table, e))?;
+            }
+            for (table, column) in &unexpected_columns {
+                conn.execute(&format!("ALTER TABLE {} DROP COLUMN {}", table, column), libsql::params![])
+                    .await
+                    .map_err(|e| format!("Failed to drop column {}.{}: {}", table, column, e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Global switch that has to be on (`SCHEMA_ALLOW_DROPS=true`) before any migration
+/// policy may drop tables or columns. Off by default so no deploy can drop data by
+/// accident.
+fn drops_allowed() -> bool {
+    std::env::var("SCHEMA_ALLOW_DROPS")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1"))
+        .unwrap_or(false)
+}
+
+/// Add a missing column. SQLite can't add a NOT NULL column without a default, so
+/// one matching the column type is supplied when the schema has none.
+async fn add_column(conn: &Connection, table: &str, column: &ColumnInfo) -> Result<(), String> {
+    let mut alter_sql = format!("ALTER TABLE {} ADD COLUMN {} {}", table, column.name, column.data_type);
+
+    if !column.is_nullable {
+        if let Some(default) = &column.default_value {
+            alter_sql.push_str(&format!(" NOT NULL DEFAULT {}", default));
+        } else {
+            match column.data_type.to_uppercase().as_str() {
+                "TEXT" | "VARCHAR" => alter_sql.push_str(" NOT NULL DEFAULT ''"),
+                "INTEGER" => alter_sql.push_str(" NOT NULL DEFAULT 0"),
+                "REAL" | "DECIMAL" => alter_sql.push_str(" NOT NULL DEFAULT 0.0"),
+                "BOOLEAN" => alter_sql.push_str(" NOT NULL DEFAULT false"),
+                "DATE" => alter_sql.push_str(" NOT NULL DEFAULT '1970-01-01'"),
+                "TIME" => alter_sql.push_str(" NOT NULL DEFAULT '00:00:00'"),
+                "TIMESTAMP" => alter_sql.push_str(" NOT NULL DEFAULT CURRENT_TIMESTAMP"),
+                _ => alter_sql.push_str(" NOT NULL DEFAULT ''"),
+            }
+        }
+    } else if let Some(default) = &column.default_value {
+        alter_sql.push_str(&format!(" DEFAULT {}", default));
+    }
+
+    conn.execute(&alter_sql, libsql::params![])
+        .await
+        .map_err(|e| format!("Failed to add column {}: {}", column.name, e))?;
     Ok(())
 }
 
@@ -746,6 +1054,7 @@ pub async fn initialize_registry_schema(conn: &Connection) -> Result<(), String>
             db_url TEXT NOT NULL,
             db_token TEXT NOT NULL,
             storage_used_bytes INTEGER DEFAULT 0,
+            provision_status TEXT NOT NULL DEFAULT 'ready',
             created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
             updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
         )
@@ -764,5 +1073,148 @@ pub async fn initialize_registry_schema(conn: &Connection) -> Result<(), String>
         .await
         .map_err(|e| format!("Failed to create email index: {}", e))?;
 
+    // Audit log of actions taken by admins on behalf of other users
+    let create_admin_events_sql = r#"
+        CREATE TABLE IF NOT EXISTS admin_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            admin_user_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            target_user_id TEXT,
+            details TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+    "#;
+
+    conn.execute(create_admin_events_sql, libsql::params![])
+        .await
+        .map_err(|e| format!("Failed to create admin_events table: {}", e))?;
+
     Ok(())
 }
+
+/// Expected schema of the registry database, matching [`initialize_registry_schema`]
+/// plus the columns added to it since
+pub fn get_registry_schema() -> Vec<TableSchema> {
+    vec![
+        TableSchema {
+            name: "user_databases".to_string(),
+            columns: vec![
+                ColumnInfo { name: "user_id".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: true },
+                ColumnInfo { name: "email".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "db_name".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "db_url".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "db_token".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "storage_used_bytes".to_string(), data_type: "INTEGER".to_string(), is_nullable: true, default_value: Some("0".to_string()), is_primary_key: false },
+                ColumnInfo { name: "provision_status".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: Some("'ready'".to_string()), is_primary_key: false },
+                ColumnInfo { name: "created_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: true, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
+                ColumnInfo { name: "updated_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: true, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
+            ],
+            indexes: vec![IndexInfo {
+                name: "idx_user_databases_email".to_string(),
+                table_name: "user_databases".to_string(),
+                columns: vec!["email".to_string()],
+                is_unique: false,
+            }],
+            triggers: vec![],
+            foreign_keys: vec![],
+        },
+        TableSchema {
+            name: "admin_events".to_string(),
+            columns: vec![
+                ColumnInfo { name: "id".to_string(), data_type: "INTEGER".to_string(), is_nullable: false, default_value: None, is_primary_key: true },
+                ColumnInfo { name: "admin_user_id".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "action".to_string(), data_type: "TEXT".to_string(), is_nullable: false, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "target_user_id".to_string(), data_type: "TEXT".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "details".to_string(), data_type: "TEXT".to_string(), is_nullable: true, default_value: None, is_primary_key: false },
+                ColumnInfo { name: "created_at".to_string(), data_type: "TIMESTAMP".to_string(), is_nullable: true, default_value: Some("CURRENT_TIMESTAMP".to_string()), is_primary_key: false },
+            ],
+            indexes: vec![],
+            triggers: vec![],
+            foreign_keys: vec![],
+        },
+    ]
+}
+
+/// Every table, column, index and trigger in the database, as `kind name` entries
+async fn schema_objects(conn: &Connection) -> Result<BTreeSet<String>, String> {
+    let mut objects = BTreeSet::new();
+    let mut rows = conn
+        .query(
+            "SELECT type, name FROM sqlite_master WHERE type IN ('table', 'index', 'trigger') AND name NOT LIKE 'sqlite_%'",
+            libsql::params![],
+        )
+        .await
+        .map_err(|e| format!("Failed to list schema objects: {}", e))?;
+
+    let mut tables = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get schema object row: {}", e))?
+    {
+        let kind: String = row.get(0).map_err(|e| format!("Failed to get object type: {}", e))?;
+        let name: String = row.get(1).map_err(|e| format!("Failed to get object name: {}", e))?;
+        if kind == "table" {
+            tables.push(name.clone());
+        }
+        objects.insert(format!("{} {}", kind, name));
+    }
+
+    for table in tables {
+        for column in get_table_columns(conn, &table).await? {
+            objects.insert(format!("column {}.{}", table, column.name));
+        }
+    }
+    Ok(objects)
+}
+
+/// Re-run registry schema initialization and converge it on [`get_registry_schema`]
+///
+/// Safe to run any number of times: only missing tables, columns and indexes are
+/// created and nothing is ever dropped. The registry isn't versioned, so the report has
+/// no previous version; `repaired` lists what was created and `migrated` is whether
+/// anything was.
+///
+/// ```
+/// use cron_jobs::server::turso::repair_registry_schema;
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+///     let conn = db.connect().unwrap();
+///     repair_registry_schema(&conn).await.unwrap();
+///
+///     conn.execute("DROP INDEX idx_user_databases_email", ()).await.unwrap();
+///     let report = repair_registry_schema(&conn).await.unwrap();
+///     assert_eq!(report.repaired, vec!["index idx_user_databases_email"]);
+///     assert!(report.migrated);
+///
+///     let report = repair_registry_schema(&conn).await.unwrap();
+///     assert!(report.repaired.is_empty());
+/// });
+/// ```
+pub async fn repair_registry_schema(conn: &Connection) -> Result<SyncReport, String> {
+    let before = schema_objects(conn).await?;
+
+    initialize_registry_schema(conn).await?;
+    apply_schema_migrations(conn, &get_registry_schema(), MigrationPolicy::Safe)
+        .await
+        .map_err(|e| format!("Failed to converge registry schema: {}", e))?;
+
+    let repaired: Vec<String> = schema_objects(conn)
+        .await?
+        .difference(&before)
+        .cloned()
+        .collect();
+    if repaired.is_empty() {
+        info!("Registry schema is intact");
+    } else {
+        warn!("Repaired registry schema: created {:?}", repaired);
+    }
+
+    Ok(SyncReport {
+        previous_version: None,
+        current_version: "registry".to_string(),
+        migrated: !repaired.is_empty(),
+        repaired,
+    })
+}
@@ -0,0 +1,185 @@
+use crate::server::actions::helpers::{get_turso_client, require_admin, server_fn_error};
+use crate::server::models::{JobTransferReport, Pagination};
+use crate::server::service::transfer_jobs;
+use crate::server::turso::{
+    get_supabase_user_by_email, AuthError, SchemaVersion, SupabaseUser, SyncReport, TursoConfig, UserDatabaseFilter,
+    UserDatabaseSummary,
+};
+use leptos::prelude::ServerFnError;
+use leptos::server;
+use leptos::server_fn::codec::GetUrl;
+use leptos_actix::extract;
+use log::{error, info};
+
+/// Force a schema sync for a single user's database (admin only)
+#[server(AdminSyncUserSchema, "/api")]
+pub async fn admin_sync_user_schema(user_id: String) -> Result<SyncReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let admin_id = require_admin(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    info!("[Admin] {} forcing schema sync for user {}", admin_id, user_id);
+    let result = client.sync_user_database_schema(&user_id).await;
+    
+    let details = match &result {
+        Ok(report) => format!(
+            "{} -> {} (migrated: {})",
+            report.previous_version.as_deref().unwrap_or("none"),
+            report.current_version,
+            report.migrated
+        ),
+        Err(e) => format!("failed: {}", e),
+    };
+    if let Err(e) = client
+        .record_admin_event(&admin_id, "sync_user_schema", Some(&user_id), Some(&details))
+        .await
+    {
+        error!("[Admin] Failed to record admin event: {}", e);
+    }
+    
+    result.map_err(|e| server_fn_error(&format!("Failed to sync schema for user {}", user_id), e))
+}
+
+/// Re-run registry schema initialization, creating anything missing (admin only).
+/// Never drops registry data; the report lists what was created.
+#[server(RepairRegistry, "/api")]
+pub async fn admin_repair_registry() -> Result<SyncReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let admin_id = require_admin(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    info!("[Admin] {} repairing registry schema", admin_id);
+    let result = client.repair_registry_schema().await;
+    
+    let details = match &result {
+        Ok(report) if report.repaired.is_empty() => "nothing to repair".to_string(),
+        Ok(report) => format!("created {}", report.repaired.join(", ")),
+        Err(e) => format!("failed: {}", e),
+    };
+    if let Err(e) = client
+        .record_admin_event(&admin_id, "repair_registry", None, Some(&details))
+        .await
+    {
+        error!("[Admin] Failed to record admin event: {}", e);
+    }
+    
+    result.map_err(|e| server_fn_error("Failed to repair registry schema", e))
+}
+
+/// Move jobs from one user to another, e.g. for a team handoff (admin only).
+/// Each job is copied with a new id; with `delete_originals` the copied originals are
+/// moved to the source user's trash.
+#[server(AdminTransferJobs, "/api")]
+pub async fn admin_transfer_jobs(
+    from_user_id: String,
+    to_user_id: String,
+    job_ids: Vec<String>,
+    delete_originals: bool,
+) -> Result<JobTransferReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let admin_id = require_admin(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    info!("[Admin] {} transferring {} jobs from user {} to user {}", admin_id, job_ids.len(), from_user_id, to_user_id);
+    let result = transfer_jobs(&client, &from_user_id, &to_user_id, job_ids, delete_originals).await;
+    
+    let details = match &result {
+        Ok(report) => format!(
+            "to {}: {} transferred, {} failed, {} originals trashed{}",
+            to_user_id,
+            report.transferred,
+            report.failed,
+            report.originals_trashed,
+            report.error.as_ref().map(|e| format!(", stopped early: {}", e)).unwrap_or_default()
+        ),
+        Err(e) => format!("to {}: failed: {}", to_user_id, e),
+    };
+    if let Err(e) = client
+        .record_admin_event(&admin_id, "transfer_jobs", Some(&from_user_id), Some(&details))
+        .await
+    {
+        error!("[Admin] Failed to record admin event: {}", e);
+    }
+    
+    result.map_err(|e| server_fn_error("Failed to transfer jobs", e))
+}
+
+/// Schema versions recorded in a user's database, oldest first (admin only)
+#[server(name = AdminUserSchemaHistory, prefix = "/api", input = GetUrl)]
+pub async fn admin_user_schema_history(user_id: String) -> Result<Vec<SchemaVersion>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    require_admin(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    client
+        .get_user_schema_history(&user_id)
+        .await
+        .map_err(|e| server_fn_error(&format!("Failed to get schema history for user {}", user_id), e))
+}
+
+/// Look up a Supabase user by email for support (admin only, sensitive fields redacted)
+#[server(name = AdminLookupUser, prefix = "/api", input = GetUrl)]
+pub async fn admin_lookup_user(email: String) -> Result<SupabaseUser, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let admin_id = require_admin(&req).await?;
+    let client = get_turso_client(&req)?;
+    let config = TursoConfig::from_env()
+        .map_err(|e| ServerFnError::new(format!("Config error: {}", e)))?;
+    
+    info!("[Admin] {} looking up user by email", admin_id);
+    let result = get_supabase_user_by_email(email.trim(), &config.supabase).await;
+    
+    let (target, details) = match &result {
+        Ok(user) => (Some(user.id.as_str()), "found".to_string()),
+        Err(e) => (None, format!("failed: {}", e)),
+    };
+    if let Err(e) = client
+        .record_admin_event(&admin_id, "lookup_user", target, Some(&details))
+        .await
+    {
+        error!("[Admin] Failed to record admin event: {}", e);
+    }
+    
+    result.map_err(|e| match e {
+        AuthError::UserNotFound(_) => ServerFnError::new(format!("User not found: {}", email.trim())),
+        e => ServerFnError::new(format!("Failed to look up user: {}", e)),
+    })
+}
+
+/// List user databases, optionally by provisioning status and creation window (admin only)
+///
+/// The `limit`/`offset` of `filter` are replaced by `pagination`, which applies the
+/// usual default and maximum page size. Credentials are never returned.
+#[server(name = AdminListUserDatabases, prefix = "/api", input = GetUrl)]
+pub async fn admin_list_user_databases(
+    #[server(default)] filter: UserDatabaseFilter,
+    #[server(default)] pagination: Pagination,
+) -> Result<Vec<UserDatabaseSummary>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    require_admin(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    let filter = UserDatabaseFilter {
+        limit: Some(pagination.limit()),
+        offset: Some(pagination.offset()),
+        ..filter
+    };
+    
+    client
+        .list_user_databases(&filter)
+        .await
+        .map(|entries| entries.into_iter().map(UserDatabaseSummary::from).collect())
+        .map_err(|e| server_fn_error("Failed to list user databases", e))
+}
@@ -0,0 +1,35 @@
+use crate::server::actions::helpers::server_fn_error;
+use crate::server::error::ServerError;
+use crate::server::middleware::RateLimiter;
+use crate::server::models::BuildMetadata;
+use leptos::prelude::ServerFnError;
+use leptos::server;
+use leptos::server_fn::codec::GetUrl;
+use leptos_actix::extract;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Build info calls allowed per client IP each minute
+static BUILD_INFO_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(30, Duration::from_secs(60)));
+
+/// Crate version, schema version, git commit and build time of the running server.
+/// Needs no login, but is rate limited per client IP.
+#[server(name = BuildInfo, prefix = "/api", input = GetUrl)]
+pub async fn build_info_action() -> Result<BuildMetadata, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let client_ip = req
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    if !BUILD_INFO_LIMITER.check(&client_ip) {
+        return Err(server_fn_error(
+            "Failed to get build info",
+            ServerError::RateLimited("Too many requests, try again later".to_string()),
+        ));
+    }
+    
+    Ok(BuildMetadata::current())
+}
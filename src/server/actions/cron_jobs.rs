@@ -1,21 +1,66 @@
-use crate::server::actions::helpers::{get_user_id_from_request, get_turso_client};
-use crate::server::models::{CronJob, CreateCronJobRequest, UpdateCronJobRequest};
-use crate::server::service::{create_cron_job, delete_cron_job, get_user_cron_jobs, update_cron_job};
+use crate::server::actions::helpers::{get_turso_client, get_user_id_from_request, server_fn_error};
+use crate::server::models::{
+    AccountImportMode, ActivityCursor, ActivityFilter, ActivityPage, BackupImportReport,
+    BatchResult, Bucket, CronJob, CronJobStats, CronJobPage, CreateCronJobRequest,
+    CrontabImportReport, ImportCronJobRow, ImportImpact, ImportReport, JobLint, JobRun,
+    ListCronJobsFilter, Pagination, RunBucket, UpdateCronJobRequest, UrlCheck,
+};
+use crate::server::service::{
+    bulk_update_enabled, check_batch_size, clone_cron_job, create_cron_job, delete_cron_job,
+    delete_cron_jobs, estimate_import_impact, export_account, export_crontab, export_jobs_json,
+    get_activity_feed, get_cron_job_stats, get_cron_jobs_by_ids, get_job_runs, get_run_summary,
+    get_user_cron_jobs, import_cron_jobs, import_account, import_crontab, import_jobs_json,
+    lint_cron_jobs, list_job_env_keys, list_trashed_cron_jobs, purge_cron_job, purge_deleted,
+    restore_cron_job, run_job_now, set_job_env, test_command_url, update_cron_job,
+};
 use leptos::prelude::ServerFnError;
 use leptos::server;
+use leptos::server_fn::codec::GetUrl;
 use leptos_actix::extract;
 
-#[server(GetCronJobs, "/api")]
-pub async fn get_cron_jobs() -> Result<Vec<CronJob>, ServerFnError> {
+// Struct arguments whose fields are all unset are left out of the query string
+#[server(name = GetCronJobs, prefix = "/api", input = GetUrl)]
+pub async fn get_cron_jobs(
+    #[server(default)] filter: ListCronJobsFilter,
+    #[server(default)] pagination: Pagination,
+) -> Result<CronJobPage, ServerFnError> {
     let req = extract::<actix_web::HttpRequest>().await
         .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
     
     let user_id = get_user_id_from_request(&req).await?;
     let client = get_turso_client(&req)?;
     
-    get_user_cron_jobs(&client, &user_id)
+    get_user_cron_jobs(&client, &user_id, filter, pagination)
         .await
-        .map_err(|e| ServerFnError::new(format!("Failed to get cron jobs: {}", e)))
+        .map_err(|e| server_fn_error("Failed to get cron jobs", e))
+}
+
+/// Total, enabled and disabled job counts for the dashboard header
+#[server(name = GetCronJobStats, prefix = "/api", input = GetUrl)]
+pub async fn get_cron_job_stats_action() -> Result<CronJobStats, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    get_cron_job_stats(&client, &user_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to get cron job stats", e))
+}
+
+/// Fetch specific jobs, e.g. the selected rows, without reloading the whole list
+#[server(name = GetCronJobsByIds, prefix = "/api", input = GetUrl)]
+pub async fn get_cron_jobs_by_ids_action(#[server(default)] job_ids: Vec<String>) -> Result<Vec<CronJob>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    get_cron_jobs_by_ids(&client, &user_id, job_ids)
+        .await
+        .map_err(|e| server_fn_error("Failed to get cron jobs", e))
 }
 
 #[server(CreateCronJob, "/api")]
@@ -28,7 +73,7 @@ pub async fn create_cron_job_action(request: CreateCronJobRequest) -> Result<Cro
     
     create_cron_job(&client, &user_id, request)
         .await
-        .map_err(|e| ServerFnError::new(format!("Failed to create cron job: {}", e)))
+        .map_err(|e| server_fn_error("Failed to create cron job", e))
 }
 
 #[server(UpdateCronJob, "/api")]
@@ -44,7 +89,21 @@ pub async fn update_cron_job_action(
     
     update_cron_job(&client, &user_id, &job_id, request)
         .await
-        .map_err(|e| ServerFnError::new(format!("Failed to update cron job: {}", e)))
+        .map_err(|e| server_fn_error("Failed to update cron job", e))
+}
+
+/// Copy a cron job under a new name; the copy starts disabled
+#[server(CloneCronJob, "/api")]
+pub async fn clone_cron_job_action(job_id: String, new_name: String) -> Result<CronJob, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    clone_cron_job(&client, &user_id, &job_id, new_name)
+        .await
+        .map_err(|e| server_fn_error("Failed to clone cron job", e))
 }
 
 #[server(DeleteCronJob, "/api")]
@@ -57,6 +116,319 @@ pub async fn delete_cron_job_action(job_id: String) -> Result<(), ServerFnError>
     
     delete_cron_job(&client, &user_id, &job_id)
         .await
-        .map_err(|e| ServerFnError::new(format!("Failed to delete cron job: {}", e)))
+        .map_err(|e| server_fn_error("Failed to delete cron job", e))
 }
 
+#[server(DeleteCronJobs, "/api")]
+pub async fn delete_cron_jobs_action(job_ids: Vec<String>) -> Result<BatchResult, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    check_batch_size(&job_ids).map_err(|e| server_fn_error("Failed to delete cron jobs", e))?;
+    let client = get_turso_client(&req)?;
+    
+    delete_cron_jobs(&client, &user_id, job_ids)
+        .await
+        .map_err(|e| server_fn_error("Failed to delete cron jobs", e))
+}
+
+#[server(BulkToggleCronJobs, "/api")]
+pub async fn bulk_toggle_cron_jobs(job_ids: Vec<String>, enabled: bool) -> Result<BatchResult, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    check_batch_size(&job_ids).map_err(|e| server_fn_error("Failed to update cron jobs", e))?;
+    let client = get_turso_client(&req)?;
+    
+    bulk_update_enabled(&client, &user_id, &job_ids, enabled)
+        .await
+        .map_err(|e| server_fn_error("Failed to update cron jobs", e))
+}
+
+#[server(name = ExportCrontab, prefix = "/api", input = GetUrl)]
+pub async fn export_crontab_action() -> Result<String, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    export_crontab(&client, &user_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to export crontab", e))
+}
+
+#[server(ImportCronJobs, "/api")]
+pub async fn import_cron_jobs_action(
+    rows: Vec<ImportCronJobRow>,
+    dry_run: bool,
+) -> Result<ImportReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    import_cron_jobs(&client, &user_id, rows, dry_run)
+        .await
+        .map_err(|e| server_fn_error("Failed to import cron jobs", e))
+}
+
+#[server(ImportCrontab, "/api")]
+pub async fn import_crontab_action(text: String, dry_run: bool) -> Result<CrontabImportReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    import_crontab(&client, &user_id, &text, dry_run)
+        .await
+        .map_err(|e| server_fn_error("Failed to import crontab", e))
+}
+
+#[server(name = ExportJobsJson, prefix = "/api", input = GetUrl)]
+pub async fn export_jobs_json_action() -> Result<String, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    export_jobs_json(&client, &user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to export cron jobs: {}", e)))
+}
+
+#[server(ImportJobsJson, "/api")]
+pub async fn import_jobs_json_action(json: String) -> Result<BackupImportReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    import_jobs_json(&client, &user_id, &json)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to restore cron jobs: {}", e)))
+}
+
+#[server(name = ExportAccount, prefix = "/api", input = GetUrl)]
+pub async fn export_account_action() -> Result<String, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    export_account(&client, &user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to export account: {}", e)))
+}
+
+#[server(ImportAccount, "/api")]
+pub async fn import_account_action(
+    json: String,
+    mode: AccountImportMode,
+) -> Result<BackupImportReport, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    import_account(&client, &user_id, &json, mode)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to import account: {}", e)))
+}
+
+#[server(EstimateImportImpact, "/api")]
+pub async fn estimate_import_impact_action(
+    rows: Vec<ImportCronJobRow>,
+) -> Result<ImportImpact, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    estimate_import_impact(&client, &user_id, &rows)
+        .await
+        .map_err(|e| server_fn_error("Failed to estimate import impact", e))
+}
+
+#[server(RestoreCronJob, "/api")]
+pub async fn restore_cron_job_action(job_id: String) -> Result<CronJob, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    restore_cron_job(&client, &user_id, &job_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to restore cron job", e))
+}
+
+#[server(name = GetTrashedCronJobs, prefix = "/api", input = GetUrl)]
+pub async fn get_trashed_cron_jobs() -> Result<Vec<CronJob>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    list_trashed_cron_jobs(&client, &user_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to get trashed cron jobs", e))
+}
+
+#[server(PurgeCronJob, "/api")]
+pub async fn purge_cron_job_action(job_id: String) -> Result<(), ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    purge_cron_job(&client, &user_id, &job_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to purge cron job", e))
+}
+
+#[server(PurgeDeletedJobs, "/api")]
+pub async fn purge_deleted_jobs(retention_days: u32) -> Result<u64, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    purge_deleted(&client, &user_id, chrono::Duration::days(i64::from(retention_days)))
+        .await
+        .map_err(|e| server_fn_error("Failed to purge deleted cron jobs", e))
+}
+
+#[server(name = GetJobRuns, prefix = "/api", input = GetUrl)]
+pub async fn get_job_runs_action(
+    job_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<JobRun>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    get_job_runs(&client, &user_id, &job_id, limit)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to get job runs: {}", e)))
+}
+
+/// Newest-first page of the user's job changes and runs; pass `next_cursor` back
+/// for the following page
+#[server(name = GetActivityFeed, prefix = "/api", input = GetUrl)]
+pub async fn get_activity_feed_action(
+    #[server(default)] filter: ActivityFilter,
+    cursor: Option<ActivityCursor>,
+    limit: Option<u32>,
+) -> Result<ActivityPage, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    get_activity_feed(&client, &user_id, &filter, cursor, limit)
+        .await
+        .map_err(|e| server_fn_error("Failed to get activity feed", e))
+}
+
+/// Store an environment variable for a job's runs, encrypted at rest. The value can't be
+/// read back through the API.
+#[server(SetJobEnv, "/api")]
+pub async fn set_job_env_action(job_id: String, key: String, value: String) -> Result<(), ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    set_job_env(&client, &user_id, &job_id, &key, &value)
+        .await
+        .map_err(|e| server_fn_error("Failed to set job environment variable", e))
+}
+
+#[server(name = ListJobEnvKeys, prefix = "/api", input = GetUrl)]
+pub async fn list_job_env_keys_action(job_id: String) -> Result<Vec<String>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    list_job_env_keys(&client, &user_id, &job_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to list job environment variables", e))
+}
+
+#[server(RunCronJobNow, "/api")]
+pub async fn run_cron_job_now(
+    job_id: String,
+    allow_disabled: bool,
+) -> Result<JobRun, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    run_job_now(&client, &user_id, &job_id, allow_disabled)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to run cron job: {}", e)))
+}
+
+#[server(name = GetRunSummary, prefix = "/api", input = GetUrl)]
+pub async fn get_run_summary_action(
+    job_id: String,
+    bucket: Bucket,
+) -> Result<Vec<RunBucket>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    get_run_summary(&client, &user_id, &job_id, bucket)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to get run summary: {}", e)))
+}
+
+#[server(name = LintJobs, prefix = "/api", input = GetUrl)]
+pub async fn lint_jobs() -> Result<Vec<JobLint>, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+    
+    lint_cron_jobs(&client, &user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to lint cron jobs: {}", e)))
+}
+
+/// Probe the URL of an HTTP job before it is saved. Nothing is recorded.
+#[server(TestCommandUrl, "/api")]
+pub async fn test_command_url_action(url: String) -> Result<UrlCheck, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+    
+    // Only signed-in users may make the server send requests
+    get_user_id_from_request(&req).await?;
+    
+    test_command_url(&url)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to test command URL: {}", e)))
+}
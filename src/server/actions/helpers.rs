@@ -1,26 +1,47 @@
-use crate::server::turso::{get_supabase_user_id, TursoClient, TursoConfig};
+use crate::context::UNAUTHORIZED_PREFIX;
+use crate::server::error::ServerError;
+use crate::server::turso::{
+    bearer_token, get_supabase_claims, AuthError, SupabaseClaims, TursoClient, TursoConfig,
+};
 use leptos::prelude::ServerFnError;
 use std::sync::Arc;
 
-/// Helper to get user_id from request by validating JWT token
-pub async fn get_user_id_from_request(req: &actix_web::HttpRequest) -> Result<String, ServerFnError> {
+/// Helper to get validated JWT claims from request. Only user tokens (role
+/// `authenticated`) are accepted.
+pub async fn get_claims_from_request(req: &actix_web::HttpRequest) -> Result<SupabaseClaims, ServerFnError> {
     let auth_header = req.headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| ServerFnError::new("Authorization header missing"))?;
+        .ok_or_else(|| unauthorized_error("Authorization header missing"))?;
     
-    let token = auth_header
-        .strip_prefix("Bearer ")
-        .ok_or_else(|| ServerFnError::new("Invalid authorization format"))?;
+    let token = bearer_token(auth_header)
+        .map_err(|e| auth_error("Invalid authorization header", e))?;
     
     let config = TursoConfig::from_env()
         .map_err(|e| ServerFnError::new(format!("Config error: {}", e)))?;
     
-    let user_id = get_supabase_user_id(token, &config.supabase)
+    get_supabase_claims(token, &config.supabase)
         .await
-        .map_err(|e| ServerFnError::new(format!("JWT validation failed: {}", e)))?;
-    
-    Ok(user_id)
+        .map_err(|e| auth_error("JWT validation failed", e))
+}
+
+/// Helper to get user_id from request by validating JWT token
+pub async fn get_user_id_from_request(req: &actix_web::HttpRequest) -> Result<String, ServerFnError> {
+    Ok(get_claims_from_request(req).await?.sub)
+}
+
+/// Helper to get the admin's user_id from request, rejecting non-admin callers with 403
+pub async fn require_admin(req: &actix_web::HttpRequest) -> Result<String, ServerFnError> {
+    let claims = get_claims_from_request(req).await?;
+    admin_id(claims).map_err(|e| server_fn_error("Admin role required", e))
+}
+
+/// The user id of `claims` if they carry the admin role
+fn admin_id(claims: SupabaseClaims) -> Result<String, ServerError> {
+    if !claims.is_admin() {
+        return Err(ServerError::Forbidden(format!("User {} is not an admin", claims.sub)));
+    }
+    Ok(claims.sub)
 }
 
 /// Helper to get TursoClient from app data
@@ -30,3 +51,121 @@ pub fn get_turso_client(req: &actix_web::HttpRequest) -> Result<Arc<TursoClient>
         .map(|data| data.get_ref().clone())
 }
 
+
+/// Set the status of the current server function response, if there is one
+fn set_response_status(code: u16) {
+    if let Some(response) = leptos::prelude::use_context::<leptos_actix::ResponseOptions>() {
+        if let Ok(status) = actix_web::http::StatusCode::from_u16(code) {
+            response.set_status(status);
+        }
+    }
+}
+
+/// Helper to turn a server layer error into a `ServerFnError`, setting the response
+/// status to match the kind of error (404 for missing jobs, 403 for quota, ...).
+/// Server-side failures are logged, tagged with the request's trace id. Only
+/// [`unauthorized_error`] marks a message as a rejected token, so a refused operation
+/// doesn't end the caller's session.
+pub fn server_fn_error(context: &str, error: ServerError) -> ServerFnError {
+    let status = error.status_code();
+    if status >= 500 {
+        log::error!("{}: {}", context, error);
+    } else {
+        log::info!("{}: {}", context, error);
+    }
+    set_response_status(status);
+    ServerFnError::new(format!("{}: {}", context, error))
+}
+
+/// Helper for a missing or invalid access token. Answers 401 so the auth rate limiter
+/// can count the failure, and marks the message so the browser knows to refresh or end
+/// the session.
+pub fn unauthorized_error(message: impl Into<String>) -> ServerFnError {
+    set_response_status(ServerError::Unauthorized(String::new()).status_code());
+    ServerFnError::new(format!("{}{}", UNAUTHORIZED_PREFIX, message.into()))
+}
+
+/// The server layer error for a failed token check. Only a token Supabase's keys reject
+/// is 401; a JWKS or admin API that is down or too slow is the server's problem, so it
+/// answers 503/504 and neither counts as a failed sign-in nor ends the session.
+fn auth_server_error(error: AuthError) -> ServerError {
+    match error {
+        AuthError::DeadlineExceeded(msg) => ServerError::DeadlineExceeded(msg),
+        AuthError::JwksFetchError(_) | AuthError::AdminRequestFailed(_) => {
+            ServerError::Unavailable(error.to_string())
+        }
+        AuthError::AdminUnauthorized(_) => ServerError::TursoApi(error.to_string()),
+        AuthError::MissingToken
+        | AuthError::ValidationFailed(_)
+        | AuthError::InvalidFormat
+        | AuthError::Expired => ServerError::Unauthorized(error.to_string()),
+        AuthError::UserNotFound(_) => ServerError::NotFound(error.to_string()),
+    }
+}
+
+/// Helper for a failed token check: 401 for a bad token, 503 when the JWKS couldn't be
+/// fetched and 504 when the fetch ran out of time
+pub fn auth_error(context: &str, error: AuthError) -> ServerFnError {
+    match auth_server_error(error) {
+        ServerError::Unauthorized(msg) => unauthorized_error(format!("{}: {}", context, msg)),
+        error => server_fn_error(context, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::is_unauthorized;
+
+    fn claims(app_metadata: serde_json::Value) -> SupabaseClaims {
+        SupabaseClaims {
+            sub: "user-1".to_string(),
+            email: None,
+            aud: "authenticated".to_string(),
+            role: "authenticated".to_string(),
+            exp: 0,
+            iat: 0,
+            app_metadata,
+        }
+    }
+
+    #[test]
+    fn admins_are_let_through() {
+        let admin = claims(serde_json::json!({ "role": "admin" }));
+        assert_eq!(admin_id(admin), Ok("user-1".to_string()));
+    }
+
+    #[test]
+    fn other_users_are_forbidden() {
+        for metadata in [serde_json::json!({}), serde_json::json!({ "role": "support" })] {
+            let error = admin_id(claims(metadata)).unwrap_err();
+            assert!(matches!(error, ServerError::Forbidden(_)), "{:?}", error);
+            assert_eq!(error.status_code(), 403);
+        }
+        let error = server_fn_error("Admin role required", ServerError::Forbidden("no".into()));
+        assert!(!is_unauthorized(&error));
+    }
+
+    #[test]
+    fn rejected_tokens_are_unauthorized() {
+        for error in [AuthError::Expired, AuthError::InvalidFormat, AuthError::ValidationFailed("bad signature".into())] {
+            assert_eq!(auth_server_error(error).status_code(), 401);
+        }
+        assert!(is_unauthorized(&auth_error("JWT validation failed", AuthError::Expired)));
+    }
+
+    #[test]
+    fn upstream_failures_are_not_unauthorized() {
+        let fetch = || AuthError::JwksFetchError("HTTP 502".into());
+        assert_eq!(auth_server_error(fetch()).status_code(), 503);
+        assert_eq!(auth_server_error(AuthError::DeadlineExceeded("JWKS fetch".into())).status_code(), 504);
+        assert!(!is_unauthorized(&auth_error("JWT validation failed", fetch())));
+    }
+
+    #[test]
+    fn only_rejected_tokens_are_marked_unauthorized() {
+        let refused = server_fn_error("Failed to delete job", ServerError::Unauthorized("Not your job".into()));
+        assert!(!is_unauthorized(&refused));
+        assert!(is_unauthorized(&unauthorized_error("Authorization header missing")));
+    }
+}
@@ -1,8 +1,14 @@
+pub mod admin;
+pub mod build_info;
 pub mod cron_jobs;
+pub mod timezone;
 pub mod user;
 pub mod helpers;
 
 // Re-export all public server functions
+pub use admin::*;
+pub use build_info::*;
 pub use cron_jobs::*;
+pub use timezone::*;
 pub use user::*;
 
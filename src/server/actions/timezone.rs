@@ -0,0 +1,10 @@
+use crate::server::service::normalize_timezone;
+use leptos::prelude::ServerFnError;
+use leptos::server;
+use leptos::server_fn::codec::GetUrl;
+
+/// Validate a user-entered timezone and return its canonical IANA name
+#[server(name = NormalizeTimezone, prefix = "/api", input = GetUrl)]
+pub async fn normalize_timezone_action(tz: String) -> Result<String, ServerFnError> {
+    normalize_timezone(&tz).map_err(ServerFnError::new)
+}
@@ -1,44 +1,120 @@
-use crate::server::actions::helpers::{get_user_id_from_request, get_turso_client};
-use crate::server::service::create_user_database;
+use crate::context::DatabaseInfo;
+use crate::server::actions::helpers::{
+    get_claims_from_request, get_turso_client, get_user_id_from_request, server_fn_error,
+    unauthorized_error,
+};
+use crate::server::error::ServerError;
+use crate::server::models::AccountUsage;
+use crate::server::service::{create_user_database, delete_user_database, get_account_usage, get_database_info};
+use crate::server::turso::ProvisionStatus;
 use leptos::prelude::ServerFnError;
 use leptos::server;
+use leptos::server_fn::codec::GetUrl;
 use leptos_actix::extract;
-use log::{info, error};
+use log::{info, error, warn};
 
+/// Create the authenticated user's database, or sync its schema if it exists.
+/// `region` (a Turso location code such as `fra`) overrides `TURSO_REGION` for a new
+/// database. The email recorded for it is the one in the verified access token.
 #[server(CreateUserDatabase, "/api")]
-pub async fn create_user_database_action(email: String) -> Result<String, ServerFnError> {
+pub async fn create_user_database_action(region: Option<String>) -> Result<String, ServerFnError> {
     let req = extract::<actix_web::HttpRequest>().await
         .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
     
-    let user_id = get_user_id_from_request(&req).await?;
+    let claims = get_claims_from_request(&req).await?;
+    let user_id = claims.sub;
+    let email = claims.email.ok_or_else(|| unauthorized_error("Access token has no email"))?;
     info!("[Database Setup] Checking database for user: {} ({})", user_id, email);
     
     let client = get_turso_client(&req)?;
     
     // Check if user database already exists
     match client.get_user_database_entry(&user_id).await {
-        Ok(_) => {
+        Ok(entry) if entry.provision_status == ProvisionStatus::Ready => {
             info!("[Database Setup] Database exists for user {}, syncing schema...", user_id);
             client.sync_user_database_schema(&user_id)
                 .await
                 .map_err(|e| {
                     error!("[Database Setup] Failed to sync schema for user {}: {}", user_id, e);
-                    ServerFnError::new(format!("Failed to sync user database schema: {}", e))
+                    server_fn_error("Failed to sync user database schema", e)
                 })?;
             info!("[Database Setup] Schema sync completed successfully for user {}", user_id);
+            // Keep the stored usage fresh for quota checks; a stale value is not fatal
+            if let Err(e) = client.refresh_storage_usage(&user_id).await {
+                warn!("[Database Setup] Failed to refresh storage usage for user {}: {}", user_id, e);
+            }
             Ok("Database schema updated".to_string())
         }
-        Err(_) => {
+        Ok(_) | Err(ServerError::NotFound(_)) => {
+            // No database yet, or an earlier attempt was interrupted; provisioning
+            // resumes from the last step recorded in the registry
             info!("[Database Setup] Creating new database for user: {} ({})", user_id, email);
-    create_user_database(&client, &user_id, &email)
-        .await
+            create_user_database(&client, &user_id, &email, region.as_deref())
+                .await
                 .map_err(|e| {
                     error!("[Database Setup] Failed to create database for user {}: {}", user_id, e);
-                    ServerFnError::new(format!("Failed to create user database: {}", e))
+                    server_fn_error("Failed to create user database", e)
                 })?;
             info!("[Database Setup] Database created successfully for user: {} ({})", user_id, email);
             Ok("Database created".to_string())
         }
+        // A registry failure says nothing about whether the database exists, and
+        // provisioning on top of it could replace a ready entry
+        Err(e) => {
+            error!("[Database Setup] Failed to look up database for user {}: {}", user_id, e);
+            Err(server_fn_error("Failed to look up user database", e))
+        }
     }
 }
 
+
+/// Permanently delete the authenticated user's database, e.g. on account deletion.
+/// Repeating the call after the database is gone succeeds.
+#[server(DeleteUserDatabase, "/api")]
+pub async fn delete_user_database_action() -> Result<(), ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+
+    info!("[Database Teardown] Deleting database for user: {}", user_id);
+    delete_user_database(&client, &user_id)
+        .await
+        .map_err(|e| {
+            error!("[Database Teardown] Failed to delete database for user {}: {}", user_id, e);
+            server_fn_error("Failed to delete user database", e)
+        })?;
+    info!("[Database Teardown] Database deleted for user: {}", user_id);
+    Ok(())
+}
+
+/// Name, region, creation date and storage use of the authenticated user's database.
+/// Credentials are never included.
+#[server(name = GetMyDatabaseInfo, prefix = "/api", input = GetUrl)]
+pub async fn get_my_database_info() -> Result<DatabaseInfo, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+
+    get_database_info(&client, &user_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to get database info", e))
+}
+
+/// Job count, storage and retained runs of the authenticated user with the limits that
+/// apply, for usage meters. Storage is cached briefly rather than fetched on every call.
+#[server(name = GetAccountUsage, prefix = "/api", input = GetUrl)]
+pub async fn get_account_usage_action() -> Result<AccountUsage, ServerFnError> {
+    let req = extract::<actix_web::HttpRequest>().await
+        .map_err(|e| ServerFnError::new(format!("Failed to extract request: {}", e)))?;
+
+    let user_id = get_user_id_from_request(&req).await?;
+    let client = get_turso_client(&req)?;
+
+    get_account_usage(&client, &user_id)
+        .await
+        .map_err(|e| server_fn_error("Failed to get account usage", e))
+}
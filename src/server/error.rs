@@ -0,0 +1,71 @@
+/// Error returned by the server layer.
+///
+/// Keeps the kind of failure so a server function can answer "not found" differently
+/// from "database down" or "quota exceeded".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerError {
+    /// The record doesn't exist or belongs to another user
+    NotFound(String),
+    /// The caller may not perform the operation
+    Unauthorized(String),
+    /// The caller is signed in but lacks the role the operation needs, e.g. admin
+    Forbidden(String),
+    /// The request is invalid, e.g. a bad schedule, timezone or command
+    Validation(String),
+    /// A registry or user database query failed
+    Database(String),
+    /// A call to the Turso platform API failed
+    TursoApi(String),
+    /// The user's storage quota is used up
+    QuotaExceeded(String),
+    /// The caller sent too many requests and should retry later
+    RateLimited(String),
+    /// A dependency didn't answer before the request's deadline
+    DeadlineExceeded(String),
+    /// A dependency, e.g. the Supabase JWKS endpoint, failed or couldn't be reached
+    Unavailable(String),
+}
+
+impl ServerError {
+    /// HTTP status a server function should answer with for this error
+    pub fn status_code(&self) -> u16 {
+        match self {
+            ServerError::NotFound(_) => 404,
+            ServerError::Unauthorized(_) => 401,
+            ServerError::Forbidden(_) => 403,
+            ServerError::Validation(_) => 400,
+            ServerError::Database(_) => 500,
+            ServerError::TursoApi(_) => 502,
+            ServerError::QuotaExceeded(_) => 403,
+            ServerError::RateLimited(_) => 429,
+            ServerError::DeadlineExceeded(_) => 504,
+            ServerError::Unavailable(_) => 503,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerError::NotFound(msg)
+            | ServerError::Unauthorized(msg)
+            | ServerError::Forbidden(msg)
+            | ServerError::Validation(msg)
+            | ServerError::Database(msg)
+            | ServerError::TursoApi(msg)
+            | ServerError::QuotaExceeded(msg)
+            | ServerError::RateLimited(msg)
+            | ServerError::DeadlineExceeded(msg)
+            | ServerError::Unavailable(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+/// Lets code that still reports `String` errors use `?` on server layer calls
+impl From<ServerError> for String {
+    fn from(error: ServerError) -> Self {
+        error.to_string()
+    }
+}
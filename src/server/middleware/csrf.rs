@@ -0,0 +1,131 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{error, Error};
+use std::env;
+
+/// Name of the cookie holding the CSRF token
+pub const CSRF_COOKIE: &str = "csrf_token";
+/// Header that must echo the cookie value on mutating server function calls
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// CSRF protection settings
+///
+/// Deployments that only authenticate with a bearer header are not exposed to CSRF and
+/// can leave this disabled.
+#[derive(Debug, Clone)]
+pub struct CsrfConfig {
+    pub enabled: bool,
+}
+
+impl CsrfConfig {
+    /// Read `CSRF_PROTECTION` (`true`/`1` to enable, disabled by default)
+    pub fn from_env() -> Self {
+        let enabled = env::var("CSRF_PROTECTION")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1"))
+            .unwrap_or(false);
+        Self { enabled }
+    }
+}
+
+/// Methods that must not change state, and so skip the CSRF check. Read-only server
+/// functions are declared with `input = GetUrl`.
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Build the `Set-Cookie` value that issues a CSRF token
+pub fn csrf_cookie(token: &str) -> String {
+    format!("{}={}; Path=/; SameSite=Strict; Secure", CSRF_COOKIE, token)
+}
+
+/// Double-submit CSRF check for server functions.
+///
+/// Calls under `/api` other than GET, HEAD and OPTIONS must send the `X-CSRF-Token`
+/// header with the same value as the `csrf_token` cookie issued at login.
+pub async fn csrf_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if !is_safe_method(req.method()) && req.path().starts_with("/api/") {
+        let cookie = req.cookie(CSRF_COOKIE).map(|c| c.value().to_string());
+        let header = req
+            .headers()
+            .get(CSRF_HEADER)
+            .and_then(|h| h.to_str().ok());
+
+        match (cookie, header) {
+            (Some(cookie), Some(header)) if !cookie.is_empty() && cookie == header => {}
+            _ => return Err(error::ErrorForbidden("CSRF token missing or invalid")),
+        }
+    }
+
+    next.call(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::cookie::Cookie;
+    use actix_web::http::StatusCode;
+    use actix_web::middleware::from_fn;
+    use actix_web::{test, web, App, HttpResponse};
+
+    async fn call(req: test::TestRequest) -> StatusCode {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(csrf_middleware))
+                .route("/api/{name}", web::to(HttpResponse::Ok))
+                .route("/login", web::to(HttpResponse::Ok)),
+        )
+        .await;
+        match test::try_call_service(&app, req.to_request()).await {
+            Ok(response) => response.status(),
+            Err(e) => e.as_response_error().status_code(),
+        }
+    }
+
+    fn mutation() -> test::TestRequest {
+        test::TestRequest::post().uri("/api/delete_cron_job")
+    }
+
+    #[actix_web::test]
+    async fn mutation_without_token_is_rejected() {
+        assert_eq!(call(mutation()).await, StatusCode::FORBIDDEN);
+        let cookie_only = mutation().cookie(Cookie::new(CSRF_COOKIE, "token-1"));
+        assert_eq!(call(cookie_only).await, StatusCode::FORBIDDEN);
+        let header_only = mutation().insert_header((CSRF_HEADER, "token-1"));
+        assert_eq!(call(header_only).await, StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn mutation_with_matching_token_succeeds() {
+        let req = mutation()
+            .cookie(Cookie::new(CSRF_COOKIE, "token-1"))
+            .insert_header((CSRF_HEADER, "token-1"));
+        assert_eq!(call(req).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn mismatched_or_empty_token_is_rejected() {
+        let mismatched = mutation()
+            .cookie(Cookie::new(CSRF_COOKIE, "token-1"))
+            .insert_header((CSRF_HEADER, "token-2"));
+        assert_eq!(call(mismatched).await, StatusCode::FORBIDDEN);
+        let empty = mutation()
+            .cookie(Cookie::new(CSRF_COOKIE, ""))
+            .insert_header((CSRF_HEADER, ""));
+        assert_eq!(call(empty).await, StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn only_unsafe_api_calls_are_checked() {
+        let read = test::TestRequest::get().uri("/api/get_cron_job_stats");
+        assert_eq!(call(read).await, StatusCode::OK);
+        let delete = test::TestRequest::delete().uri("/api/delete_cron_job");
+        assert_eq!(call(delete).await, StatusCode::FORBIDDEN);
+        let outside_api = test::TestRequest::post().uri("/login");
+        assert_eq!(call(outside_api).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::server::error::ServerError;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use std::env;
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+/// Time budget of a request when `REQUEST_TIMEOUT` is unset, in seconds
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Time budget of each request, from `REQUEST_TIMEOUT` (seconds)
+static REQUEST_TIMEOUT: LazyLock<Duration> = LazyLock::new(|| {
+    let secs = env::var("REQUEST_TIMEOUT")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+});
+
+tokio::task_local! {
+    /// When the request being handled on this task runs out of time
+    static DEADLINE: Instant;
+}
+
+/// Run `future` with a deadline `budget` from now, which [`within_deadline`] calls made
+/// inside it respect
+pub async fn with_deadline<F: Future>(budget: Duration, future: F) -> F::Output {
+    DEADLINE.scope(Instant::now() + budget, future).await
+}
+
+/// Time left before the current request's deadline, if it has one
+pub fn remaining_time() -> Option<Duration> {
+    DEADLINE
+        .try_with(|deadline| deadline.saturating_duration_since(Instant::now()))
+        .ok()
+}
+
+/// Await a call to a dependency (Turso, Supabase, an outbound URL), giving up with
+/// [`ServerError::DeadlineExceeded`] when the request's deadline passes first. Outside a
+/// request, e.g. in the scheduler, the call runs without a limit.
+///
+/// ```
+/// use cron_jobs::server::middleware::{with_deadline, within_deadline};
+/// use cron_jobs::server::ServerError;
+/// use std::time::{Duration, Instant};
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let started = Instant::now();
+///     let slow_dependency = actix_web::rt::time::sleep(Duration::from_secs(5));
+///     let result = with_deadline(
+///         Duration::from_millis(50),
+///         within_deadline("Slow dependency", slow_dependency),
+///     )
+///     .await;
+///
+///     assert!(matches!(result, Err(ServerError::DeadlineExceeded(_))));
+///     assert!(started.elapsed() < Duration::from_secs(1));
+/// });
+/// ```
+pub async fn within_deadline<F: Future>(what: &str, future: F) -> Result<F::Output, ServerError> {
+    let Some(remaining) = remaining_time() else {
+        return Ok(future.await);
+    };
+    actix_web::rt::time::timeout(remaining, future)
+        .await
+        .map_err(|_| {
+            ServerError::DeadlineExceeded(format!("{} did not finish within the request deadline", what))
+        })
+}
+
+/// Give every request a deadline of `REQUEST_TIMEOUT` seconds (default 30) for its calls
+/// to dependencies. The handler itself is not cut off, so writes are never abandoned
+/// half-way; only the dependency calls wrapped in [`within_deadline`] fail.
+pub async fn deadline_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    with_deadline(*REQUEST_TIMEOUT, next.call(req)).await
+}
@@ -0,0 +1,17 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod csrf;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod deadline;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rate_limit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod trace;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use csrf::{csrf_middleware, CsrfConfig};
+#[cfg(not(target_arch = "wasm32"))]
+pub use deadline::{deadline_middleware, remaining_time, with_deadline, within_deadline};
+#[cfg(not(target_arch = "wasm32"))]
+pub use rate_limit::{auth_rate_limit_middleware, RateLimiter, TokenBucket};
+#[cfg(not(target_arch = "wasm32"))]
+pub use trace::{current_trace_id, init_logging, trace_middleware, TRACE_HEADER};
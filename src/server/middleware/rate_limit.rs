@@ -0,0 +1,170 @@
+use crate::context::{CreateUserDatabase, GetDatabaseInfo};
+use crate::server::actions::GetMyDatabaseInfo;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::{error, Error};
+use server_fn::ServerFn;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Fixed-window request limiter keyed by caller (e.g. client IP)
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    hits: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    /// Allow `limit` requests per key in each `window`
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from `key`; `false` once the key has used up its window
+    pub fn check(&self, key: &str) -> bool {
+        let Ok(mut hits) = self.hits.lock() else {
+            return true;
+        };
+        let now = Instant::now();
+        // Forget callers whose window has passed so the map doesn't grow without bound
+        hits.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+
+        let (_, count) = hits.entry(key.to_string()).or_insert((now, 0));
+        if *count >= self.limit {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+/// Token bucket keyed by caller (e.g. client IP)
+///
+/// Each key starts with `capacity` tokens and regains them at a steady rate, a full
+/// bucket per `refill_window`:
+///
+/// ```
+/// use cron_jobs::server::middleware::TokenBucket;
+/// use std::time::Duration;
+///
+/// let bucket = TokenBucket::new(2, Duration::from_millis(100));
+/// bucket.take("10.0.0.1");
+/// bucket.take("10.0.0.1");
+/// assert!(!bucket.has_tokens("10.0.0.1"));
+/// assert!(bucket.has_tokens("10.0.0.2"));
+///
+/// std::thread::sleep(Duration::from_millis(60));
+/// assert!(bucket.has_tokens("10.0.0.1"));
+/// ```
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_window: Duration,
+    /// Tokens left per key and when they were counted
+    buckets: Mutex<HashMap<String, (f64, Instant)>>,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u32, refill_window: Duration) -> Self {
+        Self {
+            capacity: f64::from(capacity),
+            refill_window,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tokens a bucket last counted at `since` holds at `now`
+    fn refilled(&self, tokens: f64, since: Instant, now: Instant) -> f64 {
+        let window = self.refill_window.as_secs_f64();
+        if window <= 0.0 {
+            return self.capacity;
+        }
+        let regained = now.duration_since(since).as_secs_f64() / window * self.capacity;
+        (tokens + regained).min(self.capacity)
+    }
+
+    /// Whether `key` has at least one token left
+    pub fn has_tokens(&self, key: &str) -> bool {
+        let Ok(buckets) = self.buckets.lock() else {
+            return true;
+        };
+        buckets
+            .get(key)
+            .is_none_or(|(tokens, since)| self.refilled(*tokens, *since, Instant::now()) >= 1.0)
+    }
+
+    /// Take a token from `key`; `false` if it had none left
+    pub fn take(&self, key: &str) -> bool {
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return true;
+        };
+        let now = Instant::now();
+        // Forget keys whose bucket has refilled so the map doesn't grow without bound
+        buckets.retain(|_, (tokens, since)| self.refilled(*tokens, *since, now) < self.capacity);
+
+        let (tokens, since) = buckets.entry(key.to_string()).or_insert((self.capacity, now));
+        let available = self.refilled(*tokens, *since, now);
+        *since = now;
+        if available < 1.0 {
+            *tokens = available;
+            return false;
+        }
+        *tokens = available - 1.0;
+        true
+    }
+}
+
+/// Failed sign-ins allowed per client IP, refilled over `AUTH_FAILURE_WINDOW_SECS`.
+/// Read from `AUTH_FAILURE_LIMIT` (default 10) and `AUTH_FAILURE_WINDOW_SECS` (default 300).
+static AUTH_FAILURES: LazyLock<TokenBucket> = LazyLock::new(|| {
+    let limit = env::var("AUTH_FAILURE_LIMIT")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(10);
+    let window = env::var("AUTH_FAILURE_WINDOW_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(300);
+    TokenBucket::new(limit, Duration::from_secs(window))
+});
+
+/// Server functions that check an access token while a user signs in
+fn is_auth_route(path: &str) -> bool {
+    [CreateUserDatabase::PATH, GetDatabaseInfo::PATH, GetMyDatabaseInfo::PATH].contains(&path)
+}
+
+/// Throttle repeated failed sign-ins per client IP.
+///
+/// Every 401 from a sign-in server function takes a token from the caller's bucket; once
+/// it is empty those functions answer 429 until it refills. Other routes are not affected.
+pub async fn auth_rate_limit_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if !is_auth_route(req.path()) {
+        return next.call(req).await;
+    }
+
+    let client_ip = req
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    if !AUTH_FAILURES.has_tokens(&client_ip) {
+        return Err(error::ErrorTooManyRequests("Too many failed sign-in attempts, try again later"));
+    }
+
+    let response = next.call(req).await?;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        AUTH_FAILURES.take(&client_ip);
+    }
+    Ok(response)
+}
@@ -0,0 +1,91 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::env;
+use std::io::Write;
+
+/// Response header carrying the request's trace id
+pub const TRACE_HEADER: &str = "x-trace-id";
+
+tokio::task_local! {
+    /// Trace id of the request being handled on this task
+    static TRACE_ID: String;
+}
+
+/// Trace id of the request the current task is handling, if any.
+/// Work moved to another task with `spawn` is not covered.
+pub fn current_trace_id() -> Option<String> {
+    TRACE_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Give every request a trace id.
+///
+/// The id is added to each log line written while the request is handled (see
+/// [`init_logging`]) and returned in the [`TRACE_HEADER`] response header, so a failing
+/// request can be matched with its log lines.
+pub async fn trace_middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let trace_id = uuid::Uuid::new_v4().simple().to_string();
+    let header = HeaderValue::from_str(&trace_id).ok();
+
+    let mut response = TRACE_ID.scope(trace_id, next.call(req)).await?;
+    if let Some(header) = header {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(TRACE_HEADER), header);
+    }
+    Ok(response)
+}
+
+/// Writes log records to stderr, tagged with the current request's trace id
+struct TraceLogger {
+    level: LevelFilter,
+}
+
+impl Log for TraceLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let trace = current_trace_id()
+            .map(|id| format!(" [trace={}]", id))
+            .unwrap_or_default();
+        let _ = writeln!(
+            std::io::stderr(),
+            "{} {:<5}{} {}: {}",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            record.level(),
+            trace,
+            record.target(),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+/// Install the process logger. The level comes from `LOG_LEVEL` (`error`, `warn`,
+/// `info`, `debug` or `trace`; defaults to `info`).
+pub fn init_logging() {
+    let level = env::var("LOG_LEVEL")
+        .ok()
+        .and_then(|v| v.trim().parse::<Level>().ok())
+        .map(|level| level.to_level_filter())
+        .unwrap_or(LevelFilter::Info);
+
+    // Leaked once per process; later calls leave the first logger in place
+    if log::set_logger(Box::leak(Box::new(TraceLogger { level }))).is_ok() {
+        log::set_max_level(level);
+    }
+}
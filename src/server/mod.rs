@@ -1,8 +1,18 @@
 #[cfg(not(target_arch = "wasm32"))]
 pub mod actions;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod middleware;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod models;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod routes;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scheduler;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod service;
 #[cfg(not(target_arch = "wasm32"))]
-pub mod turso;
\ No newline at end of file
+pub mod turso;
+#[cfg(not(target_arch = "wasm32"))]
+pub use error::ServerError;
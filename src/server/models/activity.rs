@@ -0,0 +1,75 @@
+use crate::server::models::RunStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Entry of a user's activity feed
+///
+/// Job changes come from the job's own timestamps, so only a job's latest update
+/// appears in the feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ActivityItem {
+    JobCreated {
+        job_id: String,
+        job_name: String,
+        #[serde(with = "chrono::serde::ts_seconds")]
+        at: DateTime<Utc>,
+    },
+    JobUpdated {
+        job_id: String,
+        job_name: String,
+        #[serde(with = "chrono::serde::ts_seconds")]
+        at: DateTime<Utc>,
+    },
+    /// The job was moved to the trash
+    JobDeleted {
+        job_id: String,
+        job_name: String,
+        #[serde(with = "chrono::serde::ts_seconds")]
+        at: DateTime<Utc>,
+    },
+    Run {
+        job_id: String,
+        job_name: String,
+        run_id: String,
+        status: RunStatus,
+        /// When the run started
+        #[serde(with = "chrono::serde::ts_seconds")]
+        at: DateTime<Utc>,
+    },
+}
+
+/// Which kinds of entries an activity feed includes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivitySource {
+    /// Job creations, updates and deletions
+    Jobs,
+    Runs,
+}
+
+/// Filter for an activity feed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityFilter {
+    /// Only entries for this job
+    pub job_id: Option<String>,
+    /// Only job changes or only runs (`None` for both)
+    pub source: Option<ActivitySource>,
+}
+
+/// Position after the last entry of a page; pass it back to get the next page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityCursor {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub at: DateTime<Utc>,
+    /// Tie-breaker between entries with the same timestamp
+    pub key: String,
+}
+
+/// One page of a user's activity feed, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityPage {
+    pub items: Vec<ActivityItem>,
+    /// `None` on the last page
+    pub next_cursor: Option<ActivityCursor>,
+}
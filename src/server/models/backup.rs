@@ -0,0 +1,100 @@
+use crate::server::models::{JobType, MisfirePolicy};
+use serde::{Deserialize, Serialize};
+
+/// Version of the JSON backup format written by this build
+pub const JOBS_BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Versioned JSON backup of a user's cron jobs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobsBackup {
+    /// Format of the document; imports reject versions newer than
+    /// [`JOBS_BACKUP_FORMAT_VERSION`]
+    pub format_version: u32,
+    /// RFC 3339 time the backup was taken
+    pub exported_at: String,
+    pub jobs: Vec<BackupJob>,
+}
+
+/// One job in a backup. Ids are not kept: importing always creates new jobs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupJob {
+    pub name: String,
+    pub schedule: String,
+    pub command: String,
+    pub enabled: bool,
+    pub timezone: String,
+    #[serde(default)]
+    pub job_type: JobType,
+    #[serde(default)]
+    pub http_method: Option<String>,
+    #[serde(default)]
+    pub http_body: Option<String>,
+    #[serde(default)]
+    pub max_retries: i64,
+    #[serde(default)]
+    pub retry_backoff_secs: i64,
+    #[serde(default)]
+    pub run_history_limit: Option<i64>,
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
+    #[serde(default)]
+    pub max_catchup: Option<i64>,
+}
+
+/// How an account import treats the jobs already in the account
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountImportMode {
+    /// Keep existing jobs and add the archived ones next to them
+    #[default]
+    Merge,
+    /// Move existing jobs to the trash before restoring the archived ones
+    Replace,
+}
+
+/// What happened to one job of an imported backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupJobResult {
+    /// Position of the job in the backup's `jobs`
+    pub index: usize,
+    pub name: String,
+    /// Id of the job created for it
+    pub job_id: Option<String>,
+    /// Why the job was not created
+    pub error: Option<String>,
+}
+
+/// Outcome of a backup import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupImportReport {
+    /// Existing jobs moved to the trash by a [`AccountImportMode::Replace`] import
+    #[serde(default)]
+    pub trashed: usize,
+    pub created: usize,
+    pub failed: usize,
+    pub jobs: Vec<BackupJobResult>,
+}
+
+/// What happened to one job of an ownership transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobTransferResult {
+    /// Id of the job in the source account
+    pub source_job_id: String,
+    /// Id of the copy created in the destination account
+    pub job_id: Option<String>,
+    /// Why the job was not transferred
+    pub error: Option<String>,
+}
+
+/// Outcome of moving jobs from one account to another
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobTransferReport {
+    pub transferred: usize,
+    pub failed: usize,
+    /// Originals moved to the source account's trash after being copied
+    pub originals_trashed: usize,
+    pub jobs: Vec<JobTransferResult>,
+    /// Database or Turso error that stopped the transfer early; jobs after the one it
+    /// failed on weren't attempted
+    pub error: Option<String>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a batch operation over several cron jobs
+///
+/// Jobs that do not exist or are not owned by the caller are reported in `skipped`, and
+/// jobs the operation refused in `failed`, rather than failing the whole batch. Database
+/// errors still abort the batch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub succeeded: Vec<String>,
+    pub skipped: Vec<String>,
+    #[serde(default)]
+    pub failed: Vec<BatchFailure>,
+}
+
+/// A job a batch operation found but refused to change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchFailure {
+    pub job_id: String,
+    pub error: String,
+}
@@ -0,0 +1,33 @@
+use crate::server::turso::schema::get_current_schema_version;
+use serde::{Deserialize, Serialize};
+
+/// Version details of the running build, for correlating reports with a deploy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildMetadata {
+    /// Crate version from Cargo.toml
+    pub crate_version: String,
+    /// Schema version this build migrates user databases to
+    pub schema_version: String,
+    /// Short git commit the build was made from (`unknown` outside a checkout)
+    pub git_sha: String,
+    /// When the build script last ran (RFC 3339)
+    pub built_at: String,
+}
+
+impl BuildMetadata {
+    pub fn current() -> Self {
+        let built_at = env!("BUILD_TIMESTAMP")
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_default();
+
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: get_current_schema_version().version,
+            git_sha: env!("GIT_SHA").to_string(),
+            built_at,
+        }
+    }
+}
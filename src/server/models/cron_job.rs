@@ -1,3 +1,7 @@
+use crate::server::service::{
+    normalize_schedule, normalize_timezone, validate_command_spec, validate_job_name,
+    validate_max_catchup, validate_retry_policy, validate_run_history_limit, validate_schedule,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -7,12 +11,55 @@ pub struct CronJob {
     pub user_id: String,
     pub name: String,
     pub schedule: String,
+    /// Shell command, or the URL to call for HTTP jobs
     pub command: String,
     pub enabled: bool,
+    pub job_type: JobType,
+    /// Request method for HTTP jobs
+    pub http_method: Option<String>,
+    /// Request body for HTTP jobs
+    pub http_body: Option<String>,
+    /// How many times a failed scheduled run is retried
+    #[serde(default)]
+    pub max_retries: i64,
+    /// Delay before the first retry; doubled for each further attempt
+    #[serde(default)]
+    pub retry_backoff_secs: i64,
+    /// Runs kept in this job's history; `None` uses the global `RUN_HISTORY_LIMIT`
+    #[serde(default)]
+    pub run_history_limit: Option<i64>,
+    /// What the scheduler does with runs missed while it was down
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
+    /// Most missed runs fired on catch-up; `None` uses the global `MAX_CATCHUP_RUNS`
+    #[serde(default)]
+    pub max_catchup: Option<i64>,
+    /// IANA timezone the schedule is read in
+    pub timezone: String,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub created_at: Option<DateTime<Utc>>,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub updated_at: Option<DateTime<Utc>>,
+    /// When the job was moved to the trash (`None` for live jobs)
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// Next time the job fires (`None` when disabled or the schedule never fires again)
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub next_run: Option<DateTime<Utc>>,
+    /// English description of `schedule` for display (the raw schedule if it can't be described)
+    #[serde(default)]
+    pub schedule_human: Option<String>,
+    /// Non-fatal issue found while saving (e.g. a disabled draft whose schedule never fires)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// Counts of a user's live jobs, for summary headers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CronJobStats {
+    pub total: u64,
+    pub enabled: u64,
+    pub disabled: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,13 +68,318 @@ pub struct CreateCronJobRequest {
     pub schedule: String,
     pub command: String,
     pub enabled: Option<bool>,
+    /// IANA timezone name, defaults to UTC
+    pub timezone: Option<String>,
+    /// Defaults to `shell`
+    pub job_type: Option<JobType>,
+    /// Defaults to `GET` for HTTP jobs
+    pub http_method: Option<String>,
+    pub http_body: Option<String>,
+    /// Defaults to 0 (no retries)
+    pub max_retries: Option<i64>,
+    /// Defaults to 0
+    pub retry_backoff_secs: Option<i64>,
+    /// Defaults to the global `RUN_HISTORY_LIMIT`
+    #[serde(default)]
+    pub run_history_limit: Option<i64>,
+    /// Defaults to `skip`
+    #[serde(default)]
+    pub misfire_policy: Option<MisfirePolicy>,
+    /// Defaults to the global `MAX_CATCHUP_RUNS`
+    #[serde(default)]
+    pub max_catchup: Option<i64>,
 }
 
+impl CreateCronJobRequest {
+    pub fn builder() -> CreateCronJobRequestBuilder {
+        CreateCronJobRequestBuilder::default()
+    }
+}
+
+/// Fluent builder for [`CreateCronJobRequest`]
+///
+/// `build` fills in the defaults (enabled, UTC, shell) and validates the request the
+/// same way `create_cron_job` does, so mistakes surface where the request is built.
+#[derive(Debug, Clone, Default)]
+pub struct CreateCronJobRequestBuilder {
+    name: Option<String>,
+    schedule: Option<String>,
+    command: Option<String>,
+    enabled: Option<bool>,
+    timezone: Option<String>,
+    job_type: Option<JobType>,
+    http_method: Option<String>,
+    http_body: Option<String>,
+    max_retries: Option<i64>,
+    retry_backoff_secs: Option<i64>,
+    run_history_limit: Option<i64>,
+    misfire_policy: Option<MisfirePolicy>,
+    max_catchup: Option<i64>,
+}
+
+impl CreateCronJobRequestBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn schedule(mut self, schedule: impl Into<String>) -> Self {
+        self.schedule = Some(schedule.into());
+        self
+    }
+
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
+
+    pub fn job_type(mut self, job_type: JobType) -> Self {
+        self.job_type = Some(job_type);
+        self
+    }
+
+    /// Make this an HTTP job calling `url` with `method`
+    pub fn http(mut self, method: impl Into<String>, url: impl Into<String>) -> Self {
+        self.job_type = Some(JobType::Http);
+        self.http_method = Some(method.into());
+        self.command = Some(url.into());
+        self
+    }
+
+    pub fn http_body(mut self, body: impl Into<String>) -> Self {
+        self.http_body = Some(body.into());
+        self
+    }
+
+    /// Retry failed runs up to `max_retries` times, waiting `backoff_secs` before the
+    /// first retry and doubling the wait for each further one
+    pub fn retries(mut self, max_retries: i64, backoff_secs: i64) -> Self {
+        self.max_retries = Some(max_retries);
+        self.retry_backoff_secs = Some(backoff_secs);
+        self
+    }
+
+    /// Keep only the `limit` most recent runs of this job
+    pub fn run_history_limit(mut self, limit: i64) -> Self {
+        self.run_history_limit = Some(limit);
+        self
+    }
+
+    /// Fire runs missed while the scheduler was down, at most `max_catchup` of them
+    /// (`None` uses the global `MAX_CATCHUP_RUNS`)
+    pub fn run_missed(mut self, max_catchup: Option<i64>) -> Self {
+        self.misfire_policy = Some(MisfirePolicy::RunAll);
+        self.max_catchup = max_catchup;
+        self
+    }
+
+    pub fn build(self) -> Result<CreateCronJobRequest, String> {
+        let name = validate_job_name(self.name.as_deref().unwrap_or_default())?;
+        let schedule = self.schedule.ok_or("Schedule is required")?;
+        let command = self.command.ok_or("Command is required")?;
+
+        validate_schedule(&schedule)?;
+        let schedule = normalize_schedule(&schedule)?;
+        let timezone = normalize_timezone(self.timezone.as_deref().unwrap_or("UTC"))?;
+        let job_type = self.job_type.unwrap_or_default();
+        validate_command_spec(&CommandSpec::new(
+            job_type,
+            &command,
+            self.http_method.as_deref(),
+            self.http_body.as_deref(),
+        ))?;
+        let max_retries = self.max_retries.unwrap_or(0);
+        let retry_backoff_secs = self.retry_backoff_secs.unwrap_or(0);
+        validate_retry_policy(max_retries, retry_backoff_secs)?;
+        if let Some(limit) = self.run_history_limit {
+            validate_run_history_limit(limit)?;
+        }
+        if let Some(max) = self.max_catchup {
+            validate_max_catchup(max)?;
+        }
+
+        Ok(CreateCronJobRequest {
+            name,
+            schedule,
+            command,
+            enabled: Some(self.enabled.unwrap_or(true)),
+            timezone: Some(timezone),
+            job_type: Some(job_type),
+            http_method: self.http_method,
+            http_body: self.http_body,
+            max_retries: Some(max_retries),
+            retry_backoff_secs: Some(retry_backoff_secs),
+            run_history_limit: self.run_history_limit,
+            misfire_policy: Some(self.misfire_policy.unwrap_or_default()),
+            max_catchup: self.max_catchup,
+        })
+    }
+}
+
+/// Partial update of a cron job; `None` fields keep their current value.
+///
+/// Unknown fields are rejected rather than ignored, so a misspelled field fails with
+/// an error naming it instead of silently changing nothing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateCronJobRequest {
     pub name: Option<String>,
     pub schedule: Option<String>,
     pub command: Option<String>,
     pub enabled: Option<bool>,
+    pub timezone: Option<String>,
+    pub job_type: Option<JobType>,
+    pub http_method: Option<String>,
+    pub http_body: Option<String>,
+    pub max_retries: Option<i64>,
+    pub retry_backoff_secs: Option<i64>,
+    #[serde(default)]
+    pub run_history_limit: Option<i64>,
+    #[serde(default)]
+    pub misfire_policy: Option<MisfirePolicy>,
+    #[serde(default)]
+    pub max_catchup: Option<i64>,
+}
+
+impl UpdateCronJobRequest {
+    /// Whether the update leaves every field unchanged
+    pub fn is_empty(&self) -> bool {
+        let UpdateCronJobRequest {
+            name,
+            schedule,
+            command,
+            enabled,
+            timezone,
+            job_type,
+            http_method,
+            http_body,
+            max_retries,
+            retry_backoff_secs,
+            run_history_limit,
+            misfire_policy,
+            max_catchup,
+        } = self;
+        name.is_none()
+            && schedule.is_none()
+            && command.is_none()
+            && enabled.is_none()
+            && timezone.is_none()
+            && job_type.is_none()
+            && http_method.is_none()
+            && http_body.is_none()
+            && max_retries.is_none()
+            && retry_backoff_secs.is_none()
+            && run_history_limit.is_none()
+            && misfire_policy.is_none()
+            && max_catchup.is_none()
+    }
+}
+
+/// Server-side filter for listing cron jobs. Text filters are case-insensitive substring matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListCronJobsFilter {
+    /// Only return enabled jobs
+    #[serde(default)]
+    pub enabled_only: bool,
+    pub name_contains: Option<String>,
+    pub command_contains: Option<String>,
+}
+
+/// How a job's `command` is run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobType {
+    /// `command` is run with `sh -c`
+    #[default]
+    Shell,
+    /// `command` is a URL requested with `http_method` and `http_body`
+    Http,
+}
+
+impl JobType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobType::Shell => "shell",
+            JobType::Http => "http",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "shell" => Ok(JobType::Shell),
+            "http" => Ok(JobType::Http),
+            other => Err(format!("Unknown job type: {}", other)),
+        }
+    }
 }
 
+/// What the scheduler does with runs a job missed while the scheduler was down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// Missed runs are dropped; the job next runs at its next scheduled time
+    #[default]
+    Skip,
+    /// Missed runs are fired one after another, up to the job's catch-up bound
+    RunAll,
+}
+
+impl MisfirePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MisfirePolicy::Skip => "skip",
+            MisfirePolicy::RunAll => "run_all",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "skip" => Ok(MisfirePolicy::Skip),
+            "run_all" => Ok(MisfirePolicy::RunAll),
+            other => Err(format!("Unknown misfire policy: {}", other)),
+        }
+    }
+}
+
+/// What running a job does, assembled from its command columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandSpec {
+    Shell { command: String },
+    Http { url: String, method: String, body: Option<String> },
+}
+
+impl CommandSpec {
+    pub fn new(job_type: JobType, command: &str, http_method: Option<&str>, http_body: Option<&str>) -> Self {
+        match job_type {
+            JobType::Shell => CommandSpec::Shell {
+                command: command.to_string(),
+            },
+            JobType::Http => CommandSpec::Http {
+                url: command.to_string(),
+                method: http_method.unwrap_or("GET").to_uppercase(),
+                body: http_body.map(str::to_string),
+            },
+        }
+    }
+}
+
+impl CronJob {
+    pub fn command_spec(&self) -> CommandSpec {
+        CommandSpec::new(
+            self.job_type,
+            &self.command,
+            self.http_method.as_deref(),
+            self.http_body.as_deref(),
+        )
+    }
+}
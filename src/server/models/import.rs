@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// One job in an import document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCronJobRow {
+    /// Stable key from the source system; re-importing a row with the same key
+    /// updates the job it created instead of adding a duplicate
+    pub idempotency_key: Option<String>,
+    pub name: String,
+    pub schedule: String,
+    pub command: String,
+    pub enabled: Option<bool>,
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Created,
+    Updated,
+    /// The row was invalid and nothing was written for it
+    Skipped,
+}
+
+/// What happened to a single row of an import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRowResult {
+    /// Position of the row in the import document
+    pub index: usize,
+    pub idempotency_key: Option<String>,
+    pub outcome: ImportOutcome,
+    /// Job created or updated by the row
+    pub job_id: Option<String>,
+    /// Schedule as stored, after macros are expanded and whitespace collapsed; compare
+    /// with the row's schedule to see how it was rewritten
+    pub normalized_schedule: Option<String>,
+    /// Why the row was skipped
+    pub error: Option<String>,
+}
+
+/// Per-row outcomes of an import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Nothing was written; outcomes show what the import would have done
+    pub dry_run: bool,
+    pub rows: Vec<ImportRowResult>,
+}
+
+impl ImportReport {
+    pub fn count(&self, outcome: ImportOutcome) -> usize {
+        self.rows.iter().filter(|row| row.outcome == outcome).count()
+    }
+}
+
+/// What happened to one job line of an imported crontab
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrontabLineResult {
+    /// 1-based line number in the crontab text
+    pub line: usize,
+    pub outcome: ImportOutcome,
+    /// Job created or updated by the line
+    pub job_id: Option<String>,
+    /// Schedule as stored, for lines that were imported
+    pub normalized_schedule: Option<String>,
+    /// Why the line was skipped
+    pub error: Option<String>,
+}
+
+/// Outcome of a crontab import. Blank lines and comments are not listed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrontabImportReport {
+    /// Nothing was written; outcomes show what the import would have done
+    pub dry_run: bool,
+    /// Lines that created or updated a job
+    pub imported: usize,
+    /// Lines that could not be parsed or failed validation
+    pub skipped: usize,
+    pub lines: Vec<CrontabLineResult>,
+}
+
+/// Estimated storage effect of an import, computed without writing anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportImpact {
+    /// Valid rows that would add a job
+    pub new_rows: u64,
+    /// Valid rows that would update a previously imported job
+    pub updated_rows: u64,
+    /// Rows that would be skipped as invalid
+    pub skipped_rows: u64,
+    /// Approximate bytes the new rows would add
+    pub estimated_bytes: u64,
+    pub storage_used_bytes: u64,
+    /// Configured soft limit, if any
+    pub soft_limit_bytes: Option<u64>,
+    /// Set when the import would likely push storage over the soft limit
+    pub warning: Option<String>,
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+    /// The run was not executed (e.g. the previous run was still in progress)
+    Skipped,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::Failed => "failed",
+            RunStatus::Skipped => "skipped",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "succeeded" => Ok(RunStatus::Succeeded),
+            "failed" => Ok(RunStatus::Failed),
+            "skipped" => Ok(RunStatus::Skipped),
+            other => Err(format!("Unknown run status: {}", other)),
+        }
+    }
+}
+
+/// One execution of a cron job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub id: String,
+    pub job_id: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub started_at: DateTime<Utc>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub status: RunStatus,
+    /// Process exit code, or the response status for HTTP jobs
+    pub exit_code: Option<i64>,
+    /// Tail of the captured output
+    pub output_excerpt: Option<String>,
+}
+
+/// Period runs are grouped by in a run summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bucket {
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+    Month,
+}
+
+/// Run counts for one period of a job's history
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunBucket {
+    /// First day of the period, in the job's timezone
+    pub start: NaiveDate,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub skipped: u64,
+}
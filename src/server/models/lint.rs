@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    /// The job cannot run as configured
+    Error,
+    /// The job runs, but probably not the way the user intended
+    Warning,
+}
+
+/// A problem found in one of the user's jobs by the account-wide lint pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobLint {
+    pub job_id: String,
+    pub level: LintLevel,
+    pub message: String,
+}
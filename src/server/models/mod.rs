@@ -1,6 +1,54 @@
 #[cfg(not(target_arch = "wasm32"))]
+pub mod activity;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod backup;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod batch;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod build_info;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod cron_job;
-
 #[cfg(not(target_arch = "wasm32"))]
-pub use cron_job::{CronJob, CreateCronJobRequest, UpdateCronJobRequest};
+pub mod import;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod job_run;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod lint;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pagination;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod url_check;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod usage;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use activity::{ActivityCursor, ActivityFilter, ActivityItem, ActivityPage, ActivitySource};
+#[cfg(not(target_arch = "wasm32"))]
+pub use backup::{
+    AccountImportMode, BackupImportReport, BackupJob, BackupJobResult, JobTransferReport,
+    JobTransferResult, JobsBackup, JOBS_BACKUP_FORMAT_VERSION,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use batch::{BatchFailure, BatchResult};
+#[cfg(not(target_arch = "wasm32"))]
+pub use build_info::BuildMetadata;
+#[cfg(not(target_arch = "wasm32"))]
+pub use cron_job::{
+    CommandSpec, CronJob, CronJobStats, CreateCronJobRequest, CreateCronJobRequestBuilder,
+    JobType, ListCronJobsFilter, MisfirePolicy, UpdateCronJobRequest,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use job_run::{Bucket, JobRun, RunBucket, RunStatus};
+#[cfg(not(target_arch = "wasm32"))]
+pub use lint::{JobLint, LintLevel};
+#[cfg(not(target_arch = "wasm32"))]
+pub use pagination::{CronJobPage, Pagination, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+#[cfg(not(target_arch = "wasm32"))]
+pub use import::{
+    CrontabImportReport, CrontabLineResult, ImportCronJobRow, ImportImpact, ImportOutcome,
+    ImportReport, ImportRowResult,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use url_check::UrlCheck;
+#[cfg(not(target_arch = "wasm32"))]
+pub use usage::AccountUsage;
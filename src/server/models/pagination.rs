@@ -0,0 +1,34 @@
+use crate::server::models::CronJob;
+use serde::{Deserialize, Serialize};
+
+/// Page size used when the caller does not ask for one
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+/// Largest page a caller may request
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+/// Requested page of a listing
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Pagination {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl Pagination {
+    /// Page size to query, defaulted and capped to [`MAX_PAGE_SIZE`]
+    pub fn limit(&self) -> u32 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.offset.unwrap_or(0)
+    }
+}
+
+/// One page of a user's cron jobs along with the total number of jobs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronJobPage {
+    pub jobs: Vec<CronJob>,
+    pub total: u64,
+    pub limit: u32,
+    pub offset: u32,
+}
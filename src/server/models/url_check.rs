@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Result of probing an HTTP job's URL before it is saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlCheck {
+    /// Whether the server answered at all, whatever the status
+    pub reachable: bool,
+    /// Response status (`None` when the server could not be reached)
+    pub status: Option<u16>,
+    /// Why the request failed, when it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A user's usage next to the limits that apply to it, for usage meters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountUsage {
+    /// Live jobs, not counting the trash
+    pub jobs_used: u64,
+    /// `None` when the server sets no job limit
+    pub jobs_limit: Option<u64>,
+    pub storage_used_bytes: u64,
+    /// `None` when the server sets no storage limit
+    pub storage_limit_bytes: Option<u64>,
+    /// Runs kept in the history of the user's jobs
+    pub runs_retained: u64,
+}
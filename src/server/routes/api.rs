@@ -0,0 +1,10 @@
+use actix_web::HttpResponse;
+
+/// JSON 404 for `/api` paths with no registered server function, kept distinct from
+/// the HTML page 404 rendered by the router.
+///
+/// Register it on `/api/{tail:.*}` after the server function handler, so it only sees
+/// the paths that handler's guard turned away.
+pub async fn unknown_api_endpoint() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({ "error": "unknown_endpoint" }))
+}
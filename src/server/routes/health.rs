@@ -0,0 +1,60 @@
+use crate::server::models::BuildMetadata;
+use crate::server::turso::{check_jwks, TursoClient};
+use actix_web::{web, HttpResponse};
+
+/// Liveness probe: the process is up and serving requests
+#[actix_web::get("livez")]
+pub async fn livez() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness probe: 200 only when the registry database and the Supabase JWKS endpoint
+/// are reachable, 503 otherwise so no traffic is routed here yet
+#[actix_web::get("readyz")]
+pub async fn readyz(turso_client: web::Data<TursoClient>) -> HttpResponse {
+    let registry = turso_client.check_registry().await.map_err(|e| e.to_string());
+    let jwks = check_jwks(turso_client.supabase()).await.map_err(|e| e.to_string());
+
+    let check = |result: &Result<(), String>| match result {
+        Ok(()) => serde_json::json!({ "status": "ok" }),
+        Err(e) => serde_json::json!({ "status": "error", "error": e }),
+    };
+    let body = serde_json::json!({
+        "status": if registry.is_ok() && jwks.is_ok() { "ok" } else { "unavailable" },
+        "registry": check(&registry),
+        "jwks": check(&jwks),
+    });
+
+    if registry.is_ok() && jwks.is_ok() {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
+
+/// Detailed health for monitoring: registry and Turso API reachability with latencies,
+/// plus build info. 503 when the registry is down; a failing Turso API only marks the
+/// server `degraded`, since it is needed for provisioning alone.
+#[actix_web::get("health")]
+pub async fn health(turso_client: web::Data<TursoClient>) -> HttpResponse {
+    let report = turso_client.health_check().await;
+    let status = if report.is_healthy() {
+        "ok"
+    } else if report.registry.ok {
+        "degraded"
+    } else {
+        "unavailable"
+    };
+    let body = serde_json::json!({
+        "status": status,
+        "registry": report.registry,
+        "turso_api": report.turso_api,
+        "build": BuildMetadata::current(),
+    });
+
+    if report.registry.ok {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
@@ -0,0 +1,25 @@
+pub mod api;
+pub mod health;
+
+use actix_web::{guard, web};
+
+/// Register the routes that don't depend on the Leptos router
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(health::livez)
+        .service(health::readyz)
+        .service(health::health);
+}
+
+/// Route `/api` paths to the server function they name, and the rest to
+/// [`api::unknown_api_endpoint`]
+pub fn configure_api(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/api/{tail:.*}",
+        leptos_actix::handle_server_fns().guard(guard::fn_guard(|ctx| {
+            server_fn::actix::get_server_fn_service(ctx.head().uri.path(), &ctx.head().method)
+                .is_some()
+        })),
+    )
+    // Unmatched API paths get a JSON 404 instead of the server fn handler's 400
+    .route("/api/{tail:.*}", web::to(api::unknown_api_endpoint));
+}
@@ -0,0 +1,348 @@
+use crate::server::models::{CommandSpec, CronJob};
+use crate::server::service::outbound::{resolve_outbound_url, UrlPolicy};
+use actix_web::rt::time::timeout;
+use std::collections::HashMap;
+use std::env;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Longest output kept from a run, in bytes
+const MAX_OUTPUT_BYTES: usize = 4096;
+
+/// How long an HTTP job may take before it is reported as failed
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run time of a shell job when `SHELL_JOB_TIMEOUT_SECS` is unset, in seconds
+const DEFAULT_SHELL_TIMEOUT_SECS: u64 = 300;
+
+/// How long a shell job may run before it is killed, from `SHELL_JOB_TIMEOUT_SECS`
+static SHELL_TIMEOUT: LazyLock<Duration> = LazyLock::new(|| {
+    let secs = env::var("SHELL_JOB_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(DEFAULT_SHELL_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+});
+
+/// Outcome of running a job's command once
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    pub success: bool,
+    /// Process exit code, or the response status for HTTP jobs (`None` if the process was
+    /// killed by a signal or the command never ran)
+    pub exit_code: Option<i32>,
+    /// Combined stdout and stderr, or the response body for HTTP jobs, truncated to the
+    /// last [`MAX_OUTPUT_BYTES`]
+    pub output: String,
+}
+
+/// Keep the tail of the output, where errors usually are
+fn truncate_output(output: String) -> String {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output;
+    }
+    let mut start = output.len() - MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    output[start..].to_string()
+}
+
+/// Run a job once and capture its result
+///
+/// `env` is added to the environment of shell commands; HTTP jobs ignore it.
+pub async fn execute_job(job: &CronJob, env: &HashMap<String, String>) -> ExecutionResult {
+    match job.command_spec() {
+        CommandSpec::Shell { command } => execute_shell(command, env.clone(), *SHELL_TIMEOUT).await,
+        CommandSpec::Http { url, method, body } => {
+            execute_http(&url, &method, body, &UrlPolicy::from_env()).await
+        }
+    }
+}
+
+/// Most redirects an HTTP job follows
+const MAX_REDIRECTS: usize = 10;
+
+/// Issue the request for an HTTP job. Any non-2xx status counts as a failure.
+///
+/// The URL must pass the outbound [`UrlPolicy`], and so must every redirect it leads
+/// to; each request is pinned to the addresses that were checked.
+async fn execute_http(url: &str, method: &str, body: Option<String>, policy: &UrlPolicy) -> ExecutionResult {
+    let method = match reqwest::Method::from_bytes(method.as_bytes()) {
+        Ok(method) => method,
+        Err(_) => {
+            return ExecutionResult {
+                success: false,
+                exit_code: None,
+                output: format!("Invalid HTTP method '{}'", method),
+            }
+        }
+    };
+
+    let response = timeout(HTTP_TIMEOUT, send_following_redirects(url, method, body, policy)).await;
+    match response {
+        Ok(Ok(response)) => {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            ExecutionResult {
+                success: status.is_success(),
+                exit_code: Some(i32::from(status.as_u16())),
+                output: truncate_output(text),
+            }
+        }
+        Ok(Err(e)) => ExecutionResult {
+            success: false,
+            exit_code: None,
+            output: e,
+        },
+        Err(_) => ExecutionResult {
+            success: false,
+            exit_code: None,
+            output: format!("Request timed out after {}s", HTTP_TIMEOUT.as_secs()),
+        },
+    }
+}
+
+/// Send a request and follow its redirects by hand, resolving and checking each hop
+/// before connecting to it. reqwest's own redirect policy runs before the next host is
+/// resolved, so it can't catch a name that points at an internal address.
+async fn send_following_redirects(
+    url: &str,
+    mut method: reqwest::Method,
+    mut body: Option<String>,
+    policy: &UrlPolicy,
+) -> Result<reqwest::Response, String> {
+    let mut url = url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        let target = resolve_outbound_url(&url, policy).await.map_err(|e| e.to_string())?;
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(&target.host, &target.addrs)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+        let mut request = client.request(method.clone(), target.url.clone());
+        if let Some(body) = &body {
+            request = request.body(body.clone());
+        }
+        let response = request.send().await.map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        let location = match response.headers().get(reqwest::header::LOCATION) {
+            Some(location) if status.is_redirection() => location,
+            _ => return Ok(response),
+        };
+        let location = location
+            .to_str()
+            .map_err(|_| "Redirect location is not valid text".to_string())?;
+        url = target
+            .url
+            .join(location)
+            .map_err(|e| format!("Invalid redirect location '{}': {}", location, e))?
+            .to_string();
+
+        // Same rewriting as reqwest's default policy: 301, 302 and 303 continue as a
+        // bodiless GET (HEAD stays HEAD), 307 and 308 repeat the request as it was
+        if matches!(
+            status,
+            reqwest::StatusCode::MOVED_PERMANENTLY | reqwest::StatusCode::FOUND | reqwest::StatusCode::SEE_OTHER
+        ) {
+            if method != reqwest::Method::HEAD {
+                method = reqwest::Method::GET;
+            }
+            body = None;
+        }
+    }
+    Err(format!("Too many redirects (more than {})", MAX_REDIRECTS))
+}
+
+/// Run a shell command with `sh -c` and capture its output. A command still running
+/// after `limit` is killed and reported as failed.
+async fn execute_shell(command: String, env: HashMap<String, String>, limit: Duration) -> ExecutionResult {
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            return ExecutionResult {
+                success: false,
+                exit_code: None,
+                output: format!("Failed to start command: {}", e),
+            }
+        }
+    };
+
+    // On timeout the child is dropped with the future, which kills it
+    match timeout(limit, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            ExecutionResult {
+                success: output.status.success(),
+                exit_code: output.status.code(),
+                output: truncate_output(text),
+            }
+        }
+        Ok(Err(e)) => ExecutionResult {
+            success: false,
+            exit_code: None,
+            output: format!("Failed to wait for command: {}", e),
+        },
+        Err(_) => ExecutionResult {
+            success: false,
+            exit_code: None,
+            output: format!("Command timed out after {}s and was killed", limit.as_secs()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::thread;
+
+    /// Answer one connection per response, in order, reporting each request line
+    fn serve(listener: TcpListener, responses: Vec<String>) -> Receiver<String> {
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            for response in responses {
+                let Ok((mut stream, _)) = listener.accept() else { return };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                reader.read_exact(&mut vec![0; content_length]).unwrap();
+                tx.send(request_line.trim().to_string()).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        rx
+    }
+
+    fn redirect(status: &str, location: &str) -> String {
+        format!("HTTP/1.1 {}\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status, location)
+    }
+
+    fn local_policy() -> UrlPolicy {
+        UrlPolicy {
+            require_https: false,
+            allow_private: false,
+            allowed_hosts: vec!["127.0.0.1".to_string()],
+        }
+    }
+
+    #[actix_web::test]
+    async fn follows_redirects_between_allowed_hosts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = serve(
+            listener,
+            vec![
+                redirect("303 See Other", "/done"),
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok".to_string(),
+            ],
+        );
+
+        let url = format!("http://127.0.0.1:{}/start", port);
+        let result = execute_http(&url, "POST", Some("payload".to_string()), &local_policy()).await;
+
+        assert!(result.success, "{}", result.output);
+        assert_eq!(result.exit_code, Some(200));
+        assert_eq!(result.output, "ok");
+        let requests: Vec<String> = requests.try_iter().collect();
+        assert_eq!(requests, ["POST /start HTTP/1.1", "GET /done HTTP/1.1"]);
+    }
+
+    #[actix_web::test]
+    async fn redirect_targets_are_resolved_and_checked() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let location = format!("http://localhost:{}/internal", port);
+        let requests = serve(listener, vec![redirect("302 Found", &location); 2]);
+
+        let url = format!("http://127.0.0.1:{}/start", port);
+        let result = execute_http(&url, "GET", None, &local_policy()).await;
+
+        assert!(!result.success);
+        assert!(result.output.contains("internal address"), "{}", result.output);
+        assert_eq!(requests.try_iter().count(), 1);
+    }
+
+    #[actix_web::test]
+    async fn stops_after_too_many_redirects() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = serve(listener, vec![redirect("307 Temporary Redirect", "/again"); MAX_REDIRECTS + 1]);
+
+        let url = format!("http://127.0.0.1:{}/", port);
+        let result = execute_http(&url, "GET", None, &local_policy()).await;
+
+        assert!(!result.success);
+        assert!(result.output.starts_with("Too many redirects"), "{}", result.output);
+        assert_eq!(requests.try_iter().count(), MAX_REDIRECTS + 1);
+    }
+
+    #[actix_web::test]
+    async fn initial_url_must_pass_the_policy() {
+        let result = execute_http("https://169.254.169.254/latest/meta-data/", "GET", None, &UrlPolicy::default()).await;
+        assert!(!result.success);
+        assert_eq!(result.exit_code, None);
+        assert!(result.output.contains("internal address"), "{}", result.output);
+    }
+
+    #[actix_web::test]
+    async fn shell_commands_report_output_and_exit_code() {
+        let env = HashMap::from([("GREETING".to_string(), "hello".to_string())]);
+        let result = execute_shell("echo $GREETING; echo oops >&2; exit 3".to_string(), env, Duration::from_secs(10)).await;
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, Some(3));
+        assert_eq!(result.output, "hello\noops\n");
+    }
+
+    #[actix_web::test]
+    async fn shell_commands_are_killed_after_the_timeout() {
+        let pid_file = std::env::temp_dir().join(format!("shell-timeout-{}", uuid::Uuid::new_v4()));
+        let command = format!("echo $$ > {}; exec sleep 30", pid_file.display());
+
+        let started = std::time::Instant::now();
+        let result = execute_shell(command, HashMap::new(), Duration::from_millis(500)).await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!result.success);
+        assert_eq!(result.exit_code, None);
+        assert!(result.output.contains("timed out"), "{}", result.output);
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).unwrap();
+        actix_web::rt::time::sleep(Duration::from_millis(200)).await;
+        // Gone, or a zombie waiting to be reaped; either way no longer running
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        assert!(stat.is_empty() || stat.contains(") Z "), "{}", stat);
+    }
+}
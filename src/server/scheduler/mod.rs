@@ -0,0 +1,465 @@
+pub mod executor;
+
+pub use executor::{execute_job, ExecutionResult};
+
+use crate::server::models::{CronJob, JobRun, MisfirePolicy, RunStatus};
+use crate::server::service::{
+    cached_next_run, check_job_type_allowed, get_all_enabled_jobs, get_job_env,
+    last_run_started_at, parse_schedule, record_job_run, run_history_limit, REBOOT_SENTINEL,
+};
+use crate::server::turso::TursoClient;
+use actix_web::rt::time::interval;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the scheduler checks for due jobs
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Missed runs fired per job when neither the job nor `MAX_CATCHUP_RUNS` sets a bound
+pub const DEFAULT_MAX_CATCHUP: u32 = 10;
+
+/// How many missed runs of a `run_all` job are fired: its own `max_catchup`, else the
+/// global `MAX_CATCHUP_RUNS`, else [`DEFAULT_MAX_CATCHUP`]
+pub fn max_catchup(job: &CronJob) -> u32 {
+    job.max_catchup
+        .and_then(|max| u32::try_from(max).ok())
+        .or_else(|| {
+            env::var("MAX_CATCHUP_RUNS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+        })
+        .filter(|max| *max > 0)
+        .unwrap_or(DEFAULT_MAX_CATCHUP)
+}
+
+/// Times a job's schedule fired in a window, capped at a catch-up bound
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MissedRuns {
+    /// The most recent fire times, oldest first
+    pub runs: Vec<DateTime<Utc>>,
+    /// Older fire times dropped to stay within the bound
+    pub discarded: u64,
+}
+
+/// Times the job's schedule fired in `(since, now]`, keeping only the latest `max`
+pub fn missed_runs(job: &CronJob, since: DateTime<Utc>, now: DateTime<Utc>, max: u32) -> MissedRuns {
+    let mut missed = MissedRuns::default();
+    let (Ok(schedule), Ok(tz)) = (parse_schedule(&job.schedule), job.timezone.parse::<Tz>()) else {
+        return missed;
+    };
+
+    let mut runs = VecDeque::new();
+    let mut previous = since;
+    while previous < now {
+        let Some(next) = schedule.next_within(tz, previous, now - previous) else {
+            break;
+        };
+        if runs.len() == max as usize {
+            runs.pop_front();
+            missed.discarded += 1;
+        }
+        runs.push_back(next);
+        previous = next;
+    }
+    missed.runs = runs.into();
+    missed
+}
+
+/// Runs enabled cron jobs across all user databases
+///
+/// Every tick it loads the enabled jobs of each ready user database and dispatches the
+/// ones whose schedule fired since the previous tick. `@reboot` jobs run once when the
+/// scheduler starts. Runs missed while the scheduler was down are dropped, except for
+/// jobs with the `run_all` misfire policy: those fire their missed runs one after another
+/// when the scheduler starts, at most `max_catchup` of them, and record the older ones
+/// as a single skipped run. A job that is still running when it comes due again is skipped
+/// rather than run twice, and so is a shell job while `ALLOW_SHELL_JOBS` is off. A failed
+/// run is retried up to the job's `max_retries` times with exponential backoff. Every
+/// attempt, including skipped runs, is recorded in `job_runs`.
+pub struct Scheduler {
+    client: Arc<TursoClient>,
+    /// Ids of jobs currently executing
+    running: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Scheduler {
+    pub fn new(client: Arc<TursoClient>) -> Self {
+        Self {
+            client,
+            running: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Start the scheduler loop on the current actix runtime
+    pub fn spawn(self) {
+        actix_web::rt::spawn(self.run());
+    }
+
+    /// Scheduler loop; runs until the process exits
+    pub async fn run(self) {
+        info!("Scheduler started (tick every {}s)", TICK_INTERVAL.as_secs());
+
+        let mut last_tick = Utc::now();
+        self.catch_up(last_tick).await;
+        self.dispatch_matching(|job| job.schedule == REBOOT_SENTINEL).await;
+
+        let mut ticker = interval(TICK_INTERVAL);
+        // The first tick completes immediately
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let now = Utc::now();
+            self.dispatch_matching(|job| is_due(job, last_tick, now)).await;
+            last_tick = now;
+        }
+    }
+
+    /// Dispatch every enabled job, across all ready user databases, matching `due`
+    async fn dispatch_matching(&self, due: impl Fn(&CronJob) -> bool) {
+        let jobs = match get_all_enabled_jobs(&self.client).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                error!("[Scheduler] Failed to load enabled jobs: {}", e);
+                return;
+            }
+        };
+
+        for job in jobs.into_iter().filter(|job| due(job)) {
+            self.dispatch(job, 1);
+        }
+    }
+
+    /// Fire the runs that `run_all` jobs missed between their last run and `now`
+    async fn catch_up(&self, now: DateTime<Utc>) {
+        let jobs = match get_all_enabled_jobs(&self.client).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                error!("[Scheduler] Failed to load enabled jobs: {}", e);
+                return;
+            }
+        };
+
+        for job in jobs {
+            if job.misfire_policy != MisfirePolicy::RunAll || job.schedule == REBOOT_SENTINEL {
+                continue;
+            }
+            let last_run = match self.client.get_user_database_connection(&job.user_id).await {
+                Ok(conn) => last_run_started_at(&conn, &job.id).await,
+                Err(e) => Err(e.into()),
+            };
+            let since = match last_run {
+                Ok(last_run) => last_run.or(job.created_at),
+                Err(e) => {
+                    error!("[Scheduler] Failed to find the last run of job {}: {}", job.id, e);
+                    continue;
+                }
+            };
+            let Some(since) = since else {
+                continue;
+            };
+
+            let max = max_catchup(&job);
+            let missed = missed_runs(&job, since, now, max);
+            if missed.runs.is_empty() {
+                continue;
+            }
+            if missed.discarded > 0 {
+                warn!(
+                    "[Scheduler] Job {} ({}) missed {} runs; skipping the oldest {}",
+                    job.id,
+                    job.name,
+                    missed.discarded + missed.runs.len() as u64,
+                    missed.discarded
+                );
+                self.record_skipped(
+                    job.clone(),
+                    format!(
+                        "Skipped {} missed runs before {}; catch-up is capped at {} runs",
+                        missed.discarded,
+                        missed.runs[0].format("%Y-%m-%d %H:%M UTC"),
+                        max
+                    ),
+                );
+            }
+            info!(
+                "[Scheduler] Catching up {} missed runs of job {} ({})",
+                missed.runs.len(),
+                job.id,
+                job.name
+            );
+            self.dispatch(job, missed.runs.len());
+        }
+    }
+
+    /// Run a job `runs` times in a row in the background, unless it is already running
+    fn dispatch(&self, job: CronJob, runs: usize) {
+        if let Err(reason) = check_job_type_allowed(&job.command_spec(), self.client.allow_shell_jobs()) {
+            warn!(
+                "[Scheduler] Skipping job {} ({}) for user {}: {}",
+                job.id, job.name, job.user_id, reason
+            );
+            self.record_skipped(job, reason);
+            return;
+        }
+
+        let newly_running = self
+            .running
+            .lock()
+            .map(|mut running| running.insert(job.id.clone()))
+            .unwrap_or(false);
+        if !newly_running {
+            warn!(
+                "[Scheduler] Skipping job {} ({}) for user {}: previous run still in progress",
+                job.id, job.name, job.user_id
+            );
+            self.record_skipped(job, "Previous run still in progress".to_string());
+            return;
+        }
+
+        info!(
+            "[Scheduler] Dispatching job {} ({}) for user {}",
+            job.id, job.name, job.user_id
+        );
+
+        let client = self.client.clone();
+        let running = self.running.clone();
+        actix_web::rt::spawn(async move {
+            let env = match get_job_env(&client, &job.user_id, &job.id).await {
+                Ok(env) => Some(env),
+                Err(e) => {
+                    error!("[Scheduler] Failed to load environment of job {}: {}", job.id, e);
+                    let now = Utc::now();
+                    let run = JobRun {
+                        id: Uuid::new_v4().to_string(),
+                        job_id: job.id.clone(),
+                        started_at: now,
+                        finished_at: Some(now),
+                        status: RunStatus::Failed,
+                        exit_code: None,
+                        output_excerpt: Some(format!("Failed to load job environment: {}", e)),
+                    };
+                    store_run(&client, &job, &run).await;
+                    None
+                }
+            };
+
+            if let Some(env) = &env {
+                for _ in 0..runs {
+                    run_with_retries(&client, &job, env).await;
+                }
+            }
+
+            if let Ok(mut running) = running.lock() {
+                running.remove(&job.id);
+            }
+        });
+    }
+
+    /// Record in the background that an occurrence of `job` was skipped, and why
+    fn record_skipped(&self, job: CronJob, reason: String) {
+        let client = self.client.clone();
+        actix_web::rt::spawn(async move {
+            let now = Utc::now();
+            let run = JobRun {
+                id: Uuid::new_v4().to_string(),
+                job_id: job.id.clone(),
+                started_at: now,
+                finished_at: Some(now),
+                status: RunStatus::Skipped,
+                exit_code: None,
+                output_excerpt: Some(reason),
+            };
+            store_run(&client, &job, &run).await;
+        });
+    }
+}
+
+/// Wait before retry number `attempt` (1-based): `backoff_secs`, doubled per attempt
+fn retry_delay(backoff_secs: i64, attempt: i64) -> Duration {
+    let factor = 1u64 << (attempt - 1).clamp(0, 32);
+    Duration::from_secs(u64::try_from(backoff_secs).unwrap_or(0).saturating_mul(factor))
+}
+
+/// Run a job once, retrying it up to its `max_retries` times while it fails, and store
+/// every attempt
+async fn run_with_retries(client: &TursoClient, job: &CronJob, env: &HashMap<String, String>) {
+    let mut attempt = 0;
+    loop {
+        let run = run_job(job, env).await;
+        store_run(client, job, &run).await;
+
+        if run.status == RunStatus::Succeeded {
+            info!("[Scheduler] Job {} finished successfully", job.id);
+            return;
+        }
+        warn!(
+            "[Scheduler] Job {} failed (exit code {:?}): {}",
+            job.id,
+            run.exit_code,
+            run.output_excerpt.as_deref().unwrap_or_default()
+        );
+
+        if attempt >= job.max_retries {
+            return;
+        }
+        attempt += 1;
+        let delay = retry_delay(job.retry_backoff_secs, attempt);
+        info!(
+            "[Scheduler] Retrying job {} in {}s (attempt {} of {})",
+            job.id,
+            delay.as_secs(),
+            attempt,
+            job.max_retries
+        );
+        actix_web::rt::time::sleep(delay).await;
+    }
+}
+
+/// Execute a job with the given environment and describe the execution as a run record
+pub async fn run_job(job: &CronJob, env: &HashMap<String, String>) -> JobRun {
+    let started_at = Utc::now();
+    let result = execute_job(job, env).await;
+
+    JobRun {
+        id: Uuid::new_v4().to_string(),
+        job_id: job.id.clone(),
+        started_at,
+        finished_at: Some(Utc::now()),
+        status: if result.success {
+            RunStatus::Succeeded
+        } else {
+            RunStatus::Failed
+        },
+        exit_code: result.exit_code.map(i64::from),
+        output_excerpt: (!result.output.is_empty()).then_some(result.output),
+    }
+}
+
+/// Write a run to the job's user database, logging rather than failing on error
+async fn store_run(client: &TursoClient, job: &CronJob, run: &JobRun) {
+    let stored = match client.get_user_database_connection(&job.user_id).await {
+        Ok(conn) => record_job_run(&conn, run, run_history_limit(job)).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = stored {
+        error!("[Scheduler] {}", e);
+    }
+}
+
+/// Whether the job's schedule fired in `(since, now]`
+fn is_due(job: &CronJob, since: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    cached_next_run(job, since).is_some_and(|next| next <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::service::{create_user_database, get_cron_job, get_job_runs};
+    use chrono::TimeZone;
+
+    #[actix_web::test]
+    async fn a_due_shell_job_is_skipped_unless_shell_jobs_are_allowed() {
+        let client = Arc::new(TursoClient::new_in_memory().await.unwrap());
+        create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+        let conn = client.get_user_database_connection("user-1").await.unwrap();
+        conn.execute(
+            "INSERT INTO cron_jobs (id, user_id, name, schedule, command, job_type)
+             VALUES ('job-1', 'user-1', 'Backup', '0 3 * * *', 'echo backup', 'shell')",
+            (),
+        )
+        .await
+        .unwrap();
+        let job = get_cron_job(&client, "user-1", "job-1").await.unwrap();
+
+        Scheduler::new(client.clone()).dispatch(job, 1);
+
+        // The skipped run is written in the background
+        let mut runs = Vec::new();
+        for _ in 0..100 {
+            runs = get_job_runs(&client, "user-1", "job-1", None).await.unwrap();
+            if !runs.is_empty() {
+                break;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, RunStatus::Skipped);
+        let reason = runs[0].output_excerpt.as_deref().unwrap_or_default();
+        assert!(reason.contains("Shell jobs are disabled"), "{}", reason);
+    }
+
+    #[test]
+    fn a_thousand_missed_runs_are_capped_at_the_catch_up_bound() {
+        let job = CronJob {
+            id: "job-1".to_string(),
+            user_id: "user-1".to_string(),
+            name: "Ping".to_string(),
+            schedule: "* * * * *".to_string(),
+            command: "https://example.com/ping".to_string(),
+            enabled: true,
+            job_type: crate::server::models::JobType::Http,
+            http_method: None,
+            http_body: None,
+            max_retries: 0,
+            retry_backoff_secs: 0,
+            run_history_limit: None,
+            misfire_policy: MisfirePolicy::RunAll,
+            max_catchup: Some(5),
+            timezone: "UTC".to_string(),
+            created_at: None,
+            updated_at: None,
+            deleted_at: None,
+            next_run: None,
+            schedule_human: None,
+            warning: None,
+        };
+        let down_since = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let now = down_since + chrono::Duration::minutes(1000);
+
+        let missed = missed_runs(&job, down_since, now, max_catchup(&job));
+
+        assert_eq!(missed.discarded, 995);
+        let expected: Vec<_> = (996..=1000).map(|m| down_since + chrono::Duration::minutes(m)).collect();
+        assert_eq!(missed.runs, expected);
+    }
+
+    #[actix_web::test]
+    async fn catch_up_fires_the_latest_missed_runs_and_records_the_rest_as_skipped() {
+        let client = Arc::new(TursoClient::new_in_memory().await.unwrap());
+        create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+        let conn = client.get_user_database_connection("user-1").await.unwrap();
+        let now = Utc::now();
+        // Loopback URLs are refused by the outbound policy, so each run fails at once
+        conn.execute(
+            "INSERT INTO cron_jobs (id, user_id, name, schedule, command, job_type, misfire_policy, max_catchup, created_at)
+             VALUES ('job-1', 'user-1', 'Ping', '* * * * *', 'http://127.0.0.1/ping', 'http', 'run_all', 3, ?)",
+            libsql::params![crate::server::turso::row::sqlite_timestamp(&(now - chrono::Duration::minutes(1000)))],
+        )
+        .await
+        .unwrap();
+
+        Scheduler::new(client.clone()).catch_up(now).await;
+
+        // The runs are written in the background
+        let mut runs = Vec::new();
+        for _ in 0..200 {
+            runs = get_job_runs(&client, "user-1", "job-1", None).await.unwrap();
+            if runs.len() >= 4 {
+                break;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        let skipped: Vec<_> = runs.iter().filter(|run| run.status == RunStatus::Skipped).collect();
+        assert_eq!(skipped.len(), 1);
+        let reason = skipped[0].output_excerpt.as_deref().unwrap_or_default();
+        assert!(reason.starts_with("Skipped 997 missed runs"), "{}", reason);
+        assert!(reason.contains("capped at 3 runs"), "{}", reason);
+        assert_eq!(runs.iter().filter(|run| run.status == RunStatus::Failed).count(), 3);
+    }
+}
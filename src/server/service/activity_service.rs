@@ -0,0 +1,142 @@
+use crate::server::error::ServerError;
+use crate::server::models::{
+    ActivityCursor, ActivityFilter, ActivityItem, ActivityPage, ActivitySource, RunStatus,
+    DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE,
+};
+use crate::server::turso::row::{get_optional_timestamp, sqlite_timestamp};
+use crate::server::turso::TursoClient;
+
+/// Newest-first feed of the user's job changes and runs, merged by time
+///
+/// Pages are keyed on `(timestamp, key)` rather than an offset, so entries added
+/// while paging don't shift later pages.
+pub async fn get_activity_feed(
+    client: &TursoClient,
+    user_id: &str,
+    filter: &ActivityFilter,
+    cursor: Option<ActivityCursor>,
+    limit: Option<u32>,
+) -> Result<ActivityPage, ServerError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let job_clause = |column: &str| match filter.job_id {
+        Some(_) => format!(" AND {} = ?", column),
+        None => String::new(),
+    };
+
+    let mut parts = Vec::new();
+    let mut params: Vec<libsql::Value> = Vec::new();
+    let mut push_part = |sql: String, params: &mut Vec<libsql::Value>| {
+        params.push(user_id.into());
+        if let Some(job_id) = &filter.job_id {
+            params.push(job_id.as_str().into());
+        }
+        parts.push(sql);
+    };
+
+    if filter.source != Some(ActivitySource::Runs) {
+        push_part(
+            format!(
+                "SELECT 'job_created' AS kind, id AS job_id, name AS job_name, created_at AS at, id || ':created' AS item_key, NULL AS status
+                 FROM cron_jobs WHERE user_id = ?{}",
+                job_clause("id")
+            ),
+            &mut params,
+        );
+        // Moving a job to the trash also bumps updated_at, which isn't a separate update
+        push_part(
+            format!(
+                "SELECT 'job_updated', id, name, updated_at, id || ':updated', NULL
+                 FROM cron_jobs WHERE user_id = ? AND updated_at > created_at
+                 AND (deleted_at IS NULL OR updated_at > deleted_at){}",
+                job_clause("id")
+            ),
+            &mut params,
+        );
+        push_part(
+            format!(
+                "SELECT 'job_deleted', id, name, deleted_at, id || ':deleted', NULL
+                 FROM cron_jobs WHERE user_id = ? AND deleted_at IS NOT NULL{}",
+                job_clause("id")
+            ),
+            &mut params,
+        );
+    }
+    if filter.source != Some(ActivitySource::Jobs) {
+        push_part(
+            format!(
+                "SELECT 'run', r.job_id, j.name, r.started_at, r.id, r.status
+                 FROM job_runs r JOIN cron_jobs j ON j.id = r.job_id WHERE j.user_id = ?{}",
+                job_clause("r.job_id")
+            ),
+            &mut params,
+        );
+    }
+
+    let mut sql = format!(
+        "SELECT kind, job_id, job_name, at, item_key, status FROM ({}) WHERE at IS NOT NULL",
+        parts.join(" UNION ALL ")
+    );
+    if let Some(cursor) = &cursor {
+        sql.push_str(" AND (at < ? OR (at = ? AND item_key < ?))");
+        let at = sqlite_timestamp(&cursor.at);
+        params.push(at.clone().into());
+        params.push(at.into());
+        params.push(cursor.key.clone().into());
+    }
+    // One extra row tells whether there is a next page
+    sql.push_str(" ORDER BY at DESC, item_key DESC LIMIT ?");
+    params.push((limit as i64 + 1).into());
+
+    let conn = client.get_user_database_connection(user_id).await?;
+    let mut rows = conn
+        .prepare(&sql)
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare activity query: {}", e)))?
+        .query(libsql::params_from_iter(params))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to query activity: {}", e)))?;
+
+    let mut entries = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        let kind: String = row.get(0).map_err(|e| ServerError::Database(format!("Failed to get kind: {}", e)))?;
+        let job_id: String = row.get(1).map_err(|e| ServerError::Database(format!("Failed to get job_id: {}", e)))?;
+        let job_name: String = row.get(2).map_err(|e| ServerError::Database(format!("Failed to get job_name: {}", e)))?;
+        let at = get_optional_timestamp(&row, 3, "at")
+            .map_err(ServerError::Database)?
+            .ok_or_else(|| ServerError::Database("Column 'at' is NULL".to_string()))?;
+        let key: String = row.get(4).map_err(|e| ServerError::Database(format!("Failed to get item_key: {}", e)))?;
+
+        let item = match kind.as_str() {
+            "job_created" => ActivityItem::JobCreated { job_id, job_name, at },
+            "job_updated" => ActivityItem::JobUpdated { job_id, job_name, at },
+            "job_deleted" => ActivityItem::JobDeleted { job_id, job_name, at },
+            _ => {
+                let status: String = row.get(5).map_err(|e| ServerError::Database(format!("Failed to get status: {}", e)))?;
+                ActivityItem::Run {
+                    job_id,
+                    job_name,
+                    run_id: key.clone(),
+                    status: RunStatus::parse(&status).map_err(ServerError::Database)?,
+                    at,
+                }
+            }
+        };
+        entries.push((item, ActivityCursor { at, key }));
+    }
+
+    let next_cursor = if entries.len() > limit as usize {
+        entries.truncate(limit as usize);
+        entries.last().map(|(_, cursor)| cursor.clone())
+    } else {
+        None
+    };
+
+    Ok(ActivityPage {
+        items: entries.into_iter().map(|(item, _)| item).collect(),
+        next_cursor,
+    })
+}
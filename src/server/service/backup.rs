@@ -0,0 +1,317 @@
+use crate::server::error::ServerError;
+use crate::server::models::{
+    AccountImportMode, BackupImportReport, BackupJob, BackupJobResult, CreateCronJobRequest,
+    CronJob, JobTransferReport, JobTransferResult, JobsBackup, ListCronJobsFilter, Pagination,
+    JOBS_BACKUP_FORMAT_VERSION, MAX_PAGE_SIZE,
+};
+use crate::server::service::cron_service::{
+    create_cron_job, delete_cron_jobs, get_cron_job, get_user_cron_jobs,
+};
+use crate::server::turso::{ProvisionStatus, TursoClient};
+use log::{info, warn};
+
+impl From<CronJob> for BackupJob {
+    fn from(job: CronJob) -> Self {
+        BackupJob {
+            name: job.name,
+            schedule: job.schedule,
+            command: job.command,
+            enabled: job.enabled,
+            timezone: job.timezone,
+            job_type: job.job_type,
+            http_method: job.http_method,
+            http_body: job.http_body,
+            max_retries: job.max_retries,
+            retry_backoff_secs: job.retry_backoff_secs,
+            run_history_limit: job.run_history_limit,
+            misfire_policy: job.misfire_policy,
+            max_catchup: job.max_catchup,
+        }
+    }
+}
+
+impl From<BackupJob> for CreateCronJobRequest {
+    fn from(job: BackupJob) -> Self {
+        CreateCronJobRequest {
+            name: job.name,
+            schedule: job.schedule,
+            command: job.command,
+            enabled: Some(job.enabled),
+            timezone: Some(job.timezone),
+            job_type: Some(job.job_type),
+            http_method: job.http_method,
+            http_body: job.http_body,
+            max_retries: Some(job.max_retries),
+            retry_backoff_secs: Some(job.retry_backoff_secs),
+            run_history_limit: job.run_history_limit,
+            misfire_policy: Some(job.misfire_policy),
+            max_catchup: job.max_catchup,
+        }
+    }
+}
+
+/// All of the user's live jobs, enabled or not
+async fn all_jobs(client: &TursoClient, user_id: &str) -> Result<Vec<CronJob>, ServerError> {
+    let mut jobs = Vec::new();
+    loop {
+        let page = get_user_cron_jobs(
+            client,
+            user_id,
+            ListCronJobsFilter::default(),
+            Pagination {
+                limit: Some(MAX_PAGE_SIZE),
+                offset: Some(jobs.len() as u32),
+            },
+        )
+        .await?;
+        let done = page.jobs.is_empty() || jobs.len() + page.jobs.len() >= page.total as usize;
+        jobs.extend(page.jobs);
+        if done {
+            return Ok(jobs);
+        }
+    }
+}
+
+/// All of the user's jobs, enabled or not, as a versioned JSON backup.
+/// Trashed jobs, run history and job secrets are not included.
+pub async fn export_jobs_json(client: &TursoClient, user_id: &str) -> Result<String, String> {
+    let jobs = all_jobs(client, user_id).await?;
+    let backup = JobsBackup {
+        format_version: JOBS_BACKUP_FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        jobs: jobs.into_iter().map(BackupJob::from).collect(),
+    };
+    serde_json::to_string_pretty(&backup).map_err(|e| format!("Failed to serialize backup: {}", e))
+}
+
+/// Parse a backup document, rejecting format versions this build can't read
+fn parse_backup(json: &str) -> Result<JobsBackup, String> {
+    let backup: JobsBackup =
+        serde_json::from_str(json).map_err(|e| format!("Invalid backup document: {}", e))?;
+    if backup.format_version == 0 || backup.format_version > JOBS_BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported backup format version {} (this server reads up to {})",
+            backup.format_version, JOBS_BACKUP_FORMAT_VERSION
+        ));
+    }
+    Ok(backup)
+}
+
+/// Create a job for each entry of `jobs`, recording the outcome in `report`
+async fn restore_jobs(
+    client: &TursoClient,
+    user_id: &str,
+    jobs: Vec<BackupJob>,
+    report: &mut BackupImportReport,
+) -> Result<(), String> {
+    for (index, job) in jobs.into_iter().enumerate() {
+        let name = job.name.clone();
+        let result = match create_cron_job(client, user_id, job.into()).await {
+            Ok(created) => BackupJobResult {
+                index,
+                name,
+                job_id: Some(created.id),
+                error: None,
+            },
+            Err(e @ (ServerError::Database(_) | ServerError::TursoApi(_))) => return Err(e.into()),
+            Err(e) => BackupJobResult {
+                index,
+                name,
+                job_id: None,
+                error: Some(e.to_string()),
+            },
+        };
+        if result.error.is_none() {
+            report.created += 1;
+        } else {
+            report.failed += 1;
+        }
+        report.jobs.push(result);
+    }
+    Ok(())
+}
+
+/// Restore jobs from a JSON backup written by [`export_jobs_json`].
+///
+/// Every job is created anew with a fresh id, so restoring into an account that still
+/// has the original jobs duplicates them. Each job is validated like `create_cron_job`
+/// and jobs that fail validation or the quota are reported and skipped; a database
+/// error stops the import, leaving the jobs created so far in place.
+pub async fn import_jobs_json(
+    client: &TursoClient,
+    user_id: &str,
+    json: &str,
+) -> Result<BackupImportReport, String> {
+    import_account(client, user_id, json, AccountImportMode::Merge).await
+}
+
+/// Everything that can be moved to another account, as one versioned JSON archive.
+///
+/// Jobs are the only per-account data kept today, so the archive is the same document
+/// as [`export_jobs_json`]. Job secrets, run history and database credentials are never
+/// included.
+pub async fn export_account(client: &TursoClient, user_id: &str) -> Result<String, String> {
+    export_jobs_json(client, user_id).await
+}
+
+/// Restore an archive written by [`export_account`].
+///
+/// The archive is parsed and its version checked before anything changes. With
+/// [`AccountImportMode::Replace`] the account's current jobs are moved to the trash
+/// first, so they can still be restored from there; otherwise jobs are restored as
+/// [`import_jobs_json`] describes.
+pub async fn import_account(
+    client: &TursoClient,
+    user_id: &str,
+    json: &str,
+    mode: AccountImportMode,
+) -> Result<BackupImportReport, String> {
+    let backup = parse_backup(json)?;
+    let mut report = BackupImportReport::default();
+
+    if mode == AccountImportMode::Replace {
+        let existing = all_jobs(client, user_id).await?;
+        let ids = existing.into_iter().map(|job| job.id).collect();
+        report.trashed = delete_cron_jobs(client, user_id, ids).await?.succeeded.len();
+    }
+    restore_jobs(client, user_id, backup.jobs, &mut report).await?;
+
+    info!(
+        "Restored cron jobs for user {} from backup ({:?}): {} trashed, {} created, {} failed",
+        user_id, mode, report.trashed, report.created, report.failed
+    );
+    Ok(report)
+}
+
+/// Move jobs from one account to another, e.g. when a team hands work over.
+///
+/// Each database belongs to one user, so every job is copied into `to_user`'s database
+/// as a new job (new id, same settings) through the same validation and quota as
+/// `create_cron_job`. Job secrets and run history stay behind. With `delete_originals`
+/// the jobs copied successfully are then moved to `from_user`'s trash.
+///
+/// The two databases can't share a transaction, so consistency is best effort: a job
+/// that fails to copy is reported and left where it was. A database error stops the
+/// transfer: the jobs copied so far stay copied (and are trashed with
+/// `delete_originals`), and the report comes back with the error in
+/// [`JobTransferReport::error`]. The destination must already have a provisioned
+/// database.
+///
+/// ```
+/// use cron_jobs::server::error::ServerError;
+/// use cron_jobs::server::models::{CreateCronJobRequest, ListCronJobsFilter, Pagination};
+/// use cron_jobs::server::service::{
+///     create_cron_job, create_user_database, get_user_cron_jobs, transfer_jobs,
+/// };
+/// use cron_jobs::server::turso::TursoClient;
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let client = TursoClient::new_in_memory().await.unwrap();
+///     create_user_database(&client, "alice", "alice@example.com", None).await.unwrap();
+///     create_user_database(&client, "bob", "bob@example.com", None).await.unwrap();
+///
+///     let mut ids = Vec::new();
+///     for name in ["Backup", "Report"] {
+///         let request = CreateCronJobRequest::builder()
+///             .name(name)
+///             .schedule("0 3 * * *")
+///             .http("GET", "https://example.com/ping")
+///             .build()
+///             .unwrap();
+///         ids.push(create_cron_job(&client, "alice", request).await.unwrap().id);
+///     }
+///
+///     let report = transfer_jobs(&client, "alice", "bob", vec![ids[0].clone()], true)
+///         .await
+///         .unwrap();
+///     assert_eq!((report.transferred, report.failed, report.originals_trashed), (1, 0, 1));
+///
+///     let jobs = |user| get_user_cron_jobs(&client, user, ListCronJobsFilter::default(), Pagination::default());
+///     let bob = jobs("bob").await.unwrap();
+///     assert_eq!(bob.jobs.len(), 1);
+///     assert_eq!(bob.jobs[0].name, "Backup");
+///     assert_ne!(bob.jobs[0].id, ids[0]);
+///     assert_eq!(jobs("alice").await.unwrap().jobs[0].id, ids[1]);
+///
+///     // The destination needs a database first
+///     let result = transfer_jobs(&client, "alice", "carol", vec![ids[1].clone()], false).await;
+///     assert!(matches!(result, Err(ServerError::Validation(_))));
+/// });
+/// ```
+pub async fn transfer_jobs(
+    client: &TursoClient,
+    from_user: &str,
+    to_user: &str,
+    job_ids: Vec<String>,
+    delete_originals: bool,
+) -> Result<JobTransferReport, ServerError> {
+    if from_user == to_user {
+        return Err(ServerError::Validation(
+            "Jobs can't be transferred to the account that owns them".to_string(),
+        ));
+    }
+    match client.get_user_database_entry(to_user).await {
+        Ok(entry) if entry.provision_status == ProvisionStatus::Ready => {}
+        Ok(_) | Err(ServerError::NotFound(_)) => {
+            return Err(ServerError::Validation(format!(
+                "User {} has no provisioned database to transfer jobs to",
+                to_user
+            )))
+        }
+        Err(e) => return Err(e),
+    }
+
+    let mut report = JobTransferReport::default();
+    let mut copied = Vec::new();
+    for source_job_id in job_ids {
+        let created = match get_cron_job(client, from_user, &source_job_id).await {
+            Ok(job) => create_cron_job(client, to_user, BackupJob::from(job).into()).await,
+            Err(e) => Err(e),
+        };
+        let result = match created {
+            Ok(job) => {
+                copied.push(source_job_id.clone());
+                JobTransferResult {
+                    source_job_id,
+                    job_id: Some(job.id),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                if matches!(e, ServerError::Database(_) | ServerError::TursoApi(_)) {
+                    report.error = Some(e.to_string());
+                }
+                JobTransferResult {
+                    source_job_id,
+                    job_id: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        if result.error.is_none() {
+            report.transferred += 1;
+        } else {
+            report.failed += 1;
+        }
+        report.jobs.push(result);
+        if report.error.is_some() {
+            break;
+        }
+    }
+
+    if delete_originals && !copied.is_empty() {
+        match delete_cron_jobs(client, from_user, copied).await {
+            Ok(deleted) => report.originals_trashed = deleted.succeeded.len(),
+            Err(e) => {
+                warn!("Failed to trash transferred originals of user {}: {}", from_user, e);
+                report.error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+
+    info!(
+        "Transferred cron jobs from user {} to user {}: {} transferred, {} failed, {} originals trashed",
+        from_user, to_user, report.transferred, report.failed, report.originals_trashed
+    );
+    Ok(report)
+}
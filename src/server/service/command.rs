@@ -0,0 +1,56 @@
+use crate::server::models::CommandSpec;
+
+/// Methods an HTTP job may use
+const HTTP_METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Check a job's command before it is saved.
+///
+/// Shell commands must not be empty. HTTP jobs need an absolute `http` or `https` URL
+/// with a host and one of the supported methods, and `GET` requests cannot carry a body.
+pub fn validate_command_spec(spec: &CommandSpec) -> Result<(), String> {
+    match spec {
+        CommandSpec::Shell { command } => {
+            if command.trim().is_empty() {
+                return Err("Command must not be empty".to_string());
+            }
+        }
+        CommandSpec::Http { url, method, body } => {
+            let parsed = url::Url::parse(url.trim())
+                .map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(format!(
+                    "URL '{}' must use http or https, found {}",
+                    url,
+                    parsed.scheme()
+                ));
+            }
+            if parsed.host_str().is_none() {
+                return Err(format!("URL '{}' has no host", url));
+            }
+            if !HTTP_METHODS.contains(&method.as_str()) {
+                return Err(format!(
+                    "Unsupported HTTP method '{}' (expected one of {})",
+                    method,
+                    HTTP_METHODS.join(", ")
+                ));
+            }
+            if method == "GET" && body.is_some() {
+                return Err("GET requests cannot have a body".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Refuse shell commands unless the operator has enabled them with `ALLOW_SHELL_JOBS`.
+///
+/// Shell jobs run with `sh -c` on the web server itself, so in a shared deployment they
+/// are off and HTTP jobs are the only type users can create or run.
+pub fn check_job_type_allowed(spec: &CommandSpec, allow_shell_jobs: bool) -> Result<(), String> {
+    match spec {
+        CommandSpec::Shell { .. } if !allow_shell_jobs => {
+            Err("Shell jobs are disabled on this server; use an HTTP job instead".to_string())
+        }
+        _ => Ok(()),
+    }
+}
@@ -1,78 +1,580 @@
-use crate::server::models::{CronJob, CreateCronJobRequest, UpdateCronJobRequest};
-use crate::server::turso::TursoClient;
+use crate::server::error::ServerError;
+use crate::server::models::{
+    BatchFailure, BatchResult, CommandSpec, CronJob, CronJobPage, CronJobStats, CreateCronJobRequest, JobType,
+    ListCronJobsFilter, MisfirePolicy, Pagination, UpdateCronJobRequest, MAX_PAGE_SIZE,
+};
+use crate::server::service::command::{check_job_type_allowed, validate_command_spec};
+use crate::server::service::schedule::{
+    check_schedule_fireable, describe_schedule, normalize_schedule, validate_schedule,
+};
+use crate::server::service::schedule_cache::cached_next_run;
+use crate::server::service::timezone::normalize_timezone;
+use crate::server::turso::row::{
+    get_bool, get_i64, get_optional_i64, get_optional_timestamp, sqlite_timestamp,
+};
+use crate::server::turso::schema::get_table_columns;
+use crate::server::turso::{execute_with_count, TursoClient};
+use libsql::Connection;
+use log::error;
+use chrono::{Duration, Utc};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+/// Columns read into a `CronJob`, in the order `cron_job_from_row` expects
+const JOB_COLUMNS: [&str; 18] = [
+    "id", "user_id", "name", "schedule", "command", "enabled",
+    "created_at", "updated_at", "timezone", "deleted_at",
+    "job_type", "http_method", "http_body", "max_retries", "retry_backoff_secs",
+    "run_history_limit", "misfire_policy", "max_catchup",
+];
+
+/// Columns added to `cron_jobs` after the initial schema, with the value read for them
+/// while a user's database has not been migrated yet
+const OPTIONAL_JOB_COLUMNS: [(&str, &str); 10] = [
+    ("timezone", "'UTC'"),
+    ("deleted_at", "NULL"),
+    ("job_type", "'shell'"),
+    ("http_method", "NULL"),
+    ("http_body", "NULL"),
+    ("max_retries", "0"),
+    ("retry_backoff_secs", "0"),
+    ("run_history_limit", "NULL"),
+    ("misfire_policy", "'skip'"),
+    ("max_catchup", "NULL"),
+];
+
+/// Table expression to read cron jobs from.
+///
+/// Normally just `cron_jobs`. If the user's database is missing optional columns, a
+/// subquery supplies their defaults under the same names, so listing keeps working
+/// before the schema sync has run.
+async fn job_source(client: &TursoClient, user_id: &str) -> Result<String, ServerError> {
+    let columns = client.get_table_columns(user_id, "cron_jobs").await?;
+    Ok(job_source_for(&columns))
+}
+
+/// Table expression to read cron jobs from, given the columns `cron_jobs` has
+fn job_source_for(columns: &HashSet<String>) -> String {
+    let missing: Vec<(&str, &str)> = OPTIONAL_JOB_COLUMNS
+        .into_iter()
+        .filter(|(name, _)| !columns.contains(*name))
+        .collect();
+
+    if missing.is_empty() {
+        return "cron_jobs".to_string();
+    }
+
+    let select_list: Vec<String> = JOB_COLUMNS
+        .iter()
+        .map(|column| match missing.iter().find(|(name, _)| name == column) {
+            Some((name, default)) => format!("{} AS {}", default, name),
+            None => column.to_string(),
+        })
+        .collect();
+
+    format!("(SELECT {} FROM cron_jobs) AS cron_jobs", select_list.join(", "))
+}
+
+/// Build a `CronJob` from a row selected as
+/// `id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at,
+/// job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit,
+/// misfire_policy, max_catchup`
+fn cron_job_from_row(row: &libsql::Row) -> Result<CronJob, ServerError> {
+    let schedule = row.get::<String>(3).map_err(|e| ServerError::Database(format!("Failed to get schedule: {}", e)))?;
+
+    let mut job = CronJob {
+        id: row.get::<String>(0).map_err(|e| ServerError::Database(format!("Failed to get id: {}", e)))?,
+        user_id: row.get::<String>(1).map_err(|e| ServerError::Database(format!("Failed to get user_id: {}", e)))?,
+        name: row.get::<String>(2).map_err(|e| ServerError::Database(format!("Failed to get name: {}", e)))?,
+        command: row.get::<String>(4).map_err(|e| ServerError::Database(format!("Failed to get command: {}", e)))?,
+        next_run: None,
+        schedule_human: Some(describe_schedule(&schedule)),
+        schedule,
+        enabled: get_bool(row, 5, "enabled").map_err(ServerError::Database)?,
+        job_type: JobType::parse(
+            &row.get::<String>(10).map_err(|e| ServerError::Database(format!("Failed to get job_type: {}", e)))?,
+        ).map_err(ServerError::Database)?,
+        http_method: row.get::<Option<String>>(11).map_err(|e| ServerError::Database(format!("Failed to get http_method: {}", e)))?,
+        http_body: row.get::<Option<String>>(12).map_err(|e| ServerError::Database(format!("Failed to get http_body: {}", e)))?,
+        max_retries: get_i64(row, 13, "max_retries").map_err(ServerError::Database)?,
+        retry_backoff_secs: get_i64(row, 14, "retry_backoff_secs").map_err(ServerError::Database)?,
+        run_history_limit: get_optional_i64(row, 15, "run_history_limit").map_err(ServerError::Database)?,
+        misfire_policy: MisfirePolicy::parse(
+            &row.get::<String>(16).map_err(|e| ServerError::Database(format!("Failed to get misfire_policy: {}", e)))?,
+        ).map_err(ServerError::Database)?,
+        max_catchup: get_optional_i64(row, 17, "max_catchup").map_err(ServerError::Database)?,
+        timezone: row.get::<String>(8).map_err(|e| ServerError::Database(format!("Failed to get timezone: {}", e)))?,
+        deleted_at: row.get::<Option<String>>(9).map_err(|e| ServerError::Database(format!("Failed to get deleted_at: {}", e)))?,
+        created_at: get_optional_timestamp(row, 6, "created_at").map_err(ServerError::Database)?,
+        updated_at: get_optional_timestamp(row, 7, "updated_at").map_err(ServerError::Database)?,
+        warning: None,
+    };
+    // Dashboards poll the same jobs over and over, so next runs come from the cache
+    if job.enabled {
+        job.next_run = cached_next_run(&job, Utc::now());
+    }
+    Ok(job)
+}
+
+/// `http_method`/`http_body` column values for a command (both NULL for shell jobs)
+fn http_columns(spec: &CommandSpec) -> (Option<String>, Option<String>) {
+    match spec {
+        CommandSpec::Shell { .. } => (None, None),
+        CommandSpec::Http { method, body, .. } => (Some(method.clone()), body.clone()),
+    }
+}
+
+/// Check a retry policy: both values must be non-negative
+pub fn validate_retry_policy(max_retries: i64, retry_backoff_secs: i64) -> Result<(), String> {
+    if max_retries < 0 {
+        return Err("max_retries must not be negative".to_string());
+    }
+    if retry_backoff_secs < 0 {
+        return Err("retry_backoff_secs must not be negative".to_string());
+    }
+    Ok(())
+}
+
+/// Check a per-job run history limit
+pub fn validate_run_history_limit(limit: i64) -> Result<(), String> {
+    if limit < 1 {
+        return Err("run_history_limit must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/// Check a per-job catch-up bound
+pub fn validate_max_catchup(max: i64) -> Result<(), String> {
+    if max < 1 {
+        return Err("max_catchup must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/// Check a job name, returning it without surrounding whitespace
+pub fn validate_job_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name is required".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Escape `%`, `_` and `\` so user input is matched literally by `LIKE ... ESCAPE '\'`
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Build the `WHERE` clause for a listing along with its bound parameters
+fn filter_clause(user_id: &str, filter: &ListCronJobsFilter) -> (String, Vec<libsql::Value>) {
+    let mut conditions = vec!["user_id = ?".to_string(), "deleted_at IS NULL".to_string()];
+    let mut params = vec![libsql::Value::from(user_id.to_string())];
+
+    if filter.enabled_only {
+        conditions.push("enabled = 1".to_string());
+    }
+
+    let text_filters = [
+        ("name", &filter.name_contains),
+        ("command", &filter.command_contains),
+    ];
+    for (column, value) in text_filters {
+        if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+            conditions.push(format!("{} LIKE ? ESCAPE '\\'", column));
+            params.push(libsql::Value::from(like_pattern(value)));
+        }
+    }
+
+    (conditions.join(" AND "), params)
+}
+
+/// Fetch one page of a user's cron jobs matching `filter`, newest first
 pub async fn get_user_cron_jobs(
     client: &TursoClient,
     user_id: &str,
-) -> Result<Vec<CronJob>, String> {
+    filter: ListCronJobsFilter,
+    pagination: Pagination,
+) -> Result<CronJobPage, ServerError> {
     let conn = client.get_user_database_connection(user_id).await?;
+    let limit = pagination.limit();
+    let offset = pagination.offset();
+    let (where_clause, params) = filter_clause(user_id, &filter);
+    let source = job_source(client, user_id).await?;
 
     let mut rows = conn
-        .prepare("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at FROM cron_jobs WHERE user_id = ? ORDER BY created_at DESC")
+        .prepare(&format!("SELECT COUNT(*) FROM {} WHERE {}", source, where_clause))
         .await
-        .map_err(|e| format!("Failed to prepare query: {}", e))?
-        .query(libsql::params![user_id])
+        .map_err(|e| ServerError::Database(format!("Failed to prepare count query: {}", e)))?
+        .query(libsql::params_from_iter(params.clone()))
         .await
-        .map_err(|e| format!("Failed to query cron jobs: {}", e))?;
+        .map_err(|e| ServerError::Database(format!("Failed to count cron jobs: {}", e)))?;
+
+    let total = match rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        Some(row) => get_i64(&row, 0, "count").map_err(ServerError::Database)?.max(0) as u64,
+        None => 0,
+    };
+
+    let mut page_params = params;
+    page_params.push(libsql::Value::from(limit));
+    page_params.push(libsql::Value::from(offset));
+
+    let mut rows = conn
+        .prepare(&format!(
+            "SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM {} WHERE {} ORDER BY created_at DESC LIMIT ? OFFSET ?",
+            source, where_clause
+        ))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params_from_iter(page_params))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to query cron jobs: {}", e)))?;
 
     let mut jobs = Vec::new();
     while let Some(row) = rows
         .next()
         .await
-        .map_err(|e| format!("Failed to get row: {}", e))?
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
     {
-        let job = CronJob {
-            id: row.get::<String>(0).map_err(|e| format!("Failed to get id: {}", e))?,
-            user_id: row.get::<String>(1).map_err(|e| format!("Failed to get user_id: {}", e))?,
-            name: row.get::<String>(2).map_err(|e| format!("Failed to get name: {}", e))?,
-            schedule: row.get::<String>(3).map_err(|e| format!("Failed to get schedule: {}", e))?,
-            command: row.get::<String>(4).map_err(|e| format!("Failed to get command: {}", e))?,
-            enabled: row.get::<i64>(5).map_err(|e| format!("Failed to get enabled: {}", e))? != 0,
-            created_at: None, // TODO: Parse timestamp if needed
-            updated_at: None,  // TODO: Parse timestamp if needed
-        };
+        let job = cron_job_from_row(&row)?;
         jobs.push(job);
     }
 
+    Ok(CronJobPage {
+        jobs,
+        total,
+        limit,
+        offset,
+    })
+}
+
+/// How many live jobs the user has, enabled and disabled, counted in the database.
+/// Jobs in the trash are not counted.
+pub async fn get_cron_job_stats(client: &TursoClient, user_id: &str) -> Result<CronJobStats, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+    let source = job_source(client, user_id).await?;
+
+    let mut rows = conn
+        .prepare(&format!(
+            "SELECT enabled, COUNT(*) FROM {} WHERE user_id = ? AND deleted_at IS NULL GROUP BY enabled",
+            source
+        ))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare count query: {}", e)))?
+        .query(libsql::params![user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to count cron jobs: {}", e)))?;
+
+    let mut stats = CronJobStats::default();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        let count = get_i64(&row, 1, "count").map_err(ServerError::Database)?.max(0) as u64;
+        if get_bool(&row, 0, "enabled").map_err(ServerError::Database)? {
+            stats.enabled += count;
+        } else {
+            stats.disabled += count;
+        }
+    }
+    stats.total = stats.enabled + stats.disabled;
+
+    Ok(stats)
+}
+
+/// Number of live jobs the user has. Jobs in the trash are not counted.
+pub async fn count_cron_jobs(client: &TursoClient, user_id: &str) -> Result<u64, ServerError> {
+    Ok(get_cron_job_stats(client, user_id).await?.total)
+}
+
+/// Fail when `new_jobs` more live jobs would take the user past `MAX_JOBS_PER_USER`
+pub(crate) async fn check_job_limit(client: &TursoClient, user_id: &str, new_jobs: u64) -> Result<(), ServerError> {
+    let Some(max_jobs) = client.max_jobs_per_user() else {
+        return Ok(());
+    };
+    if new_jobs == 0 {
+        return Ok(());
+    }
+    let jobs = count_cron_jobs(client, user_id).await?;
+    if jobs + new_jobs > max_jobs {
+        return Err(ServerError::QuotaExceeded(if new_jobs == 1 {
+            format!("Job limit reached: {} of {} jobs used", jobs, max_jobs)
+        } else {
+            format!(
+                "Job limit exceeded: {} new jobs requested with {} of {} jobs used",
+                new_jobs, jobs, max_jobs
+            )
+        }));
+    }
+    Ok(())
+}
+
+/// All enabled, non-deleted cron jobs of a user
+pub async fn get_enabled_cron_jobs(
+    client: &TursoClient,
+    user_id: &str,
+) -> Result<Vec<CronJob>, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+    let source = job_source(client, user_id).await?;
+
+    let mut rows = conn
+        .prepare(&format!("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM {} WHERE user_id = ? AND enabled = 1 AND deleted_at IS NULL", source))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to query enabled cron jobs: {}", e)))?;
+
+    let mut jobs = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        jobs.push(cron_job_from_row(&row)?);
+    }
+
+    Ok(jobs)
+}
+
+/// All enabled, non-deleted jobs in one user database, whoever they belong to.
+///
+/// Filters on `enabled` so SQLite can use `idx_cron_jobs_enabled`. Databases whose
+/// schema hasn't been synced yet are read with defaults for the missing columns.
+pub async fn get_enabled_jobs(conn: &Connection) -> Result<Vec<CronJob>, String> {
+    let columns: HashSet<String> = get_table_columns(conn, "cron_jobs")
+        .await?
+        .into_iter()
+        .map(|column| column.name)
+        .collect();
+
+    let mut rows = conn
+        .prepare(&format!(
+            "SELECT {} FROM {} WHERE enabled = 1 AND deleted_at IS NULL",
+            JOB_COLUMNS.join(", "),
+            job_source_for(&columns)
+        ))
+        .await
+        .map_err(|e| format!("Failed to prepare query: {}", e))?
+        .query(())
+        .await
+        .map_err(|e| format!("Failed to query enabled cron jobs: {}", e))?;
+
+    let mut jobs = Vec::new();
+    while let Some(row) = rows.next().await.map_err(|e| format!("Failed to get row: {}", e))? {
+        jobs.push(cron_job_from_row(&row)?);
+    }
+    Ok(jobs)
+}
+
+/// Enabled jobs of every ready user database (used by the scheduler).
+///
+/// Databases are queried through [`TursoClient::visit_user_databases`], so at most
+/// `USER_DB_CONCURRENCY` at a time. One that fails is logged and left out rather than
+/// failing the rest; only an unreadable registry is an error.
+///
+/// ```
+/// use cron_jobs::server::models::CreateCronJobRequest;
+/// use cron_jobs::server::service::{create_cron_job, create_user_database, get_all_enabled_jobs};
+/// use cron_jobs::server::turso::TursoClient;
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let client = TursoClient::new_in_memory().await.unwrap();
+///     create_user_database(&client, "alice", "alice@example.com", None).await.unwrap();
+///     create_user_database(&client, "bob", "bob@example.com", None).await.unwrap();
+///
+///     for (user, enabled) in [("alice", true), ("alice", false), ("bob", true)] {
+///         let request = CreateCronJobRequest::builder()
+///             .name(format!("{} job", user))
+///             .schedule("*/5 * * * *")
+///             .http("GET", "https://example.com/ping")
+///             .enabled(enabled)
+///             .build()
+///             .unwrap();
+///         create_cron_job(&client, user, request).await.unwrap();
+///     }
+///
+///     let mut owners: Vec<String> = get_all_enabled_jobs(&client)
+///         .await
+///         .unwrap()
+///         .into_iter()
+///         .map(|job| job.user_id)
+///         .collect();
+///     owners.sort();
+///     assert_eq!(owners, ["alice", "bob"]);
+/// });
+/// ```
+pub async fn get_all_enabled_jobs(client: &TursoClient) -> Result<Vec<CronJob>, ServerError> {
+    let results = client
+        .visit_user_databases(|conn| async move {
+            get_enabled_jobs(&conn).await.map_err(ServerError::Database)
+        })
+        .await?;
+
+    let mut jobs = Vec::new();
+    for (user_id, result) in results {
+        match result {
+            Ok(user_jobs) => jobs.extend(user_jobs),
+            Err(e) => error!("Failed to load enabled jobs for user {}: {}", user_id, e),
+        }
+    }
     Ok(jobs)
 }
 
+/// Create a job for the user after validating it and checking their quotas.
+///
+/// The job is read back after the insert, so the returned job carries the id,
+/// normalized schedule and timestamps the database stored.
+///
+/// ```
+/// use chrono::Utc;
+/// use cron_jobs::server::models::CreateCronJobRequest;
+/// use cron_jobs::server::service::{create_cron_job, create_user_database};
+/// use cron_jobs::server::turso::TursoClient;
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let client = TursoClient::new_in_memory().await.unwrap();
+///     create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+///
+///     let request = CreateCronJobRequest::builder()
+///         .name("Nightly backup")
+///         .schedule("0 3 * * *")
+///         .http("POST", "https://example.com/backup")
+///         .build()
+///         .unwrap();
+///     let job = create_cron_job(&client, "user-1", request).await.unwrap();
+///
+///     let created_at = job.created_at.expect("created_at is set");
+///     assert!((Utc::now() - created_at).num_seconds().abs() < 60);
+///     assert_eq!(job.updated_at, Some(created_at));
+/// });
+/// ```
 pub async fn create_cron_job(
     client: &TursoClient,
     user_id: &str,
     request: CreateCronJobRequest,
-) -> Result<CronJob, String> {
+) -> Result<CronJob, ServerError> {
+    client.check_quota(user_id).await?;
+    check_job_limit(client, user_id, 1).await?;
+    let name = validate_job_name(&request.name).map_err(ServerError::Validation)?;
+    validate_schedule(&request.schedule).map_err(ServerError::Validation)?;
+    let schedule = normalize_schedule(&request.schedule).map_err(ServerError::Validation)?;
+    let timezone = normalize_timezone(request.timezone.as_deref().unwrap_or("UTC")).map_err(ServerError::Validation)?;
+    let job_type = request.job_type.unwrap_or_default();
+    let spec = CommandSpec::new(
+        job_type,
+        &request.command,
+        request.http_method.as_deref(),
+        request.http_body.as_deref(),
+    );
+    validate_command_spec(&spec).map_err(ServerError::Validation)?;
+    check_job_type_allowed(&spec, client.allow_shell_jobs()).map_err(ServerError::Validation)?;
+    let (http_method, http_body) = http_columns(&spec);
+    let max_retries = request.max_retries.unwrap_or(0);
+    let retry_backoff_secs = request.retry_backoff_secs.unwrap_or(0);
+    validate_retry_policy(max_retries, retry_backoff_secs).map_err(ServerError::Validation)?;
+    if let Some(limit) = request.run_history_limit {
+        validate_run_history_limit(limit).map_err(ServerError::Validation)?;
+    }
+    let misfire_policy = request.misfire_policy.unwrap_or_default();
+    if let Some(max) = request.max_catchup {
+        validate_max_catchup(max).map_err(ServerError::Validation)?;
+    }
+
     let conn = client.get_user_database_connection(user_id).await?;
 
     let id = Uuid::new_v4().to_string();
     let enabled = request.enabled.unwrap_or(true);
+    let warning = check_schedule_fireable(&schedule, enabled).map_err(ServerError::Validation)?;
 
     conn.execute(
-        "INSERT INTO cron_jobs (id, user_id, name, schedule, command, enabled)
-         VALUES (?, ?, ?, ?, ?, ?)",
+        "INSERT INTO cron_jobs (id, user_id, name, schedule, command, enabled, timezone, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         libsql::params![
             id.as_str(),
             user_id,
-            request.name.as_str(),
-            request.schedule.as_str(),
-            request.command.as_str(),
+            name.as_str(),
+            schedule.as_str(),
+            request.command.trim(),
             if enabled { 1 } else { 0 },
+            timezone.as_str(),
+            job_type.as_str(),
+            http_method,
+            http_body,
+            max_retries,
+            retry_backoff_secs,
+            request.run_history_limit,
+            misfire_policy.as_str(),
+            request.max_catchup,
         ],
     )
     .await
-    .map_err(|e| format!("Failed to create cron job: {}", e))?;
-
-    Ok(CronJob {
-        id,
-        user_id: user_id.to_string(),
-        name: request.name,
-        schedule: request.schedule,
-        command: request.command,
-        enabled,
-        created_at: None,
-        updated_at: None,
-    })
+    .map_err(|e| ServerError::Database(format!("Failed to create cron job: {}", e)))?;
+
+    let mut rows = conn
+        .prepare("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM cron_jobs WHERE id = ? AND user_id = ?")
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![id.as_str(), user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to fetch created cron job: {}", e)))?;
+
+    let row = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+        .ok_or_else(|| ServerError::NotFound("Cron job not found after create".to_string()))?;
+
+    let mut job = cron_job_from_row(&row)?;
+    job.warning = warning;
+    Ok(job)
+}
+
+/// Copy one of the user's jobs under `new_name`. The copy gets a fresh id, keeps the
+/// source's schedule, command and settings, and starts disabled so it doesn't run
+/// before it has been tweaked.
+pub async fn clone_cron_job(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+    new_name: String,
+) -> Result<CronJob, ServerError> {
+    let new_name = validate_job_name(&new_name).map_err(ServerError::Validation)?;
+
+    // Only finds jobs owned by the user, so other users' jobs can't be copied
+    let source = get_cron_job(client, user_id, job_id).await.map_err(|e| match e {
+        ServerError::NotFound(_) => ServerError::NotFound("Cron job not found or access denied".to_string()),
+        e => e,
+    })?;
+
+    create_cron_job(
+        client,
+        user_id,
+        CreateCronJobRequest {
+            name: new_name,
+            schedule: source.schedule,
+            command: source.command,
+            enabled: Some(false),
+            timezone: Some(source.timezone),
+            job_type: Some(source.job_type),
+            http_method: source.http_method,
+            http_body: source.http_body,
+            max_retries: Some(source.max_retries),
+            retry_backoff_secs: Some(source.retry_backoff_secs),
+            run_history_limit: source.run_history_limit,
+            misfire_policy: Some(source.misfire_policy),
+            max_catchup: source.max_catchup,
+        },
+    )
+    .await
 }
 
 pub async fn update_cron_job(
@@ -80,92 +582,504 @@ pub async fn update_cron_job(
     user_id: &str,
     job_id: &str,
     request: UpdateCronJobRequest,
-) -> Result<CronJob, String> {
+) -> Result<CronJob, ServerError> {
+    if request.is_empty() {
+        return Err(ServerError::Validation("Update must change at least one field".to_string()));
+    }
+
+    let new_schedule = match &request.schedule {
+        Some(schedule) => {
+            validate_schedule(schedule).map_err(ServerError::Validation)?;
+            Some(normalize_schedule(schedule).map_err(ServerError::Validation)?)
+        }
+        None => None,
+    };
+    let new_timezone = request
+        .timezone
+        .as_deref()
+        .map(normalize_timezone)
+        .transpose()
+        .map_err(ServerError::Validation)?;
+
     let conn = client.get_user_database_connection(user_id).await?;
 
     // First get the existing job to use current values for fields not being updated
     let mut rows = conn
-        .prepare("SELECT id, user_id, name, schedule, command, enabled FROM cron_jobs WHERE id = ? AND user_id = ?")
+        .prepare("SELECT id, user_id, name, schedule, command, enabled, timezone, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM cron_jobs WHERE id = ? AND user_id = ? AND deleted_at IS NULL")
         .await
-        .map_err(|e| format!("Failed to prepare query: {}", e))?
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
         .query(libsql::params![job_id, user_id])
         .await
-        .map_err(|e| format!("Failed to get existing cron job: {}", e))?;
+        .map_err(|e| ServerError::Database(format!("Failed to get existing cron job: {}", e)))?;
 
     let existing_row = rows
         .next()
         .await
-        .map_err(|e| format!("Failed to get row: {}", e))?
-        .ok_or_else(|| "Cron job not found or access denied".to_string())?;
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+        .ok_or_else(|| ServerError::NotFound("Cron job not found or access denied".to_string()))?;
 
-    let current_name: String = existing_row.get(2).map_err(|e| format!("Failed to get name: {}", e))?;
-    let current_schedule: String = existing_row.get(3).map_err(|e| format!("Failed to get schedule: {}", e))?;
-    let current_command: String = existing_row.get(4).map_err(|e| format!("Failed to get command: {}", e))?;
-    let current_enabled: i64 = existing_row.get(5).map_err(|e| format!("Failed to get enabled: {}", e))?;
-    let current_enabled = current_enabled != 0;
+    let current_name: String = existing_row.get(2).map_err(|e| ServerError::Database(format!("Failed to get name: {}", e)))?;
+    let current_schedule: String = existing_row.get(3).map_err(|e| ServerError::Database(format!("Failed to get schedule: {}", e)))?;
+    let current_command: String = existing_row.get(4).map_err(|e| ServerError::Database(format!("Failed to get command: {}", e)))?;
+    let current_enabled = get_bool(&existing_row, 5, "enabled").map_err(ServerError::Database)?;
+    let current_timezone: String = existing_row.get(6).map_err(|e| ServerError::Database(format!("Failed to get timezone: {}", e)))?;
+    let current_job_type = JobType::parse(
+        &existing_row.get::<String>(7).map_err(|e| ServerError::Database(format!("Failed to get job_type: {}", e)))?,
+    ).map_err(ServerError::Database)?;
+    let current_http_method: Option<String> = existing_row.get(8).map_err(|e| ServerError::Database(format!("Failed to get http_method: {}", e)))?;
+    let current_http_body: Option<String> = existing_row.get(9).map_err(|e| ServerError::Database(format!("Failed to get http_body: {}", e)))?;
+    let current_max_retries = get_i64(&existing_row, 10, "max_retries").map_err(ServerError::Database)?;
+    let current_retry_backoff_secs = get_i64(&existing_row, 11, "retry_backoff_secs").map_err(ServerError::Database)?;
+    let current_run_history_limit = get_optional_i64(&existing_row, 12, "run_history_limit").map_err(ServerError::Database)?;
+    let current_misfire_policy = MisfirePolicy::parse(
+        &existing_row.get::<String>(13).map_err(|e| ServerError::Database(format!("Failed to get misfire_policy: {}", e)))?,
+    ).map_err(ServerError::Database)?;
+    let current_max_catchup = get_optional_i64(&existing_row, 14, "max_catchup").map_err(ServerError::Database)?;
 
     // Use new values if provided, otherwise keep existing
-    let name = request.name.as_ref().unwrap_or(&current_name);
-    let schedule = request.schedule.as_ref().unwrap_or(&current_schedule);
+    let new_name = request.name.as_deref().map(validate_job_name).transpose().map_err(ServerError::Validation)?;
+    let name = new_name.as_ref().unwrap_or(&current_name);
+    let schedule = new_schedule.as_ref().unwrap_or(&current_schedule);
     let command = request.command.as_ref().unwrap_or(&current_command);
     let enabled = request.enabled.unwrap_or(current_enabled);
+    let timezone = new_timezone.as_ref().unwrap_or(&current_timezone);
+    let job_type = request.job_type.unwrap_or(current_job_type);
+    let spec = CommandSpec::new(
+        job_type,
+        command,
+        request.http_method.as_deref().or(current_http_method.as_deref()),
+        request.http_body.as_deref().or(current_http_body.as_deref()),
+    );
+    validate_command_spec(&spec).map_err(ServerError::Validation)?;
+    // Existing shell jobs can still be renamed, rescheduled or paused, just not given a
+    // new command
+    if request.command.is_some() || request.job_type.is_some() {
+        check_job_type_allowed(&spec, client.allow_shell_jobs()).map_err(ServerError::Validation)?;
+    }
+    let (http_method, http_body) = http_columns(&spec);
+    let max_retries = request.max_retries.unwrap_or(current_max_retries);
+    let retry_backoff_secs = request.retry_backoff_secs.unwrap_or(current_retry_backoff_secs);
+    validate_retry_policy(max_retries, retry_backoff_secs).map_err(ServerError::Validation)?;
+    if let Some(limit) = request.run_history_limit {
+        validate_run_history_limit(limit).map_err(ServerError::Validation)?;
+    }
+    let run_history_limit = request.run_history_limit.or(current_run_history_limit);
+    let misfire_policy = request.misfire_policy.unwrap_or(current_misfire_policy);
+    if let Some(max) = request.max_catchup {
+        validate_max_catchup(max).map_err(ServerError::Validation)?;
+    }
+    let max_catchup = request.max_catchup.or(current_max_catchup);
+    let warning = check_schedule_fireable(schedule, enabled).map_err(ServerError::Validation)?;
 
-    // Update the job
-    conn.execute(
-        "UPDATE cron_jobs SET name = ?, schedule = ?, command = ?, enabled = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ?",
+    // Update the job; it may have been moved to the trash since it was read
+    let affected = execute_with_count(
+        &conn,
+        "UPDATE cron_jobs SET name = ?, schedule = ?, command = ?, enabled = ?, timezone = ?, job_type = ?, http_method = ?, http_body = ?, max_retries = ?, retry_backoff_secs = ?, run_history_limit = ?, misfire_policy = ?, max_catchup = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
         libsql::params![
             name.as_str(),
             schedule.as_str(),
-            command.as_str(),
+            command.trim(),
             if enabled { 1 } else { 0 },
+            timezone.as_str(),
+            job_type.as_str(),
+            http_method,
+            http_body,
+            max_retries,
+            retry_backoff_secs,
+            run_history_limit,
+            misfire_policy.as_str(),
+            max_catchup,
             job_id,
             user_id,
         ],
     )
     .await
-    .map_err(|e| format!("Failed to update cron job: {}", e))?;
+    .map_err(|e| ServerError::Database(format!("Failed to update cron job: {}", e)))?;
+
+    if affected == 0 {
+        return Err(ServerError::NotFound("Cron job not found or access denied".to_string()));
+    }
 
     // Fetch and return updated job
     let mut rows = conn
-        .prepare("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at FROM cron_jobs WHERE id = ? AND user_id = ?")
+        .prepare("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM cron_jobs WHERE id = ? AND user_id = ?")
         .await
-        .map_err(|e| format!("Failed to prepare query: {}", e))?
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
         .query(libsql::params![job_id, user_id])
         .await
-        .map_err(|e| format!("Failed to fetch updated cron job: {}", e))?;
+        .map_err(|e| ServerError::Database(format!("Failed to fetch updated cron job: {}", e)))?;
 
     let row = rows
         .next()
         .await
-        .map_err(|e| format!("Failed to get row: {}", e))?
-        .ok_or_else(|| "Cron job not found after update".to_string())?;
-
-    Ok(CronJob {
-        id: row.get::<String>(0).map_err(|e| format!("Failed to get id: {}", e))?,
-        user_id: row.get::<String>(1).map_err(|e| format!("Failed to get user_id: {}", e))?,
-        name: row.get::<String>(2).map_err(|e| format!("Failed to get name: {}", e))?,
-        schedule: row.get::<String>(3).map_err(|e| format!("Failed to get schedule: {}", e))?,
-        command: row.get::<String>(4).map_err(|e| format!("Failed to get command: {}", e))?,
-        enabled: row.get::<i64>(5).map_err(|e| format!("Failed to get enabled: {}", e))? != 0,
-        created_at: None,
-        updated_at: None,
-    })
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+        .ok_or_else(|| ServerError::NotFound("Cron job not found after update".to_string()))?;
+
+    let mut job = cron_job_from_row(&row)?;
+    job.warning = warning;
+    Ok(job)
 }
 
+/// Move a cron job to the trash. It stops being listed and can be brought back
+/// with [`restore_cron_job`] until it is purged.
+///
+/// Fails with `NotFound` if the user has no live job with this id, so a no-op is never
+/// reported as a deletion.
 pub async fn delete_cron_job(
     client: &TursoClient,
     user_id: &str,
     job_id: &str,
-) -> Result<(), String> {
+) -> Result<(), ServerError> {
     let conn = client.get_user_database_connection(user_id).await?;
 
-    conn.execute(
-        "DELETE FROM cron_jobs WHERE id = ? AND user_id = ?",
+    let affected = execute_with_count(
+        &conn,
+        "UPDATE cron_jobs SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
         libsql::params![job_id, user_id],
     )
     .await
-    .map_err(|e| format!("Failed to delete cron job: {}", e))?;
+    .map_err(|e| ServerError::Database(format!("Failed to delete cron job: {}", e)))?;
+
+    if affected == 0 {
+        return Err(ServerError::NotFound("Cron job not found or access denied".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Move several cron jobs to the trash in one transaction.
+///
+/// Ids that do not match a live job owned by the user are skipped and reported; any
+/// database error rolls back the whole batch.
+pub async fn delete_cron_jobs(
+    client: &TursoClient,
+    user_id: &str,
+    job_ids: Vec<String>,
+) -> Result<BatchResult, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+
+    let tx = conn
+        .transaction()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to start transaction: {}", e)))?;
+
+    let mut result = BatchResult::default();
+    for job_id in job_ids {
+        let affected = execute_with_count(
+            &tx,
+            "UPDATE cron_jobs SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
+            libsql::params![job_id.as_str(), user_id],
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to delete cron job {}: {}", job_id, e)))?;
+
+        if affected == 0 {
+            result.skipped.push(job_id);
+        } else {
+            result.succeeded.push(job_id);
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to commit batch delete: {}", e)))?;
+
+    Ok(result)
+}
+
+/// Take a cron job back out of the trash
+pub async fn restore_cron_job(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+) -> Result<CronJob, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+
+    let affected = execute_with_count(
+        &conn,
+        "UPDATE cron_jobs SET deleted_at = NULL WHERE id = ? AND user_id = ? AND deleted_at IS NOT NULL",
+        libsql::params![job_id, user_id],
+    )
+    .await
+    .map_err(|e| ServerError::Database(format!("Failed to restore cron job: {}", e)))?;
+
+    if affected == 0 {
+        return Err(ServerError::NotFound("Cron job not found in trash or access denied".to_string()));
+    }
+
+    let mut rows = conn
+        .prepare("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM cron_jobs WHERE id = ? AND user_id = ?")
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![job_id, user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to fetch restored cron job: {}", e)))?;
+
+    let row = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+        .ok_or_else(|| ServerError::NotFound("Cron job not found after restore".to_string()))?;
+
+    cron_job_from_row(&row)
+}
+
+/// One of the user's cron jobs that is not in the trash
+pub async fn get_cron_job(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+) -> Result<CronJob, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+    let source = job_source(client, user_id).await?;
+
+    let mut rows = conn
+        .prepare(&format!("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM {} WHERE id = ? AND user_id = ? AND deleted_at IS NULL", source))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![job_id, user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to fetch cron job: {}", e)))?;
+
+    let row = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+        .ok_or_else(|| ServerError::NotFound("Cron job not found".to_string()))?;
+
+    cron_job_from_row(&row)
+}
+
+/// Fail when a request names more than [`MAX_PAGE_SIZE`] jobs, the most one page of
+/// the job list can select
+pub fn check_batch_size(job_ids: &[String]) -> Result<(), ServerError> {
+    if job_ids.len() > MAX_PAGE_SIZE as usize {
+        return Err(ServerError::Validation(format!(
+            "At most {} job ids can be requested at once",
+            MAX_PAGE_SIZE
+        )));
+    }
+    Ok(())
+}
+
+/// The user's live jobs among `job_ids`, in the order requested.
+///
+/// Ids that don't exist, are in the trash or belong to another user are left out, as
+/// are repeats. At most [`MAX_PAGE_SIZE`] ids may be requested at once.
+pub async fn get_cron_jobs_by_ids(
+    client: &TursoClient,
+    user_id: &str,
+    job_ids: Vec<String>,
+) -> Result<Vec<CronJob>, ServerError> {
+    if job_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    check_batch_size(&job_ids)?;
+
+    let conn = client.get_user_database_connection(user_id).await?;
+    let source = job_source(client, user_id).await?;
+
+    let mut rows = conn
+        .prepare(&format!(
+            "SELECT {} FROM {} WHERE user_id = ? AND deleted_at IS NULL AND id IN ({})",
+            JOB_COLUMNS.join(", "),
+            source,
+            in_placeholders(job_ids.len())
+        ))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params_from_iter(job_id_params(user_id, &job_ids)))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to fetch cron jobs: {}", e)))?;
+
+    let mut found = HashMap::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        let job = cron_job_from_row(&row)?;
+        found.insert(job.id.clone(), job);
+    }
+
+    Ok(job_ids.iter().filter_map(|id| found.remove(id)).collect())
+}
+
+/// Cron jobs in the trash, most recently deleted first
+pub async fn list_trashed_cron_jobs(
+    client: &TursoClient,
+    user_id: &str,
+) -> Result<Vec<CronJob>, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+    let source = job_source(client, user_id).await?;
+
+    let mut rows = conn
+        .prepare(&format!("SELECT id, user_id, name, schedule, command, enabled, created_at, updated_at, timezone, deleted_at, job_type, http_method, http_body, max_retries, retry_backoff_secs, run_history_limit, misfire_policy, max_catchup FROM {} WHERE user_id = ? AND deleted_at IS NOT NULL ORDER BY deleted_at DESC", source))
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to query trashed cron jobs: {}", e)))?;
+
+    let mut jobs = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        jobs.push(cron_job_from_row(&row)?);
+    }
+
+    Ok(jobs)
+}
+
+/// Permanently delete a cron job and its run history. Only jobs already in the trash can
+/// be purged.
+pub async fn purge_cron_job(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+) -> Result<(), ServerError> {
+    let affected = purge_where(
+        client,
+        user_id,
+        "id = ? AND user_id = ? AND deleted_at IS NOT NULL",
+        vec![job_id.into(), user_id.into()],
+    )
+    .await?;
+
+    if affected == 0 {
+        return Err(ServerError::NotFound("Cron job not found in trash or access denied".to_string()));
+    }
 
     Ok(())
 }
 
+/// Permanently delete jobs that have been in the trash for longer than `older_than`,
+/// along with their run history. Returns the number of jobs removed.
+pub async fn purge_deleted(
+    client: &TursoClient,
+    user_id: &str,
+    older_than: Duration,
+) -> Result<u64, ServerError> {
+    let cutoff = Utc::now() - older_than;
+
+    purge_where(
+        client,
+        user_id,
+        "user_id = ? AND deleted_at IS NOT NULL AND deleted_at < ?",
+        vec![user_id.into(), sqlite_timestamp(&cutoff).into()],
+    )
+    .await
+}
+
+/// Delete the jobs matching `condition`. Their `job_runs` rows go with them through the
+/// `ON DELETE CASCADE` foreign key.
+async fn purge_where(
+    client: &TursoClient,
+    user_id: &str,
+    condition: &str,
+    params: Vec<libsql::Value>,
+) -> Result<u64, ServerError> {
+    let conn = client.get_user_database_connection(user_id).await?;
+
+    execute_with_count(
+        &conn,
+        &format!("DELETE FROM cron_jobs WHERE {}", condition),
+        libsql::params_from_iter(params),
+    )
+    .await
+    .map_err(|e| ServerError::Database(format!("Failed to purge cron jobs: {}", e)))
+}
+
+/// `?, ?, ...` placeholder list for an `IN (...)` clause
+fn in_placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+/// Bound parameters for a statement scoped to `user_id` and a set of job ids
+fn job_id_params(user_id: &str, job_ids: &[String]) -> Vec<libsql::Value> {
+    std::iter::once(user_id.to_string())
+        .chain(job_ids.iter().cloned())
+        .map(libsql::Value::from)
+        .collect()
+}
+
+/// Enable or disable several jobs at once.
+///
+/// Runs in one transaction, so either every eligible job is updated or none are. Ids not
+/// matching a live job owned by the user are skipped. When enabling, jobs whose schedule
+/// can never fire are refused, as `create_cron_job` and `update_cron_job` refuse them.
+pub async fn bulk_update_enabled(
+    client: &TursoClient,
+    user_id: &str,
+    job_ids: &[String],
+    enabled: bool,
+) -> Result<BatchResult, ServerError> {
+    let mut result = BatchResult::default();
+    if job_ids.is_empty() {
+        return Ok(result);
+    }
+
+    let conn = client.get_user_database_connection(user_id).await?;
+    let tx = conn
+        .transaction()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to start transaction: {}", e)))?;
+
+    let mut rows = tx
+        .query(
+            &format!(
+                "SELECT id, schedule FROM cron_jobs WHERE user_id = ? AND deleted_at IS NULL AND id IN ({})",
+                in_placeholders(job_ids.len())
+            ),
+            libsql::params_from_iter(job_id_params(user_id, job_ids)),
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to fetch cron jobs: {}", e)))?;
+    let mut schedules = HashMap::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        let id = row.get::<String>(0).map_err(|e| ServerError::Database(format!("Failed to get id: {}", e)))?;
+        let schedule = row.get::<String>(1).map_err(|e| ServerError::Database(format!("Failed to get schedule: {}", e)))?;
+        schedules.insert(id, schedule);
+    }
+
+    for job_id in job_ids {
+        let Some(schedule) = schedules.get(job_id) else {
+            result.skipped.push(job_id.clone());
+            continue;
+        };
+        match check_schedule_fireable(schedule, enabled) {
+            Ok(_) => result.succeeded.push(job_id.clone()),
+            Err(error) => result.failed.push(BatchFailure {
+                job_id: job_id.clone(),
+                error,
+            }),
+        }
+    }
+
+    if !result.succeeded.is_empty() {
+        let mut params = vec![libsql::Value::from(if enabled { 1 } else { 0 })];
+        params.extend(job_id_params(user_id, &result.succeeded));
+        execute_with_count(
+            &tx,
+            &format!(
+                "UPDATE cron_jobs SET enabled = ?, updated_at = CURRENT_TIMESTAMP WHERE user_id = ? AND deleted_at IS NULL AND id IN ({})",
+                in_placeholders(result.succeeded.len())
+            ),
+            libsql::params_from_iter(params),
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to update cron jobs: {}", e)))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to commit bulk update: {}", e)))?;
+
+    Ok(result)
+}
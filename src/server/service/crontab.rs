@@ -0,0 +1,240 @@
+use crate::server::error::ServerError;
+use crate::server::models::{
+    CommandSpec, CronJob, CrontabImportReport, CrontabLineResult, ImportCronJobRow, ImportOutcome,
+};
+use crate::server::service::cron_service::get_enabled_cron_jobs;
+use crate::server::service::import_service::import_cron_jobs;
+use crate::server::service::schedule::five_field_form;
+use crate::server::turso::TursoClient;
+
+/// Prefix of the comment line written above each exported job, followed by
+/// `<name> [id=<id>]`. An import can use it to match lines back to jobs.
+pub const CRONTAB_JOB_MARKER: &str = "# job: ";
+
+/// Longest job name derived from a command when a line has no job marker
+const DERIVED_NAME_CHARS: usize = 60;
+
+/// The user's enabled jobs as a crontab file
+///
+/// See [`format_crontab`] for the format.
+pub async fn export_crontab(client: &TursoClient, user_id: &str) -> Result<String, ServerError> {
+    let mut jobs = get_enabled_cron_jobs(client, user_id).await?;
+    jobs.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    Ok(format_crontab(&jobs))
+}
+
+/// Render jobs as crontab lines, in the given order
+///
+/// Each job gets a [`CRONTAB_JOB_MARKER`] comment followed by a `schedule command` line.
+/// A `CRON_TZ=` line is written whenever the timezone differs from the previous job's.
+/// Seconds fields are dropped and `%` is escaped, since crontab treats it as a newline.
+/// HTTP jobs and multi-line commands can't be expressed in crontab and are written as
+/// comments instead.
+pub fn format_crontab(jobs: &[CronJob]) -> String {
+    let mut out = String::from("# Cron jobs exported from cron-jobs\n");
+    let mut timezone: Option<&str> = None;
+
+    for job in jobs {
+        out.push('\n');
+        out.push_str(&format!(
+            "{}{} [id={}]\n",
+            CRONTAB_JOB_MARKER,
+            job.name.replace(['\r', '\n'], " "),
+            job.id
+        ));
+
+        let command = match job.command_spec() {
+            CommandSpec::Shell { command } if !command.contains(['\r', '\n']) => command,
+            CommandSpec::Shell { .. } => {
+                out.push_str("# Skipped: multi-line commands can't be represented in crontab\n");
+                continue;
+            }
+            CommandSpec::Http { url, method, .. } => {
+                out.push_str(&format!(
+                    "# Skipped: HTTP job ({} {}) can't be represented in crontab\n",
+                    method, url
+                ));
+                continue;
+            }
+        };
+        // `@reboot` and anything already validated passes through unchanged
+        let schedule = five_field_form(&job.schedule).unwrap_or_else(|_| job.schedule.clone());
+
+        if timezone != Some(job.timezone.as_str()) {
+            out.push_str(&format!("CRON_TZ={}\n", job.timezone));
+            timezone = Some(&job.timezone);
+        }
+        out.push_str(&format!("{} {}\n", schedule, command.replace('%', "\\%")));
+    }
+
+    out
+}
+
+/// A job line of a crontab and the row parsed from it
+#[derive(Debug)]
+struct CrontabEntry {
+    line: usize,
+    row: Result<ImportCronJobRow, String>,
+}
+
+/// Parse the `name [id=<id>]` part of a job marker comment
+fn parse_marker(marker: &str) -> (String, Option<String>) {
+    let marker = marker.trim();
+    match marker.strip_suffix(']').and_then(|rest| rest.rsplit_once(" [id=")) {
+        Some((name, id)) => (name.trim().to_string(), Some(id.trim().to_string())),
+        None => (marker.to_string(), None),
+    }
+}
+
+/// Undo the `%` escaping of a crontab command. An unescaped `%` would start the
+/// command's stdin in crontab, which jobs can't express.
+fn unescape_command(command: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(command.len());
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'%') => {
+                out.push('%');
+                chars.next();
+            }
+            '%' => {
+                return Err("Unescaped '%' (command input) is not supported; write it as '\\%'".to_string())
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Split a job line into its schedule and command
+fn split_job_line(line: &str) -> Result<(String, String), String> {
+    let fields = if line.starts_with('@') { 1 } else { 5 };
+    let mut rest = line;
+    let mut schedule = Vec::with_capacity(fields);
+    for _ in 0..fields {
+        let field_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        schedule.push(&rest[..field_end]);
+        rest = rest[field_end..].trim_start();
+    }
+    if rest.is_empty() {
+        return Err("Missing command after the schedule".to_string());
+    }
+    Ok((schedule.join(" "), unescape_command(rest)?))
+}
+
+/// Name for a job without a marker: its command, shortened
+fn derived_name(command: &str) -> String {
+    match command.char_indices().nth(DERIVED_NAME_CHARS) {
+        Some((end, _)) => format!("{}...", &command[..end]),
+        None => command.to_string(),
+    }
+}
+
+/// Parse crontab text into job rows, keeping per-line errors.
+///
+/// `CRON_TZ=`/`TZ=` lines set the timezone of the jobs after them; other variable
+/// assignments are reported as errors. A [`CRONTAB_JOB_MARKER`] comment names the job on
+/// the next line, and its id becomes the idempotency key, so importing the same export
+/// twice updates the jobs instead of duplicating them.
+fn parse_crontab(text: &str) -> Vec<CrontabEntry> {
+    let mut entries = Vec::new();
+    let mut timezone: Option<String> = None;
+    let mut marker: Option<(String, Option<String>)> = None;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix(CRONTAB_JOB_MARKER.trim_end()) {
+            marker = Some(parse_marker(rest));
+            continue;
+        }
+        if line.starts_with('#') {
+            // Jobs the export couldn't represent are a marker followed by a comment
+            marker = None;
+            continue;
+        }
+
+        let row = match line.split_once('=') {
+            Some((variable, value))
+                if variable.trim().chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && line.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') =>
+            {
+                match variable.trim() {
+                    "CRON_TZ" | "TZ" => {
+                        timezone = Some(value.trim().trim_matches('"').to_string());
+                        continue;
+                    }
+                    variable => Err(format!(
+                        "Environment variable '{}' is not supported; set it on the job instead",
+                        variable
+                    )),
+                }
+            }
+            _ => {
+                let (name, id) = marker.take().unzip();
+                split_job_line(line).map(|(schedule, command)| ImportCronJobRow {
+                    idempotency_key: id.flatten().map(|id| format!("crontab:{}", id)),
+                    name: name.unwrap_or_else(|| derived_name(&command)),
+                    schedule,
+                    command,
+                    enabled: Some(true),
+                    timezone: timezone.clone(),
+                })
+            }
+        };
+        entries.push(CrontabEntry { line: index + 1, row });
+    }
+
+    entries
+}
+
+/// Import the jobs of a crontab file as enabled shell jobs.
+///
+/// Lines that don't parse are reported and skipped; the rest go through
+/// [`import_cron_jobs`], which validates each schedule and writes them in batches.
+/// With `dry_run` nothing is written and the report shows what the import would do.
+pub async fn import_crontab(
+    client: &TursoClient,
+    user_id: &str,
+    text: &str,
+    dry_run: bool,
+) -> Result<CrontabImportReport, ServerError> {
+    let mut lines = Vec::new();
+    let mut rows = Vec::new();
+    let mut row_lines = Vec::new();
+    for entry in parse_crontab(text) {
+        match entry.row {
+            Ok(row) => {
+                rows.push(row);
+                row_lines.push(entry.line);
+            }
+            Err(error) => lines.push(CrontabLineResult {
+                line: entry.line,
+                outcome: ImportOutcome::Skipped,
+                job_id: None,
+                normalized_schedule: None,
+                error: Some(error),
+            }),
+        }
+    }
+
+    let imported = import_cron_jobs(client, user_id, rows, dry_run).await?;
+    lines.extend(imported.rows.into_iter().map(|row| CrontabLineResult {
+        line: row_lines[row.index],
+        outcome: row.outcome,
+        job_id: row.job_id,
+        normalized_schedule: row.normalized_schedule,
+        error: row.error,
+    }));
+    lines.sort_by_key(|line| line.line);
+
+    let skipped = lines.iter().filter(|line| line.outcome == ImportOutcome::Skipped).count();
+    Ok(CrontabImportReport {
+        dry_run,
+        imported: lines.len() - skipped,
+        skipped,
+        lines,
+    })
+}
@@ -0,0 +1,360 @@
+use crate::server::error::ServerError;
+use crate::server::models::{
+    CommandSpec, ImportCronJobRow, ImportImpact, ImportOutcome, ImportReport, ImportRowResult, JobType,
+};
+use crate::server::service::command::{check_job_type_allowed, validate_command_spec};
+use crate::server::service::cron_service::{check_job_limit, validate_job_name};
+use crate::server::service::schedule::{check_schedule_fireable, normalize_schedule, validate_schedule};
+use crate::server::service::timezone::normalize_timezone;
+use crate::server::turso::TursoClient;
+use libsql::{Connection, Transaction};
+use log::info;
+use std::collections::HashSet;
+use std::env;
+use uuid::Uuid;
+
+/// Rows written per transaction, keeping each remote transaction short
+const IMPORT_CHUNK_SIZE: usize = 100;
+
+/// Approximate per-row cost beyond the text columns: id, integer and timestamp columns,
+/// record header and index entries
+const ROW_OVERHEAD_BYTES: u64 = 160;
+
+/// Row after validation and normalization, ready to write
+struct ValidatedRow {
+    name: String,
+    schedule: String,
+    command: String,
+    enabled: bool,
+    timezone: String,
+}
+
+/// Apply the checks `create_cron_job` makes to an imported row, which is a shell job
+fn validate_row(row: &ImportCronJobRow, allow_shell_jobs: bool) -> Result<ValidatedRow, String> {
+    let name = validate_job_name(&row.name)?;
+    validate_schedule(&row.schedule)?;
+    let schedule = normalize_schedule(&row.schedule)?;
+    let timezone = normalize_timezone(row.timezone.as_deref().unwrap_or("UTC"))?;
+    let command = row.command.trim().to_string();
+    let spec = CommandSpec::new(JobType::Shell, &command, None, None);
+    validate_command_spec(&spec)?;
+    check_job_type_allowed(&spec, allow_shell_jobs)?;
+    let enabled = row.enabled.unwrap_or(true);
+    check_schedule_fireable(&schedule, enabled)?;
+
+    Ok(ValidatedRow {
+        name,
+        schedule,
+        command,
+        enabled,
+        timezone,
+    })
+}
+
+/// Idempotency key of a row, ignoring blank keys
+fn row_key(row: &ImportCronJobRow) -> Option<&str> {
+    row.idempotency_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+/// Id of the job previously imported with `key`, if any
+async fn find_job_by_key(conn: &Connection, user_id: &str, key: &str) -> Result<Option<String>, String> {
+    let mut rows = conn
+        .query(
+            "SELECT id FROM cron_jobs WHERE user_id = ? AND idempotency_key = ? AND deleted_at IS NULL LIMIT 1",
+            libsql::params![user_id, key],
+        )
+        .await
+        .map_err(|e| format!("Failed to look up idempotency key '{}': {}", key, e))?;
+
+    match rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get row: {}", e))?
+    {
+        Some(row) => Ok(Some(
+            row.get::<String>(0).map_err(|e| format!("Failed to get id: {}", e))?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Insert or update one validated row, returning the job id and what was done
+async fn upsert_row(
+    tx: &Transaction,
+    user_id: &str,
+    key: Option<&str>,
+    row: &ValidatedRow,
+) -> Result<(String, ImportOutcome), String> {
+    let existing = match key {
+        Some(key) => find_job_by_key(tx, user_id, key).await?,
+        None => None,
+    };
+
+    if let Some(id) = existing {
+        tx.execute(
+            "UPDATE cron_jobs SET name = ?, schedule = ?, command = ?, enabled = ?, timezone = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ?",
+            libsql::params![
+                row.name.as_str(),
+                row.schedule.as_str(),
+                row.command.as_str(),
+                if row.enabled { 1 } else { 0 },
+                row.timezone.as_str(),
+                id.as_str(),
+                user_id,
+            ],
+        )
+        .await
+        .map_err(|e| format!("Failed to update cron job {}: {}", id, e))?;
+        return Ok((id, ImportOutcome::Updated));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO cron_jobs (id, user_id, name, schedule, command, enabled, timezone, idempotency_key)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        libsql::params![
+            id.as_str(),
+            user_id,
+            row.name.as_str(),
+            row.schedule.as_str(),
+            row.command.as_str(),
+            if row.enabled { 1 } else { 0 },
+            row.timezone.as_str(),
+            key,
+        ],
+    )
+    .await
+    .map_err(|e| format!("Failed to create cron job: {}", e))?;
+    Ok((id, ImportOutcome::Created))
+}
+
+/// Import cron jobs, upserting rows that carry an idempotency key.
+///
+/// Rows get the same checks as [`create_cron_job`](super::create_cron_job). The import is
+/// refused before anything is written when the user's storage quota is used up, or when
+/// the jobs it would create take them past `MAX_JOBS_PER_USER`. Rows are written in
+/// chunks of [`IMPORT_CHUNK_SIZE`], one transaction per chunk, so a database error only
+/// rolls back the chunk it happened in (earlier chunks stay committed). Invalid rows are
+/// skipped and reported rather than failing the import.
+/// With `dry_run` all rows are written in a single transaction that is rolled back, and
+/// the report shows what a real import would do.
+///
+/// Each imported row reports the schedule as stored in `normalized_schedule`, so macros
+/// and irregular spacing don't change a schedule silently. Named days and months are
+/// stored as written.
+///
+/// ```
+/// use cron_jobs::server::models::ImportCronJobRow;
+/// use cron_jobs::server::service::{create_user_database, import_cron_jobs};
+/// use cron_jobs::server::turso::{TursoClient, TursoConfig};
+///
+/// actix_web::rt::System::new().block_on(async {
+///     // Imported rows are shell jobs, which the operator has to allow
+///     let config = TursoConfig { allow_shell_jobs: true, ..TursoConfig::for_testing() };
+///     let client = TursoClient::new_in_memory_with_config(config).await.unwrap();
+///     create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+///
+///     let row = |schedule: &str| ImportCronJobRow {
+///         idempotency_key: None,
+///         name: "Weekday report".to_string(),
+///         schedule: schedule.to_string(),
+///         command: "echo report".to_string(),
+///         enabled: None,
+///         timezone: None,
+///     };
+///     let rows = vec![row("0  9 * *  MON-FRI"), row("@weekly")];
+///     let report = import_cron_jobs(&client, "user-1", rows, false).await.unwrap();
+///
+///     assert_eq!(report.rows[0].normalized_schedule.as_deref(), Some("0 9 * * MON-FRI"));
+///     assert_eq!(report.rows[1].normalized_schedule.as_deref(), Some("0 0 * * 0"));
+/// });
+/// ```
+pub async fn import_cron_jobs(
+    client: &TursoClient,
+    user_id: &str,
+    rows: Vec<ImportCronJobRow>,
+    dry_run: bool,
+) -> Result<ImportReport, ServerError> {
+    client.check_quota(user_id).await?;
+    let conn = client.get_user_database_connection(user_id).await?;
+    let validated: Vec<Result<ValidatedRow, String>> = rows
+        .iter()
+        .map(|row| validate_row(row, client.allow_shell_jobs()))
+        .collect();
+
+    // Check the job limit against every job the import would add, before any chunk commits
+    let mut seen_keys: HashSet<&str> = HashSet::new();
+    let mut new_jobs = 0;
+    for (row, validated) in rows.iter().zip(&validated) {
+        if validated.is_err() {
+            continue;
+        }
+        let creates = match row_key(row) {
+            Some(key) => {
+                seen_keys.insert(key)
+                    && find_job_by_key(&conn, user_id, key)
+                        .await
+                        .map_err(ServerError::Database)?
+                        .is_none()
+            }
+            None => true,
+        };
+        if creates {
+            new_jobs += 1;
+        }
+    }
+    check_job_limit(client, user_id, new_jobs).await?;
+
+    let mut report = ImportReport {
+        dry_run,
+        rows: Vec::with_capacity(rows.len()),
+    };
+
+    // A dry run is one transaction, so later rows see the keys of earlier ones exactly as
+    // in a real import
+    let chunk_size = if dry_run { rows.len().max(1) } else { IMPORT_CHUNK_SIZE };
+    for (chunk_index, chunk) in rows.chunks(chunk_size).enumerate() {
+        let tx = conn
+            .transaction()
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        for (offset, row) in chunk.iter().enumerate() {
+            let index = chunk_index * chunk_size + offset;
+            let key = row_key(row);
+
+            let result = match &validated[index] {
+                Ok(validated) => {
+                    let (job_id, outcome) = upsert_row(&tx, user_id, key, validated)
+                        .await
+                        .map_err(ServerError::Database)?;
+                    ImportRowResult {
+                        index,
+                        idempotency_key: key.map(str::to_string),
+                        outcome,
+                        job_id: Some(job_id),
+                        normalized_schedule: Some(validated.schedule.clone()),
+                        error: None,
+                    }
+                }
+                Err(e) => ImportRowResult {
+                    index,
+                    idempotency_key: key.map(str::to_string),
+                    outcome: ImportOutcome::Skipped,
+                    job_id: None,
+                    normalized_schedule: None,
+                    error: Some(e.clone()),
+                },
+            };
+            report.rows.push(result);
+        }
+
+        if dry_run {
+            tx.rollback()
+                .await
+                .map_err(|e| ServerError::Database(format!("Failed to roll back dry run: {}", e)))?;
+        } else {
+            tx.commit()
+                .await
+                .map_err(|e| ServerError::Database(format!("Failed to commit import chunk: {}", e)))?;
+        }
+    }
+
+    info!(
+        "Imported cron jobs for user {}: {} created, {} updated, {} skipped (dry run: {})",
+        user_id,
+        report.count(ImportOutcome::Created),
+        report.count(ImportOutcome::Updated),
+        report.count(ImportOutcome::Skipped),
+        dry_run
+    );
+
+    Ok(report)
+}
+
+/// Soft storage limit per user database from `STORAGE_SOFT_LIMIT_BYTES`, if set
+fn storage_soft_limit() -> Option<u64> {
+    env::var("STORAGE_SOFT_LIMIT_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Approximate bytes a new job row takes in the database
+fn estimated_row_bytes(row: &ValidatedRow, key: Option<&str>) -> u64 {
+    let text = row.name.len()
+        + row.schedule.len()
+        + row.command.len()
+        + row.timezone.len()
+        + key.map_or(0, str::len);
+    text as u64 + ROW_OVERHEAD_BYTES
+}
+
+/// Estimate how much an import would add to the user's storage, without writing.
+///
+/// Rows are validated and matched against existing idempotency keys the same way
+/// [`import_cron_jobs`] does; only rows that would create a job count towards the size.
+/// The result carries a warning when current usage plus the estimate exceeds the
+/// `STORAGE_SOFT_LIMIT_BYTES` soft limit.
+pub async fn estimate_import_impact(
+    client: &TursoClient,
+    user_id: &str,
+    rows: &[ImportCronJobRow],
+) -> Result<ImportImpact, ServerError> {
+    let entry = client.get_user_database_entry(user_id).await?;
+    let conn = client.get_user_database_connection(user_id).await?;
+
+    let mut impact = ImportImpact {
+        new_rows: 0,
+        updated_rows: 0,
+        skipped_rows: 0,
+        estimated_bytes: 0,
+        storage_used_bytes: entry.storage_used_bytes.unwrap_or(0).max(0) as u64,
+        soft_limit_bytes: storage_soft_limit(),
+        warning: None,
+    };
+    // Keys created earlier in the same document are updated by later rows
+    let mut seen_keys: HashSet<&str> = HashSet::new();
+
+    for row in rows {
+        let validated = match validate_row(row, client.allow_shell_jobs()) {
+            Ok(validated) => validated,
+            Err(_) => {
+                impact.skipped_rows += 1;
+                continue;
+            }
+        };
+        let key = row_key(row);
+
+        let exists = match key {
+            Some(key) => {
+                !seen_keys.insert(key)
+                    || find_job_by_key(&conn, user_id, key)
+                        .await
+                        .map_err(ServerError::Database)?
+                        .is_some()
+            }
+            None => false,
+        };
+        if exists {
+            impact.updated_rows += 1;
+        } else {
+            impact.new_rows += 1;
+            impact.estimated_bytes += estimated_row_bytes(&validated, key);
+        }
+    }
+
+    if let Some(limit) = impact.soft_limit_bytes {
+        let projected = impact.storage_used_bytes + impact.estimated_bytes;
+        if projected > limit {
+            impact.warning = Some(format!(
+                "This import adds about {} bytes, bringing storage to about {} of the {} byte limit",
+                impact.estimated_bytes, projected, limit
+            ));
+        }
+    }
+
+    Ok(impact)
+}
@@ -0,0 +1,153 @@
+use crate::server::error::ServerError;
+use crate::server::service::cron_service::get_cron_job;
+use crate::server::turso::{EncryptionKey, TursoClient};
+use std::collections::HashMap;
+
+/// Longest environment variable name accepted
+const MAX_ENV_KEY_LEN: usize = 128;
+
+/// Largest environment variable value accepted, in bytes
+const MAX_ENV_VALUE_BYTES: usize = 4096;
+
+/// Check that `key` is a portable environment variable name: letters, digits and `_`,
+/// not starting with a digit
+fn validate_env_key(key: &str) -> Result<(), ServerError> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(ServerError::Validation(format!(
+            "Invalid environment variable name '{}': use letters, digits and '_', not starting with a digit",
+            key
+        )));
+    }
+    if key.len() > MAX_ENV_KEY_LEN {
+        return Err(ServerError::Validation(format!(
+            "Environment variable name must be at most {} characters",
+            MAX_ENV_KEY_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn require_encryption_key(client: &TursoClient) -> Result<&EncryptionKey, ServerError> {
+    client
+        .encryption_key()
+        .ok_or_else(|| ServerError::Validation("Job secrets are not enabled on this server".to_string()))
+}
+
+/// Whether the user's database has the `cron_job_env` table yet
+async fn has_env_table(client: &TursoClient, user_id: &str) -> Result<bool, ServerError> {
+    Ok(!client.get_table_columns(user_id, "cron_job_env").await?.is_empty())
+}
+
+/// Set an environment variable for one of the user's jobs, replacing any previous value.
+/// The value is encrypted before it is stored.
+pub async fn set_job_env(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), ServerError> {
+    validate_env_key(key)?;
+    if value.len() > MAX_ENV_VALUE_BYTES {
+        return Err(ServerError::Validation(format!(
+            "Environment variable value must be at most {} bytes",
+            MAX_ENV_VALUE_BYTES
+        )));
+    }
+    let encryption_key = require_encryption_key(client)?;
+    get_cron_job(client, user_id, job_id).await?;
+    if !has_env_table(client, user_id).await? {
+        return Err(ServerError::Validation(
+            "Database schema is out of date; sync it before setting job secrets".to_string(),
+        ));
+    }
+
+    let encrypted = encryption_key.encrypt(value).map_err(ServerError::Database)?;
+    let conn = client.get_user_database_connection(user_id).await?;
+    conn.execute(
+        "INSERT INTO cron_job_env (job_id, key, value_encrypted) VALUES (?, ?, ?)
+         ON CONFLICT (job_id, key) DO UPDATE SET value_encrypted = excluded.value_encrypted, updated_at = CURRENT_TIMESTAMP",
+        libsql::params![job_id, key, encrypted],
+    )
+    .await
+    .map_err(|e| ServerError::Database(format!("Failed to set environment variable: {}", e)))?;
+
+    Ok(())
+}
+
+/// Names of the environment variables set for one of the user's jobs, sorted.
+/// Values are never returned.
+pub async fn list_job_env_keys(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+) -> Result<Vec<String>, ServerError> {
+    get_cron_job(client, user_id, job_id).await?;
+    if !has_env_table(client, user_id).await? {
+        return Ok(Vec::new());
+    }
+
+    let conn = client.get_user_database_connection(user_id).await?;
+    let mut rows = conn
+        .prepare("SELECT key FROM cron_job_env WHERE job_id = ? ORDER BY key")
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![job_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to list environment variables: {}", e)))?;
+
+    let mut keys = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        keys.push(row.get(0).map_err(|e| ServerError::Database(format!("Failed to get key: {}", e)))?);
+    }
+    Ok(keys)
+}
+
+/// Decrypted environment of a job, for running it. Must not be sent to clients.
+pub async fn get_job_env(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+) -> Result<HashMap<String, String>, ServerError> {
+    if !has_env_table(client, user_id).await? {
+        return Ok(HashMap::new());
+    }
+
+    let conn = client.get_user_database_connection(user_id).await?;
+    let mut rows = conn
+        .prepare(
+            "SELECT e.key, e.value_encrypted FROM cron_job_env e JOIN cron_jobs j ON j.id = e.job_id
+             WHERE e.job_id = ? AND j.user_id = ?",
+        )
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to prepare query: {}", e)))?
+        .query(libsql::params![job_id, user_id])
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to load environment variables: {}", e)))?;
+
+    let mut env = HashMap::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to get row: {}", e)))?
+    {
+        let key: String = row.get(0).map_err(|e| ServerError::Database(format!("Failed to get key: {}", e)))?;
+        let encrypted: String = row
+            .get(1)
+            .map_err(|e| ServerError::Database(format!("Failed to get value_encrypted: {}", e)))?;
+        let value = require_encryption_key(client)?
+            .decrypt(&encrypted)
+            .map_err(|e| ServerError::Database(format!("Environment variable {}: {}", key, e)))?;
+        env.insert(key, value);
+    }
+    Ok(env)
+}
@@ -0,0 +1,107 @@
+use crate::server::models::{
+    CronJob, JobLint, LintLevel, ListCronJobsFilter, Pagination, MAX_PAGE_SIZE,
+};
+use crate::server::service::cron_service::get_user_cron_jobs;
+use crate::server::service::schedule::{
+    check_schedule_fireable, compute_next_runs, validate_schedule, REBOOT_SENTINEL,
+};
+use crate::server::service::timezone::normalize_timezone;
+use crate::server::turso::TursoClient;
+use chrono::{DateTime, Duration, Utc};
+
+/// Schedules firing at least this often are flagged as overly frequent. The scheduler
+/// works in whole minutes, so this catches every-minute schedules.
+const FREQUENT_INTERVAL: Duration = Duration::seconds(60);
+
+/// Upcoming runs sampled to find a schedule's shortest interval
+const INTERVAL_SAMPLES: usize = 10;
+
+/// Check every job in the user's account and return the problems found, in job order
+pub async fn lint_cron_jobs(client: &TursoClient, user_id: &str) -> Result<Vec<JobLint>, String> {
+    let now = Utc::now();
+    let mut lints = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let page = get_user_cron_jobs(
+            client,
+            user_id,
+            ListCronJobsFilter::default(),
+            Pagination {
+                limit: Some(MAX_PAGE_SIZE),
+                offset: Some(offset),
+            },
+        )
+        .await?;
+
+        for job in &page.jobs {
+            lints.extend(lint_job(job, now));
+        }
+
+        offset += page.jobs.len() as u32;
+        if page.jobs.is_empty() || u64::from(offset) >= page.total {
+            break;
+        }
+    }
+
+    Ok(lints)
+}
+
+/// Problems with a single job: invalid schedule or timezone, schedules that never fire,
+/// every-minute schedules and seconds fields that the scheduler ignores
+pub fn lint_job(job: &CronJob, now: DateTime<Utc>) -> Vec<JobLint> {
+    let lint = |level, message: String| JobLint {
+        job_id: job.id.clone(),
+        level,
+        message,
+    };
+
+    if let Err(e) = validate_schedule(&job.schedule) {
+        return vec![lint(LintLevel::Error, format!("Invalid schedule: {}", e))];
+    }
+    if let Err(e) = normalize_timezone(&job.timezone) {
+        return vec![lint(LintLevel::Error, e)];
+    }
+
+    let mut lints = Vec::new();
+    match check_schedule_fireable(&job.schedule, job.enabled) {
+        Err(e) => lints.push(lint(LintLevel::Error, e)),
+        Ok(Some(warning)) => lints.push(lint(LintLevel::Warning, warning)),
+        Ok(None) => {}
+    }
+
+    if job.schedule == REBOOT_SENTINEL {
+        return lints;
+    }
+
+    let fields: Vec<&str> = job.schedule.split_whitespace().collect();
+    if fields.len() == 6 && fields[0] != "0" {
+        lints.push(lint(
+            LintLevel::Warning,
+            format!(
+                "Schedule '{}' has a seconds field of '{}', but jobs run at the start of the minute",
+                job.schedule, fields[0]
+            ),
+        ));
+    }
+
+    if shortest_interval(job, now).is_some_and(|interval| interval <= FREQUENT_INTERVAL) {
+        lints.push(lint(
+            LintLevel::Warning,
+            format!(
+                "Schedule '{}' fires every minute; consider running it less often",
+                job.schedule
+            ),
+        ));
+    }
+
+    lints
+}
+
+/// Shortest gap between the job's next few runs, or `None` if it fires less than twice
+fn shortest_interval(job: &CronJob, now: DateTime<Utc>) -> Option<Duration> {
+    compute_next_runs(&job.schedule, &job.timezone, now, INTERVAL_SAMPLES + 1)
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .min()
+}
@@ -1,10 +1,77 @@
 #[cfg(not(target_arch = "wasm32"))]
+pub mod activity_service;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod backup;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod command;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod cron_service;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod crontab;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod import_service;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod job_env;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod lint_service;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod outbound;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod run_service;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod schedule;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod schedule_cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod timezone;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod url_check;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod user_service;
 
 #[cfg(not(target_arch = "wasm32"))]
-pub use cron_service::{create_cron_job, delete_cron_job, get_user_cron_jobs, update_cron_job};
+pub use activity_service::get_activity_feed;
+#[cfg(not(target_arch = "wasm32"))]
+pub use backup::{export_account, export_jobs_json, import_account, import_jobs_json, transfer_jobs};
+#[cfg(not(target_arch = "wasm32"))]
+pub use command::{check_job_type_allowed, validate_command_spec};
+#[cfg(not(target_arch = "wasm32"))]
+pub use cron_service::{
+    bulk_update_enabled, check_batch_size, clone_cron_job, count_cron_jobs, create_cron_job,
+    delete_cron_job, delete_cron_jobs, get_cron_job, get_cron_job_stats, get_cron_jobs_by_ids,
+    get_all_enabled_jobs, get_enabled_cron_jobs, get_enabled_jobs, get_user_cron_jobs,
+    list_trashed_cron_jobs, purge_cron_job, purge_deleted, restore_cron_job, update_cron_job,
+    validate_job_name, validate_max_catchup, validate_retry_policy, validate_run_history_limit,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crontab::{export_crontab, format_crontab, import_crontab, CRONTAB_JOB_MARKER};
+#[cfg(not(target_arch = "wasm32"))]
+pub use import_service::{estimate_import_impact, import_cron_jobs};
+#[cfg(not(target_arch = "wasm32"))]
+pub use job_env::{get_job_env, list_job_env_keys, set_job_env};
+#[cfg(not(target_arch = "wasm32"))]
+pub use lint_service::{lint_cron_jobs, lint_job};
+#[cfg(not(target_arch = "wasm32"))]
+pub use outbound::{validate_outbound_url, UrlPolicy};
+#[cfg(not(target_arch = "wasm32"))]
+pub use run_service::{
+    count_retained_runs, get_job_runs, get_run_summary, last_run_started_at, record_job_run,
+    run_history_limit, run_job_now, DEFAULT_RUN_HISTORY_LIMIT,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use schedule::{
+    check_schedule_fireable, compute_next_run, compute_next_runs, describe_schedule,
+    normalize_schedule, parse_schedule, schedule_horizon, validate_schedule, CronSchedule,
+    REBOOT_SENTINEL,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use schedule_cache::{cached_next_run, CacheStats, ScheduleCache};
+#[cfg(not(target_arch = "wasm32"))]
+pub use timezone::normalize_timezone;
+#[cfg(not(target_arch = "wasm32"))]
+pub use url_check::test_command_url;
 #[cfg(not(target_arch = "wasm32"))]
-pub use user_service::create_user_database;
+pub use user_service::{
+    create_user_database, delete_user_database, get_account_usage, get_database_info,
+};
 
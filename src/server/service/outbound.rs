@@ -0,0 +1,261 @@
+use actix_web::rt::task::spawn_blocking;
+use std::env;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use url::Url;
+
+/// Rules for URLs the server fetches on a user's behalf
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    /// Reject plain `http` URLs
+    pub require_https: bool,
+    /// Allow loopback, private and link-local addresses for every host
+    pub allow_private: bool,
+    /// Hosts (names or IP literals) exempt from the internal address check
+    pub allowed_hosts: Vec<String>,
+}
+
+/// Only https, and only public addresses
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        UrlPolicy {
+            require_https: true,
+            allow_private: false,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl UrlPolicy {
+    /// Policy from `OUTBOUND_REQUIRE_HTTPS` (default true), `ALLOW_PRIVATE_URLS` and the
+    /// comma-separated `OUTBOUND_ALLOWED_HOSTS`
+    pub fn from_env() -> Self {
+        let flag = |name: &str, default: bool| {
+            env::var(name)
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1"))
+                .unwrap_or(default)
+        };
+
+        UrlPolicy {
+            require_https: flag("OUTBOUND_REQUIRE_HTTPS", true),
+            allow_private: flag("ALLOW_PRIVATE_URLS", false),
+            allowed_hosts: env::var("OUTBOUND_ALLOWED_HOSTS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|host| host.trim().to_lowercase())
+                        .filter(|host| !host.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    fn allows_internal(&self, host: &str) -> bool {
+        self.allow_private || self.allowed_hosts.iter().any(|allowed| allowed == host)
+    }
+}
+
+/// Why an outbound URL can't be fetched
+#[derive(Debug, Clone)]
+pub enum OutboundError {
+    /// The URL breaks the policy
+    Rejected(String),
+    /// The host name did not resolve
+    Unresolved(String),
+}
+
+impl fmt::Display for OutboundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutboundError::Rejected(msg) | OutboundError::Unresolved(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// A URL that passed the policy, with the addresses its host resolved to.
+///
+/// Requests should be pinned to `addrs` (see `reqwest::ClientBuilder::resolve_to_addrs`)
+/// so a second DNS lookup can't point them somewhere else.
+#[derive(Debug, Clone)]
+pub struct OutboundTarget {
+    pub url: Url,
+    pub host: String,
+    pub addrs: Vec<SocketAddr>,
+}
+
+/// Addresses a user-supplied URL must not reach from the server: loopback, private,
+/// link-local (which includes the 169.254.169.254 metadata endpoint), carrier-grade NAT,
+/// unspecified, broadcast and multicast
+pub fn is_internal_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // 100.64.0.0/10
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_internal_address(IpAddr::V4(mapped)),
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    // fc00::/7, unique local
+                    || (ip.segments()[0] & 0xfe00) == 0xfc00
+                    // fe80::/10, link-local
+                    || (ip.segments()[0] & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+/// Parse and check a URL against `policy`, resolving its host.
+pub async fn resolve_outbound_url(url: &str, policy: &UrlPolicy) -> Result<OutboundTarget, OutboundError> {
+    let parsed = Url::parse(url.trim())
+        .map_err(|e| OutboundError::Rejected(format!("Invalid URL '{}': {}", url, e)))?;
+    match parsed.scheme() {
+        "https" => {}
+        "http" if !policy.require_https => {}
+        "http" => return Err(OutboundError::Rejected(format!("URL '{}' must use https", url))),
+        scheme => {
+            return Err(OutboundError::Rejected(format!(
+                "URL '{}' must use http or https, found {}",
+                url, scheme
+            )))
+        }
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| OutboundError::Rejected(format!("URL '{}' has no host", url)))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_lowercase();
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| OutboundError::Rejected(format!("URL '{}' has no port", url)))?;
+
+    let lookup_host = host.clone();
+    let addrs: Vec<SocketAddr> = spawn_blocking(move || (lookup_host.as_str(), port).to_socket_addrs())
+        .await
+        .map_err(|e| OutboundError::Unresolved(format!("Failed to resolve {}: {}", host, e)))?
+        .map_err(|e| OutboundError::Unresolved(format!("Failed to resolve {}: {}", host, e)))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(OutboundError::Unresolved(format!("{} did not resolve to any address", host)));
+    }
+
+    if !policy.allows_internal(&host) {
+        if let Some(addr) = addrs.iter().find(|addr| is_internal_address(addr.ip())) {
+            return Err(OutboundError::Rejected(format!(
+                "URL '{}' resolves to the internal address {}, which is not allowed",
+                url,
+                addr.ip()
+            )));
+        }
+    }
+
+    Ok(OutboundTarget { url: parsed, host, addrs })
+}
+
+/// Check a user-supplied URL before the server fetches it: it must parse, use https
+/// when the policy requires it, and resolve only to public addresses unless its host
+/// is allowlisted.
+pub async fn validate_outbound_url(url: &str, policy: &UrlPolicy) -> Result<Url, String> {
+    resolve_outbound_url(url, policy)
+        .await
+        .map(|target| target.url)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn internal_allowed(hosts: &[&str]) -> UrlPolicy {
+        UrlPolicy {
+            allowed_hosts: hosts.iter().map(|host| host.to_string()).collect(),
+            ..UrlPolicy::default()
+        }
+    }
+
+    async fn rejected(url: &str, policy: &UrlPolicy) -> String {
+        match resolve_outbound_url(url, policy).await {
+            Err(OutboundError::Rejected(msg)) => msg,
+            other => panic!("expected {} to be rejected, got {:?}", url, other),
+        }
+    }
+
+    #[actix_web::test]
+    async fn public_addresses_pass_and_are_pinned() {
+        let target = resolve_outbound_url("https://93.184.215.14/hook?x=1", &UrlPolicy::default())
+            .await
+            .unwrap();
+        assert_eq!(target.host, "93.184.215.14");
+        assert_eq!(target.addrs, vec!["93.184.215.14:443".parse().unwrap()]);
+        assert_eq!(target.url.as_str(), "https://93.184.215.14/hook?x=1");
+    }
+
+    #[actix_web::test]
+    async fn internal_addresses_are_rejected() {
+        let policy = UrlPolicy::default();
+        for url in [
+            "https://localhost/",
+            "https://127.0.0.1/",
+            "https://10.1.2.3/",
+            "https://192.168.0.10:8443/",
+            "https://169.254.169.254/latest/meta-data/",
+            "https://[::1]/",
+            "https://[::ffff:10.0.0.1]/",
+        ] {
+            assert!(rejected(url, &policy).await.contains("internal address"), "{}", url);
+        }
+    }
+
+    #[actix_web::test]
+    async fn plain_http_needs_opting_out_of_https() {
+        assert!(rejected("http://93.184.215.14/", &UrlPolicy::default()).await.contains("must use https"));
+
+        let policy = UrlPolicy {
+            require_https: false,
+            ..UrlPolicy::default()
+        };
+        assert!(resolve_outbound_url("http://93.184.215.14/", &policy).await.is_ok());
+        assert!(rejected("ftp://93.184.215.14/", &policy).await.contains("must use http or https"));
+    }
+
+    #[actix_web::test]
+    async fn allowlisted_hosts_may_be_internal() {
+        let policy = internal_allowed(&["10.1.2.3"]);
+        assert!(resolve_outbound_url("https://10.1.2.3/", &policy).await.is_ok());
+        assert!(rejected("https://10.1.2.4/", &policy).await.contains("internal address"));
+
+        let policy = UrlPolicy {
+            allow_private: true,
+            ..UrlPolicy::default()
+        };
+        assert!(resolve_outbound_url("https://169.254.169.254/", &policy).await.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn malformed_urls_are_rejected() {
+        let policy = UrlPolicy::default();
+        assert!(rejected("not a url", &policy).await.contains("Invalid URL"));
+        assert!(validate_outbound_url("https://127.0.0.1/", &policy).await.is_err());
+    }
+
+    #[test]
+    fn classifies_addresses() {
+        let internal = |ip: &str| is_internal_address(ip.parse().unwrap());
+        assert!(internal("100.64.0.1"));
+        assert!(internal("0.0.0.0"));
+        assert!(internal("fd00::1"));
+        assert!(internal("fe80::1"));
+        assert!(!internal("100.128.0.1"));
+        assert!(!internal("8.8.8.8"));
+        assert!(!internal("2606:4700::1111"));
+    }
+}
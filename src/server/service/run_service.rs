@@ -0,0 +1,242 @@
+use crate::server::models::{
+    Bucket, CronJob, JobRun, RunBucket, RunStatus, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE,
+};
+use crate::server::scheduler::run_job;
+use crate::server::service::{check_job_type_allowed, get_cron_job, get_job_env};
+use crate::server::turso::row::{get_i64, get_optional_i64, get_optional_timestamp, sqlite_timestamp};
+use crate::server::turso::TursoClient;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use libsql::Connection;
+use std::collections::BTreeMap;
+use std::env;
+
+/// Runs kept per job when neither the job nor `RUN_HISTORY_LIMIT` sets a limit
+pub const DEFAULT_RUN_HISTORY_LIMIT: u32 = 100;
+
+/// How many runs to keep for `job`: its own `run_history_limit`, else the global
+/// `RUN_HISTORY_LIMIT`, else [`DEFAULT_RUN_HISTORY_LIMIT`]
+pub fn run_history_limit(job: &CronJob) -> u32 {
+    job.run_history_limit
+        .and_then(|limit| u32::try_from(limit).ok())
+        .or_else(|| {
+            env::var("RUN_HISTORY_LIMIT")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+        })
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_RUN_HISTORY_LIMIT)
+}
+
+/// Store a finished run in the job's user database, then drop the job's oldest runs so
+/// at most `keep` remain. Both happen in one transaction.
+pub async fn record_job_run(conn: &Connection, run: &JobRun, keep: u32) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    tx.execute(
+        "INSERT INTO job_runs (id, job_id, started_at, finished_at, status, exit_code, output_excerpt)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        libsql::params![
+            run.id.as_str(),
+            run.job_id.as_str(),
+            sqlite_timestamp(&run.started_at),
+            run.finished_at.as_ref().map(sqlite_timestamp),
+            run.status.as_str(),
+            run.exit_code,
+            run.output_excerpt.as_deref(),
+        ],
+    )
+    .await
+    .map_err(|e| format!("Failed to record run of job {}: {}", run.job_id, e))?;
+
+    // Runs started in the same second are ordered by id so exactly `keep` survive
+    tx.execute(
+        "DELETE FROM job_runs WHERE job_id = ? AND id NOT IN (
+             SELECT id FROM job_runs WHERE job_id = ? ORDER BY started_at DESC, id DESC LIMIT ?
+         )",
+        libsql::params![run.job_id.as_str(), run.job_id.as_str(), keep.max(1)],
+    )
+    .await
+    .map_err(|e| format!("Failed to trim run history of job {}: {}", run.job_id, e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit run of job {}: {}", run.job_id, e))?;
+
+    Ok(())
+}
+
+/// Run one of the user's jobs immediately and record the run
+///
+/// Disabled jobs are refused unless `allow_disabled` is set, so a user can still test a
+/// job they have paused. Shell jobs are refused unless the operator set `ALLOW_SHELL_JOBS`.
+/// The returned run carries the captured output.
+pub async fn run_job_now(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+    allow_disabled: bool,
+) -> Result<JobRun, String> {
+    let job = get_cron_job(client, user_id, job_id).await?;
+    if !job.enabled && !allow_disabled {
+        return Err("Cron job is disabled".to_string());
+    }
+    check_job_type_allowed(&job.command_spec(), client.allow_shell_jobs())?;
+
+    let env = get_job_env(client, user_id, job_id).await?;
+    let run = run_job(&job, &env).await;
+    let conn = client.get_user_database_connection(user_id).await?;
+    record_job_run(&conn, &run, run_history_limit(&job)).await?;
+
+    Ok(run)
+}
+
+/// Number of runs kept in the history of all of the user's jobs
+pub async fn count_retained_runs(client: &TursoClient, user_id: &str) -> Result<u64, String> {
+    let conn = client.get_user_database_connection(user_id).await?;
+    let mut rows = conn
+        .query(
+            "SELECT COUNT(*) FROM job_runs r JOIN cron_jobs j ON j.id = r.job_id WHERE j.user_id = ?",
+            libsql::params![user_id],
+        )
+        .await
+        .map_err(|e| format!("Failed to count job runs: {}", e))?;
+
+    match rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get row: {}", e))?
+    {
+        Some(row) => Ok(get_i64(&row, 0, "count")?.max(0) as u64),
+        None => Ok(0),
+    }
+}
+
+/// When the job's most recent run started (`None` if it has never run)
+pub async fn last_run_started_at(conn: &Connection, job_id: &str) -> Result<Option<DateTime<Utc>>, String> {
+    let mut rows = conn
+        .query("SELECT MAX(started_at) FROM job_runs WHERE job_id = ?", libsql::params![job_id])
+        .await
+        .map_err(|e| format!("Failed to query job runs: {}", e))?;
+
+    match rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get row: {}", e))?
+    {
+        Some(row) => get_optional_timestamp(&row, 0, "started_at"),
+        None => Ok(None),
+    }
+}
+
+/// Latest runs of one of the user's jobs, newest first
+pub async fn get_job_runs(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+    limit: Option<u32>,
+) -> Result<Vec<JobRun>, String> {
+    let conn = client.get_user_database_connection(user_id).await?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let mut rows = conn
+        .prepare(
+            "SELECT r.id, r.job_id, r.started_at, r.finished_at, r.status, r.exit_code, r.output_excerpt
+             FROM job_runs r JOIN cron_jobs j ON j.id = r.job_id
+             WHERE r.job_id = ? AND j.user_id = ?
+             ORDER BY r.started_at DESC LIMIT ?",
+        )
+        .await
+        .map_err(|e| format!("Failed to prepare query: {}", e))?
+        .query(libsql::params![job_id, user_id, limit])
+        .await
+        .map_err(|e| format!("Failed to query job runs: {}", e))?;
+
+    let mut runs = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get row: {}", e))?
+    {
+        runs.push(JobRun {
+            id: row.get(0).map_err(|e| format!("Failed to get id: {}", e))?,
+            job_id: row.get(1).map_err(|e| format!("Failed to get job_id: {}", e))?,
+            started_at: get_optional_timestamp(&row, 2, "started_at")?
+                .ok_or_else(|| "Column 'started_at' is NULL".to_string())?,
+            finished_at: get_optional_timestamp(&row, 3, "finished_at")?,
+            status: RunStatus::parse(
+                &row.get::<String>(4).map_err(|e| format!("Failed to get status: {}", e))?,
+            )?,
+            exit_code: get_optional_i64(&row, 5, "exit_code")?,
+            output_excerpt: row.get(6).map_err(|e| format!("Failed to get output_excerpt: {}", e))?,
+        });
+    }
+
+    Ok(runs)
+}
+
+/// Success/failure counts of a job's runs per day, week or month, oldest period first
+///
+/// Periods follow the job's timezone, so a run at 23:30 UTC lands on the next day for a
+/// job scheduled in Europe/Berlin. SQLite's date functions only know fixed offsets, so
+/// the grouping is done here rather than in SQL to stay correct across DST changes.
+pub async fn get_run_summary(
+    client: &TursoClient,
+    user_id: &str,
+    job_id: &str,
+    bucket: Bucket,
+) -> Result<Vec<RunBucket>, String> {
+    let job = get_cron_job(client, user_id, job_id).await?;
+    let tz: Tz = job
+        .timezone
+        .parse()
+        .map_err(|_| format!("Invalid timezone: {}", job.timezone))?;
+    let conn = client.get_user_database_connection(user_id).await?;
+
+    let mut rows = conn
+        .prepare("SELECT started_at, status FROM job_runs WHERE job_id = ?")
+        .await
+        .map_err(|e| format!("Failed to prepare query: {}", e))?
+        .query(libsql::params![job_id])
+        .await
+        .map_err(|e| format!("Failed to query job runs: {}", e))?;
+
+    let mut buckets: BTreeMap<NaiveDate, RunBucket> = BTreeMap::new();
+    while let Some(row) = rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to get row: {}", e))?
+    {
+        let Some(started_at) = get_optional_timestamp(&row, 0, "started_at")? else {
+            continue;
+        };
+        let status = RunStatus::parse(
+            &row.get::<String>(1).map_err(|e| format!("Failed to get status: {}", e))?,
+        )?;
+
+        let start = bucket_start(started_at.with_timezone(&tz).date_naive(), bucket);
+        let entry = buckets.entry(start).or_insert_with(|| RunBucket {
+            start,
+            ..Default::default()
+        });
+        match status {
+            RunStatus::Succeeded => entry.succeeded += 1,
+            RunStatus::Failed => entry.failed += 1,
+            RunStatus::Skipped => entry.skipped += 1,
+        }
+    }
+
+    Ok(buckets.into_values().collect())
+}
+
+/// First day of the period containing `date`
+fn bucket_start(date: NaiveDate, bucket: Bucket) -> NaiveDate {
+    match bucket {
+        Bucket::Day => date,
+        Bucket::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+        Bucket::Month => date.with_day(1).unwrap_or(date),
+    }
+}
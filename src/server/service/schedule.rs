@@ -0,0 +1,572 @@
+use crate::cron_expr::{parse_field_mask, CronExpr, CronFieldKind};
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use std::env;
+use std::sync::LazyLock;
+
+/// Parsed five-field cron schedule (minute hour day-of-month month day-of-week)
+///
+/// Each field is stored as a bitmask where bit `n` is set when value `n` matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    pub minutes: u64,
+    pub hours: u64,
+    pub days_of_month: u64,
+    pub months: u64,
+    pub days_of_week: u64,
+    /// Day-of-month field started with `*` (unrestricted)
+    pub dom_wildcard: bool,
+    /// Day-of-week field started with `*` (unrestricted)
+    pub dow_wildcard: bool,
+}
+
+const MONTH_LABELS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Longest possible length of each month (February counts leap years)
+const MAX_DAYS_IN_MONTH: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Search horizon when `SCHEDULE_HORIZON_DAYS` is unset: four years, so a leap-day
+/// schedule finds its next Feb 29 (except across 2100, which is not a leap year)
+const DEFAULT_HORIZON_DAYS: i64 = 4 * 365 + 1;
+
+/// How far past the starting time next-run searches look, from `SCHEDULE_HORIZON_DAYS`.
+/// Bounds the work spent on sparse schedules; occurrences beyond it are not found.
+static SCHEDULE_HORIZON: LazyLock<Duration> = LazyLock::new(|| {
+    let days = env::var("SCHEDULE_HORIZON_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&days| days > 0)
+        .unwrap_or(DEFAULT_HORIZON_DAYS);
+    Duration::days(days)
+});
+
+/// The configured search horizon of next-run searches
+pub fn schedule_horizon() -> Duration {
+    *SCHEDULE_HORIZON
+}
+
+/// Stored in place of a cron expression for `@reboot` jobs, which have no 5-field form
+pub const REBOOT_SENTINEL: &str = "@reboot";
+
+/// Expand schedule macros into their 5-field equivalents before storage.
+///
+/// `@reboot` has no cron equivalent and is kept as [`REBOOT_SENTINEL`]. Unknown macros
+/// are rejected; other expressions are returned trimmed with single spaces.
+pub fn normalize_schedule(schedule: &str) -> Result<String, String> {
+    let trimmed = schedule.trim();
+    if !trimmed.starts_with('@') {
+        return Ok(trimmed.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+
+    let expanded = match trimmed.to_lowercase().as_str() {
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@monthly" => "0 0 1 * *",
+        "@weekly" => "0 0 * * 0",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@hourly" => "0 * * * *",
+        "@reboot" => REBOOT_SENTINEL,
+        _ => return Err(format!("Unknown schedule macro '{}'", trimmed)),
+    };
+
+    Ok(expanded.to_string())
+}
+
+/// Check a schedule for syntax errors.
+///
+/// Accepts standard 5-field cron, the 6-field form with a leading seconds field, and the
+/// `@` macros understood by [`normalize_schedule`]. Errors name the field that failed.
+pub fn validate_schedule(schedule: &str) -> Result<(), String> {
+    let trimmed = schedule.trim();
+    if trimmed.starts_with('@') {
+        return normalize_schedule(trimmed).map(|_| ());
+    }
+
+    five_field_form(trimmed)
+        .and_then(|expr| CronExpr::parse(&expr))
+        .map(|_| ())
+        .map_err(|e| format!("Invalid schedule '{}': {}", trimmed, e))
+}
+
+/// Reduce a 5- or 6-field expression to its 5-field form, validating the seconds field
+pub(crate) fn five_field_form(schedule: &str) -> Result<String, String> {
+    let fields: Vec<&str> = schedule.split_whitespace().collect();
+    match fields.len() {
+        5 => Ok(fields.join(" ")),
+        6 => {
+            parse_field_mask(fields[0], "second", 0, 59, None)?;
+            Ok(fields[1..].join(" "))
+        }
+        n => Err(format!(
+            "Expected 5 fields (minute hour day-of-month month day-of-week) \
+             or 6 with a leading seconds field, found {}",
+            n
+        )),
+    }
+}
+
+/// Parse a 5- or 6-field cron expression (seconds are ignored)
+pub fn parse_schedule(expr: &str) -> Result<CronSchedule, String> {
+    let expr = CronExpr::parse(&five_field_form(expr)?)?;
+
+    Ok(CronSchedule {
+        minutes: expr.mask(CronFieldKind::Minute)?,
+        hours: expr.mask(CronFieldKind::Hour)?,
+        days_of_month: expr.mask(CronFieldKind::DayOfMonth)?,
+        months: expr.mask(CronFieldKind::Month)?,
+        days_of_week: expr.mask(CronFieldKind::DayOfWeek)?,
+        dom_wildcard: expr.day_of_month().starts_with('*'),
+        dow_wildcard: expr.day_of_week().starts_with('*'),
+    })
+}
+
+impl CronSchedule {
+    /// Whether the schedule fires on the given date.
+    ///
+    /// Follows cron semantics: when both day fields are restricted a date matches if
+    /// either one matches, otherwise both must match.
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+
+        if self.dom_wildcard || self.dow_wildcard {
+            dom && dow
+        } else {
+            dom || dow
+        }
+    }
+
+    /// First matching wall-clock time strictly after `after` and no later than `until`,
+    /// or `None` if there is none
+    fn next_local_after(&self, after: NaiveDateTime, until: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = (after + Duration::minutes(1))
+            .with_second(0)?
+            .with_nanosecond(0)?;
+        let mut date = start.date();
+
+        while date <= until.date() {
+            if self.matches_date(date) {
+                let first_day = date == start.date();
+                let first_hour = if first_day { start.hour() } else { 0 };
+                for hour in (first_hour..24).filter(|h| self.hours & (1 << h) != 0) {
+                    let first_minute = if first_day && hour == start.hour() { start.minute() } else { 0 };
+                    if let Some(minute) = (first_minute..60).find(|m| self.minutes & (1 << m) != 0) {
+                        return date.and_hms_opt(hour, minute, 0).filter(|time| *time <= until);
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+
+    /// First occurrence strictly after `after`, or `None` if there is none within
+    /// [`schedule_horizon`]
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_after_in(Tz::UTC, after)
+    }
+
+    /// First occurrence strictly after `after` with the schedule read as wall-clock
+    /// time in `tz`, searching no further than [`schedule_horizon`].
+    ///
+    /// Times skipped by a DST jump do not fire; times repeated when clocks go back
+    /// fire at their first occurrence after `after`.
+    pub fn next_after_in(&self, tz: Tz, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_within(tz, after, schedule_horizon())
+    }
+
+    /// Like [`next_after_in`](Self::next_after_in), but giving up on occurrences more
+    /// than `horizon` after `after`.
+    ///
+    /// ```
+    /// use chrono::{Duration, TimeZone, Utc};
+    /// use chrono_tz::Tz;
+    /// use cron_jobs::server::service::parse_schedule;
+    ///
+    /// let leap_day = parse_schedule("0 0 29 2 *").unwrap();
+    /// let after = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    ///
+    /// let next = leap_day.next_within(Tz::UTC, after, Duration::days(4 * 365 + 1));
+    /// assert_eq!(next, Some(Utc.with_ymd_and_hms(2028, 2, 29, 0, 0, 0).unwrap()));
+    /// assert_eq!(leap_day.next_within(Tz::UTC, after, Duration::days(365)), None);
+    /// ```
+    pub fn next_within(&self, tz: Tz, after: DateTime<Utc>, horizon: Duration) -> Option<DateTime<Utc>> {
+        let until = after.checked_add_signed(horizon)?;
+        let until_local = until.with_timezone(&tz).naive_local();
+        let mut local = after.with_timezone(&tz).naive_local();
+
+        loop {
+            let candidate = self.next_local_after(local, until_local)?;
+            let fires = match tz.from_local_datetime(&candidate) {
+                LocalResult::Single(dt) => Some(dt),
+                LocalResult::Ambiguous(earliest, latest) => {
+                    [earliest, latest].into_iter().find(|dt| *dt > after)
+                }
+                LocalResult::None => None,
+            };
+
+            if let Some(dt) = fires.filter(|dt| *dt > after) {
+                return Some(dt.with_timezone(&Utc)).filter(|dt| *dt <= until);
+            }
+            local = candidate;
+        }
+    }
+
+    /// Explain why this schedule can never fire, if that is the case.
+    ///
+    /// A schedule is impossible when only the day-of-month restricts the date and none
+    /// of the selected days exist in any of the selected months (e.g. `0 0 30 2 *`).
+    /// When both day fields are restricted cron matches either one, so the
+    /// day-of-week keeps the schedule fireable.
+    pub fn impossible_date_reason(&self) -> Option<String> {
+        if self.dom_wildcard || !self.dow_wildcard {
+            return None;
+        }
+
+        let first_day = self.days_of_month.trailing_zeros();
+        let fires = (1..=12u32)
+            .filter(|month| self.months & (1 << month) != 0)
+            .any(|month| first_day <= MAX_DAYS_IN_MONTH[month as usize - 1]);
+
+        if fires {
+            return None;
+        }
+
+        let months: Vec<&str> = (1..=12u32)
+            .filter(|month| self.months & (1 << month) != 0)
+            .map(|month| MONTH_LABELS[month as usize - 1])
+            .collect();
+
+        Some(format!(
+            "day {} never occurs in {}",
+            first_day,
+            months.join(", ")
+        ))
+    }
+}
+
+/// Check that a schedule can fire before it is saved.
+///
+/// Enabled jobs with an impossible schedule are rejected. Disabled jobs are saved as
+/// drafts and the reason is returned as a warning instead. `@reboot` jobs are always
+/// flagged with a warning. Syntax is checked separately by [`validate_schedule`].
+pub fn check_schedule_fireable(schedule: &str, enabled: bool) -> Result<Option<String>, String> {
+    if schedule == REBOOT_SENTINEL {
+        return Ok(Some(
+            "@reboot has no cron equivalent; the job only runs when the scheduler starts".to_string(),
+        ));
+    }
+
+    let reason = match parse_schedule(schedule) {
+        Ok(parsed) => parsed.impossible_date_reason(),
+        Err(_) => None,
+    };
+
+    match reason {
+        Some(reason) if enabled => Err(format!(
+            "Schedule '{}' never fires: {}. Disable the job to save it as a draft.",
+            schedule, reason
+        )),
+        Some(reason) => Ok(Some(format!(
+            "Schedule '{}' never fires: {}. The job will not run until the schedule is fixed.",
+            schedule, reason
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Next time a schedule fires after `after`, reading the schedule in the IANA `timezone`.
+///
+/// Returns `None` for schedules or timezones that fail to parse, or schedules that don't
+/// fire again within [`schedule_horizon`].
+pub fn compute_next_run(
+    schedule: &str,
+    timezone: &str,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let tz = timezone.parse::<Tz>().ok()?;
+    parse_schedule(schedule).ok()?.next_after_in(tz, after)
+}
+
+/// Up to `count` next times a schedule fires after `after`, in order.
+///
+/// All of them fall within [`schedule_horizon`] of `after`, so a sparse schedule returns
+/// fewer; schedules or timezones that fail to parse return none.
+pub fn compute_next_runs(
+    schedule: &str,
+    timezone: &str,
+    after: DateTime<Utc>,
+    count: usize,
+) -> Vec<DateTime<Utc>> {
+    let (Ok(tz), Ok(parsed)) = (timezone.parse::<Tz>(), parse_schedule(schedule)) else {
+        return Vec::new();
+    };
+    let Some(until) = after.checked_add_signed(schedule_horizon()) else {
+        return Vec::new();
+    };
+
+    let mut runs = Vec::with_capacity(count);
+    let mut previous = after;
+    while runs.len() < count {
+        let Some(next) = parsed.next_within(tz, previous, until - previous) else {
+            break;
+        };
+        runs.push(next);
+        previous = next;
+    }
+    runs
+}
+
+/// How the values of one field are worded in a schedule description
+struct FieldWording {
+    /// Unit used for step values ("minute" gives "every 15 minutes")
+    unit: &'static str,
+    /// Placed between the ends of a range
+    range_separator: &'static str,
+    label: fn(u32) -> String,
+}
+
+const DAY_LABELS: [&str; 7] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
+];
+
+fn number_label(value: u32) -> String {
+    value.to_string()
+}
+
+fn hour_label(hour: u32) -> String {
+    clock_label(hour, 0)
+}
+
+fn month_label(month: u32) -> String {
+    MONTH_LABELS[month as usize - 1].to_string()
+}
+
+fn day_label(day: u32) -> String {
+    DAY_LABELS[day as usize % 7].to_string()
+}
+
+/// 12-hour clock time, omitting the minutes on the hour ("9am", "5:30pm")
+fn clock_label(hour: u32, minute: u32) -> String {
+    let suffix = if hour < 12 { "am" } else { "pm" };
+    let hour = match hour % 12 {
+        0 => 12,
+        h => h,
+    };
+    if minute == 0 {
+        format!("{}{}", hour, suffix)
+    } else {
+        format!("{}:{:02}{}", hour, minute, suffix)
+    }
+}
+
+fn wording(kind: CronFieldKind) -> FieldWording {
+    match kind {
+        CronFieldKind::Minute => FieldWording {
+            unit: "minute",
+            range_separator: " to ",
+            label: number_label,
+        },
+        CronFieldKind::Hour => FieldWording {
+            unit: "hour",
+            range_separator: "\u{2013}",
+            label: hour_label,
+        },
+        CronFieldKind::DayOfMonth => FieldWording {
+            unit: "day",
+            range_separator: " to ",
+            label: number_label,
+        },
+        CronFieldKind::Month => FieldWording {
+            unit: "month",
+            range_separator: " to ",
+            label: month_label,
+        },
+        CronFieldKind::DayOfWeek => FieldWording {
+            unit: "day",
+            range_separator: " to ",
+            label: day_label,
+        },
+    }
+}
+
+/// Join words as an English list ("a", "a and b", "a, b and c")
+fn join_words(words: Vec<String>) -> String {
+    match words.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => String::new(),
+    }
+}
+
+/// Numeric value of a single (possibly named) field value
+fn field_value(kind: CronFieldKind, value: &str) -> u32 {
+    kind.mask(value).map(u64::trailing_zeros).unwrap_or_default()
+}
+
+/// Values of a field made only of single values (e.g. `0,30`), or `None` if it has
+/// wildcards, ranges or steps
+fn plain_values(kind: CronFieldKind, field: &str) -> Option<Vec<u32>> {
+    field
+        .split(',')
+        .map(|item| {
+            if item.contains(['*', '-', '/']) {
+                None
+            } else {
+                Some(field_value(kind, item))
+            }
+        })
+        .collect()
+}
+
+/// Describe one comma-separated item of a field
+fn describe_item(kind: CronFieldKind, item: &str) -> String {
+    let wording = wording(kind);
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => (range, Some(step)),
+        None => (item, None),
+    };
+
+    let bounds = if range == "*" {
+        None
+    } else if let Some((start, end)) = range.split_once('-') {
+        Some(format!(
+            "{}{}{}",
+            (wording.label)(field_value(kind, start)),
+            wording.range_separator,
+            (wording.label)(field_value(kind, end))
+        ))
+    } else if step.is_some() {
+        Some(format!("{} onwards", (wording.label)(field_value(kind, range))))
+    } else {
+        Some((wording.label)(field_value(kind, range)))
+    };
+
+    match (step, bounds) {
+        (Some(step), Some(bounds)) => format!("every {} {}s, {}", step, wording.unit, bounds),
+        (Some(step), None) => format!("every {} {}s", step, wording.unit),
+        (None, Some(bounds)) => bounds,
+        (None, None) => format!("every {}", wording.unit),
+    }
+}
+
+fn describe_field(kind: CronFieldKind, field: &str) -> String {
+    join_words(field.split(',').map(|item| describe_item(kind, item)).collect())
+}
+
+/// Phrase for the minute and hour fields ("At 9:30am", "Every 15 minutes, 9am–5pm")
+fn describe_time(expr: &CronExpr) -> String {
+    let minutes = plain_values(CronFieldKind::Minute, expr.minute());
+    let hours = plain_values(CronFieldKind::Hour, expr.hour());
+
+    if let (Some(minutes), Some(hours)) = (&minutes, &hours) {
+        if let [minute] = minutes.as_slice() {
+            let times = hours.iter().map(|hour| clock_label(*hour, *minute)).collect();
+            return format!("At {}", join_words(times));
+        }
+    }
+
+    let minute_phrase = match &minutes {
+        Some(values) if values.len() == 1 => format!("At minute {} past the hour", values[0]),
+        Some(values) => format!(
+            "At minutes {} past the hour",
+            join_words(values.iter().map(u32::to_string).collect())
+        ),
+        None => capitalize(&describe_field(CronFieldKind::Minute, expr.minute())),
+    };
+
+    if expr.hour() == "*" {
+        return minute_phrase;
+    }
+
+    let hour_phrase = match &hours {
+        Some(values) if values.len() == 1 => format!("during the {} hour", hour_label(values[0])),
+        Some(values) => format!(
+            "during the {} hours",
+            join_words(values.iter().map(|hour| hour_label(*hour)).collect())
+        ),
+        None => describe_field(CronFieldKind::Hour, expr.hour()),
+    };
+
+    format!("{}, {}", minute_phrase, hour_phrase)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Describe a schedule in English for display, e.g. `*/15 9-17 * * 1-5` becomes
+/// "Every 15 minutes, 9am–5pm, Monday to Friday".
+///
+/// Accepts the same forms as [`validate_schedule`]; anything that fails to parse is
+/// returned unchanged.
+pub fn describe_schedule(schedule: &str) -> String {
+    let normalized = match normalize_schedule(schedule) {
+        Ok(normalized) => normalized,
+        Err(_) => return schedule.to_string(),
+    };
+    if normalized == REBOOT_SENTINEL {
+        return "When the scheduler starts".to_string();
+    }
+
+    let expr = match five_field_form(&normalized).and_then(|expr| CronExpr::parse(&expr)) {
+        Ok(expr) => expr,
+        Err(_) => return schedule.to_string(),
+    };
+
+    let mut parts = vec![describe_time(&expr)];
+
+    let mut days = Vec::new();
+    if expr.day_of_month() != "*" {
+        days.push(if expr.day_of_month().starts_with("*/") {
+            format!("{} of the month", describe_field(CronFieldKind::DayOfMonth, expr.day_of_month()))
+        } else {
+            let plural = if plain_values(CronFieldKind::DayOfMonth, expr.day_of_month())
+                .is_some_and(|values| values.len() == 1)
+            {
+                "day"
+            } else {
+                "days"
+            };
+            format!(
+                "on {} {} of the month",
+                plural,
+                describe_field(CronFieldKind::DayOfMonth, expr.day_of_month())
+            )
+        });
+    }
+    if expr.day_of_week() != "*" {
+        let described = describe_field(CronFieldKind::DayOfWeek, expr.day_of_week());
+        days.push(if plain_values(CronFieldKind::DayOfWeek, expr.day_of_week()).is_some() {
+            format!("on {}", described)
+        } else {
+            described
+        });
+    }
+    // Cron matches either day field when both are restricted
+    if !days.is_empty() {
+        parts.push(days.join(" or "));
+    }
+
+    if expr.month() != "*" {
+        let described = describe_field(CronFieldKind::Month, expr.month());
+        parts.push(if expr.month().starts_with("*/") {
+            described
+        } else {
+            format!("in {}", described)
+        });
+    }
+
+    parts.join(", ")
+}
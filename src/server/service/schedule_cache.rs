@@ -0,0 +1,281 @@
+use crate::server::models::CronJob;
+use crate::server::service::schedule::{parse_schedule, schedule_horizon, CronSchedule};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{LazyLock, Mutex};
+
+/// Upcoming fire times worked out at once for a cached job
+const CACHED_RUNS: usize = 8;
+
+/// Jobs kept when `SCHEDULE_CACHE_SIZE` is unset
+const DEFAULT_CACHE_SIZE: usize = 4096;
+
+/// Process-wide schedule cache, sized from `SCHEDULE_CACHE_SIZE` (0 turns it off)
+static SCHEDULE_CACHE: LazyLock<ScheduleCache> = LazyLock::new(|| {
+    let capacity = env::var("SCHEDULE_CACHE_SIZE")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_CACHE_SIZE);
+    ScheduleCache::new(capacity)
+});
+
+/// One job's parsed schedule and the fire times worked out from it
+struct CachedSchedule {
+    /// `updated_at` of the job when the entry was made; any edit to the job changes it
+    version: Option<DateTime<Utc>>,
+    schedule: String,
+    timezone: String,
+    /// `None` when the schedule or timezone doesn't parse
+    parsed: Option<(CronSchedule, Tz)>,
+    /// Every fire time in `(computed_after, runs.last()]`
+    computed_after: DateTime<Utc>,
+    runs: Vec<DateTime<Utc>>,
+    last_used: u64,
+}
+
+impl CachedSchedule {
+    fn new(job: &CronJob) -> Self {
+        let parsed = match (parse_schedule(&job.schedule), job.timezone.parse::<Tz>()) {
+            (Ok(schedule), Ok(tz)) => Some((schedule, tz)),
+            _ => None,
+        };
+        CachedSchedule {
+            version: job.updated_at,
+            schedule: job.schedule.clone(),
+            timezone: job.timezone.clone(),
+            parsed,
+            computed_after: DateTime::<Utc>::MIN_UTC,
+            runs: Vec::new(),
+            last_used: 0,
+        }
+    }
+
+    fn is_current(&self, job: &CronJob) -> bool {
+        self.version == job.updated_at && self.schedule == job.schedule && self.timezone == job.timezone
+    }
+
+    /// Next fire time after `after`, from the cached runs when they cover it
+    fn next_after(&mut self, after: DateTime<Utc>, stats: &mut CacheStats) -> Option<DateTime<Utc>> {
+        let (schedule, tz) = self.parsed.as_ref()?;
+        let covered = self.computed_after <= after && self.runs.last().is_some_and(|last| *last > after);
+        if covered {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+            self.runs = next_runs(schedule, *tz, after);
+            self.computed_after = after;
+        }
+        self.runs.iter().find(|run| **run > after).copied()
+    }
+}
+
+/// Up to [`CACHED_RUNS`] fire times after `after`, within the schedule horizon
+fn next_runs(schedule: &CronSchedule, tz: Tz, after: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let Some(until) = after.checked_add_signed(schedule_horizon()) else {
+        return Vec::new();
+    };
+    let mut runs = Vec::with_capacity(CACHED_RUNS);
+    let mut previous = after;
+    while runs.len() < CACHED_RUNS {
+        let Some(next) = schedule.next_within(tz, previous, until - previous) else {
+            break;
+        };
+        runs.push(next);
+        previous = next;
+    }
+    runs
+}
+
+/// How often a lookup was answered from cached fire times
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    /// Lookups that parsed a schedule or worked out fresh fire times
+    pub misses: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CachedSchedule>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// Least-recently-used cache of parsed schedules and upcoming fire times, by job id.
+///
+/// An entry is reused while the job's `updated_at`, schedule and timezone are unchanged,
+/// so polling the same jobs doesn't re-parse their schedules or search for their next
+/// run again until the cached fire times run out.
+pub struct ScheduleCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ScheduleCache {
+    /// Cache holding at most `capacity` jobs; 0 disables caching
+    pub fn new(capacity: usize) -> Self {
+        ScheduleCache {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Next time `job` fires after `after`, like [`compute_next_run`](super::compute_next_run)
+    pub fn next_run(&self, job: &CronJob, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let Ok(mut state) = self.state.lock() else {
+            return CachedSchedule::new(job).next_after(after, &mut CacheStats::default());
+        };
+        let state = &mut *state;
+        if self.capacity == 0 {
+            return CachedSchedule::new(job).next_after(after, &mut state.stats);
+        }
+
+        state.clock += 1;
+        let current = state.entries.get(&job.id).is_some_and(|entry| entry.is_current(job));
+        if !current {
+            if !state.entries.contains_key(&job.id) && state.entries.len() >= self.capacity {
+                evict_least_recently_used(&mut state.entries);
+            }
+            state.entries.insert(job.id.clone(), CachedSchedule::new(job));
+        }
+        let entry = state.entries.get_mut(&job.id)?;
+        entry.last_used = state.clock;
+        entry.next_after(after, &mut state.stats)
+    }
+
+    /// Hits and misses since the cache was created
+    pub fn stats(&self) -> CacheStats {
+        self.state.lock().map(|state| state.stats).unwrap_or_default()
+    }
+
+    /// Number of jobs currently cached
+    pub fn len(&self) -> usize {
+        self.state.lock().map(|state| state.entries.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn evict_least_recently_used(entries: &mut HashMap<String, CachedSchedule>) {
+    let oldest = entries
+        .iter()
+        .min_by_key(|(_, entry)| entry.last_used)
+        .map(|(id, _)| id.clone());
+    if let Some(id) = oldest {
+        entries.remove(&id);
+    }
+}
+
+/// Next time `job` fires after `after`, through the process-wide [`ScheduleCache`]
+pub fn cached_next_run(job: &CronJob, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    SCHEDULE_CACHE.next_run(job, after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::models::{JobType, MisfirePolicy};
+    use crate::server::service::compute_next_run;
+    use chrono::{Duration, TimeZone};
+
+    fn job(id: &str, schedule: &str, updated_at: DateTime<Utc>) -> CronJob {
+        CronJob {
+            id: id.to_string(),
+            user_id: "user-1".to_string(),
+            name: id.to_string(),
+            schedule: schedule.to_string(),
+            command: "https://example.com/hook".to_string(),
+            enabled: true,
+            job_type: JobType::Http,
+            http_method: None,
+            http_body: None,
+            max_retries: 0,
+            retry_backoff_secs: 0,
+            run_history_limit: None,
+            misfire_policy: MisfirePolicy::Skip,
+            max_catchup: None,
+            timezone: "Europe/Berlin".to_string(),
+            created_at: Some(updated_at),
+            updated_at: Some(updated_at),
+            deleted_at: None,
+            next_run: None,
+            schedule_human: None,
+            warning: None,
+        }
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn an_unchanged_job_reuses_its_cached_runs() {
+        let cache = ScheduleCache::new(16);
+        let job = job("job-1", "*/15 * * * *", start());
+
+        for minutes in [0, 20, 50] {
+            let after = start() + Duration::minutes(minutes);
+            assert_eq!(cache.next_run(&job, after), compute_next_run(&job.schedule, &job.timezone, after));
+        }
+
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 1 });
+    }
+
+    #[test]
+    fn runs_are_worked_out_again_once_the_cached_ones_have_passed() {
+        let cache = ScheduleCache::new(16);
+        let job = job("job-1", "0 * * * *", start());
+
+        cache.next_run(&job, start());
+        let later = start() + Duration::hours(CACHED_RUNS as i64);
+        assert_eq!(cache.next_run(&job, later), Some(later + Duration::hours(1)));
+
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+    }
+
+    #[test]
+    fn an_edited_job_is_parsed_again() {
+        let cache = ScheduleCache::new(16);
+        cache.next_run(&job("job-1", "0 3 * * *", start()), start());
+
+        let edited = job("job-1", "0 4 * * *", start() + Duration::seconds(1));
+        let next = cache.next_run(&edited, start());
+
+        assert_eq!(next, compute_next_run("0 4 * * *", "Europe/Berlin", start()));
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn the_least_recently_used_job_is_evicted() {
+        let cache = ScheduleCache::new(2);
+        let (a, b, c) = (job("a", "0 1 * * *", start()), job("b", "0 2 * * *", start()), job("c", "0 3 * * *", start()));
+
+        cache.next_run(&a, start());
+        cache.next_run(&b, start());
+        cache.next_run(&a, start());
+        cache.next_run(&c, start());
+        assert_eq!(cache.len(), 2);
+
+        // `a` was used after `b`, so `b` made room for `c`
+        cache.next_run(&a, start());
+        cache.next_run(&b, start());
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 4 });
+    }
+
+    #[test]
+    fn a_cache_of_size_zero_keeps_nothing() {
+        let cache = ScheduleCache::new(0);
+        let job = job("job-1", "*/15 * * * *", start());
+
+        assert_eq!(cache.next_run(&job, start()), compute_next_run(&job.schedule, &job.timezone, start()));
+        cache.next_run(&job, start());
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+    }
+}
@@ -0,0 +1,120 @@
+use crate::server::middleware::within_deadline;
+use crate::server::models::UrlCheck;
+use crate::server::service::outbound::{resolve_outbound_url, OutboundError, UrlPolicy};
+use std::time::Duration;
+
+/// How long a URL probe may take before the URL is reported unreachable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Check that a job's URL answers, without recording a run.
+///
+/// Sends a `HEAD` request, falling back to `GET` for servers that don't allow `HEAD`.
+/// Redirects are not followed. URLs rejected by the outbound [`UrlPolicy`] are an error,
+/// and the request goes to the address that was checked.
+pub async fn test_command_url(url: &str) -> Result<UrlCheck, String> {
+    check_url(url, &UrlPolicy::from_env()).await
+}
+
+/// [`test_command_url`] under `policy`
+async fn check_url(url: &str, policy: &UrlPolicy) -> Result<UrlCheck, String> {
+    let target = match resolve_outbound_url(url, policy).await {
+        Ok(target) => target,
+        Err(OutboundError::Unresolved(e)) => {
+            return Ok(UrlCheck {
+                reachable: false,
+                status: None,
+                error: Some(e),
+            })
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(PROBE_TIMEOUT)
+        .resolve_to_addrs(&target.host, &target.addrs)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let mut response = within_deadline("URL check", client.head(target.url.clone()).send()).await?;
+    if matches!(&response, Ok(r) if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED) {
+        response = within_deadline("URL check", client.get(target.url).send()).await?;
+    }
+
+    Ok(match response {
+        Ok(response) => UrlCheck {
+            reachable: true,
+            status: Some(response.status().as_u16()),
+            error: None,
+        },
+        Err(e) => UrlCheck {
+            reachable: false,
+            status: None,
+            error: Some(format!("Request failed: {}", e)),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Policy that lets tests reach servers on this machine
+    fn local_policy() -> UrlPolicy {
+        UrlPolicy {
+            require_https: false,
+            allow_private: true,
+            allowed_hosts: Vec::new(),
+        }
+    }
+
+    /// Local server answering one request with `status`
+    fn serve_once(status: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf);
+            let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        url
+    }
+
+    #[actix_web::test]
+    async fn a_reachable_url_reports_its_status() {
+        let check = check_url(&serve_once("204 No Content"), &local_policy()).await.unwrap();
+
+        assert!(check.reachable);
+        assert_eq!(check.status, Some(204));
+        assert_eq!(check.error, None);
+    }
+
+    #[actix_web::test]
+    async fn an_unreachable_url_is_reported_not_failed() {
+        // Nothing listens on the port once the listener is dropped
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+
+        let check = check_url(&format!("http://127.0.0.1:{}/hook", port), &local_policy()).await.unwrap();
+
+        assert!(!check.reachable);
+        assert_eq!(check.status, None);
+        assert!(check.error.is_some());
+    }
+
+    #[actix_web::test]
+    async fn private_addresses_are_blocked() {
+        let policy = UrlPolicy {
+            require_https: false,
+            ..UrlPolicy::default()
+        };
+
+        let result = check_url("http://127.0.0.1:8080/hook", &policy).await;
+
+        assert!(matches!(&result, Err(e) if e.contains("internal address")), "{:?}", result);
+    }
+}
@@ -1,10 +1,94 @@
+use crate::context::DatabaseInfo;
+use crate::server::error::ServerError;
+use crate::server::models::AccountUsage;
+use crate::server::service::cron_service::count_cron_jobs;
+use crate::server::service::run_service::count_retained_runs;
 use crate::server::turso::{TursoClient, UserDatabaseEntry};
+use log::warn;
 
 pub async fn create_user_database(
     client: &TursoClient,
     user_id: &str,
     email: &str,
-) -> Result<UserDatabaseEntry, String> {
-    client.create_user_database(user_id, email).await
+    region: Option<&str>,
+) -> Result<UserDatabaseEntry, ServerError> {
+    client.create_user_database(user_id, email, region).await
 }
 
+pub async fn delete_user_database(client: &TursoClient, user_id: &str) -> Result<(), ServerError> {
+    client.delete_user_database(user_id).await
+}
+
+/// What a user may see about their own database. Built field by field so the
+/// database URL and token can never reach the browser.
+pub async fn get_database_info(client: &TursoClient, user_id: &str) -> Result<DatabaseInfo, ServerError> {
+    let entry = client.get_user_database_entry(user_id).await?;
+    // The region is informational, so a Turso API failure only hides it
+    let region = match client.get_database_region(&entry.db_name).await {
+        Ok(region) => region,
+        Err(e) => {
+            warn!("Failed to get region of database {}: {}", entry.db_name, e);
+            None
+        }
+    };
+
+    Ok(DatabaseInfo {
+        db_name: entry.db_name,
+        region,
+        storage_used_bytes: entry.storage_used_bytes,
+        created_at: entry.created_at,
+    })
+}
+
+/// The user's job count, storage and retained runs next to the limits that apply.
+///
+/// Storage comes from [`TursoClient::storage_usage`], so calls in quick succession
+/// share one Turso usage API request.
+///
+/// ```
+/// use cron_jobs::server::models::CreateCronJobRequest;
+/// use cron_jobs::server::service::{create_cron_job, create_user_database, get_account_usage};
+/// use cron_jobs::server::turso::TursoClient;
+///
+/// actix_web::rt::System::new().block_on(async {
+///     let client = TursoClient::new_in_memory().await.unwrap();
+///     create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+///     let request = CreateCronJobRequest::builder()
+///         .name("Nightly backup")
+///         .schedule("0 3 * * *")
+///         .http("POST", "https://example.com/backup")
+///         .build()
+///         .unwrap();
+///     create_cron_job(&client, "user-1", request).await.unwrap();
+///
+///     let usage = get_account_usage(&client, "user-1").await.unwrap();
+///     assert_eq!(usage.jobs_used, 1);
+///     assert_eq!(usage.jobs_limit, None);
+///     assert!(usage.storage_used_bytes > 0);
+///     assert_eq!(usage.storage_limit_bytes, None);
+///     assert_eq!(usage.runs_retained, 0);
+///
+///     // Grow the database; a call right after still reports the cached size
+///     let conn = client.get_user_database_connection("user-1").await.unwrap();
+///     conn.execute("CREATE TABLE filler (data BLOB)", ()).await.unwrap();
+///     conn.execute("INSERT INTO filler VALUES (zeroblob(100000))", ()).await.unwrap();
+///     let again = get_account_usage(&client, "user-1").await.unwrap();
+///     assert_eq!(again.storage_used_bytes, usage.storage_used_bytes);
+///     assert!(client.refresh_storage_usage("user-1").await.unwrap() > usage.storage_used_bytes);
+/// });
+/// ```
+pub async fn get_account_usage(client: &TursoClient, user_id: &str) -> Result<AccountUsage, ServerError> {
+    let jobs_used = count_cron_jobs(client, user_id).await?;
+    let storage_used_bytes = client.storage_usage(user_id).await?;
+    let runs_retained = count_retained_runs(client, user_id)
+        .await
+        .map_err(ServerError::Database)?;
+
+    Ok(AccountUsage {
+        jobs_used,
+        jobs_limit: client.max_jobs_per_user(),
+        storage_used_bytes,
+        storage_limit_bytes: client.max_storage_bytes(),
+        runs_retained,
+    })
+}
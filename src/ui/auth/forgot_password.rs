@@ -0,0 +1,119 @@
+use crate::context::AuthContext;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+#[component]
+pub fn ForgotPasswordPage() -> impl IntoView {
+    let auth = expect_context::<AuthContext>();
+    
+    let email = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+    let sent_to = RwSignal::new(None::<String>);
+    let is_submitting = RwSignal::new(false);
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        
+        let email_val = email.get();
+        
+        if email_val.is_empty() {
+            error.set(Some("Email is required".to_string()));
+            return;
+        }
+        
+        if !email_val.contains('@') {
+            error.set(Some("Please enter a valid email address".to_string()));
+            return;
+        }
+        
+        error.set(None);
+        is_submitting.set(true);
+        
+        let auth_clone = auth.clone();
+        
+        spawn_local(async move {
+            match auth_clone.client.request_password_reset(email_val.clone()).await {
+                Ok(_) => {
+                    sent_to.set(Some(email_val));
+                }
+                Err(e) => {
+                    error.set(Some(format!("Could not send reset email: {}", e)));
+                }
+            }
+            is_submitting.set(false);
+        });
+    };
+
+    view! {
+        <div class="min-h-screen flex items-center justify-center bg-gradient-to-br from-blue-50 to-indigo-100">
+            <div class="max-w-md w-full space-y-8 p-8">
+                <div class="text-center">
+                    <h2 class="text-3xl font-bold text-gray-900 mb-2">"Forgot Password"</h2>
+                    <p class="text-gray-600">"We'll email you a link to reset it"</p>
+                </div>
+                
+                <div class="bg-white rounded-lg shadow-lg p-8">
+                    {move || match sent_to.get() {
+                        Some(address) => view! {
+                            <div class="space-y-6">
+                                <div class="bg-green-50 border border-green-200 text-green-700 px-4 py-3 rounded">
+                                    "If an account exists for " {address} ", a password reset link is on its way."
+                                </div>
+                                <div class="text-center">
+                                    <a href="/login" class="text-sm text-indigo-600 hover:text-indigo-500">
+                                        "Back to login"
+                                    </a>
+                                </div>
+                            </div>
+                        }.into_any(),
+                        None => view! {
+                            <form on:submit=handle_submit.clone() class="space-y-6">
+                                <div>
+                                    <label for="email" class="block text-sm font-medium text-gray-700 mb-2">
+                                        "Email"
+                                    </label>
+                                    <input
+                                        id="email"
+                                        type="email"
+                                        class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                                        placeholder="you@example.com"
+                                        prop:value=email
+                                        on:input=move |ev| {
+                                            email.set(event_target_value(&ev));
+                                            error.set(None);
+                                        }
+                                        disabled=move || is_submitting.get()
+                                    />
+                                </div>
+                                
+                                {move || error.get().map(|err| view! {
+                                    <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                        {err}
+                                    </div>
+                                })}
+                                
+                                <button
+                                    r#type="submit"
+                                    class="w-full px-4 py-2 bg-indigo-600 text-white rounded-md hover:bg-indigo-700 focus:outline-none focus:ring-2 focus:ring-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
+                                    disabled=move || is_submitting.get()
+                                >
+                                    {move || if is_submitting.get() {
+                                        "Sending..."
+                                    } else {
+                                        "Send reset link"
+                                    }}
+                                </button>
+                                
+                                <div class="text-center">
+                                    <a href="/login" class="text-sm text-indigo-600 hover:text-indigo-500">
+                                        "Remembered it? Login"
+                                    </a>
+                                </div>
+                            </form>
+                        }.into_any(),
+                    }}
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -1,17 +1,36 @@
+use crate::client::supabase::SupabaseError;
 use crate::context::AuthContext;
+use crate::ui::auth::OAuthButtons;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
+use std::time::Duration;
+
+/// Count `remaining` down to zero, one second at a time
+fn count_down(remaining: RwSignal<u64>) {
+    set_timeout(
+        move || {
+            remaining.update(|secs| *secs = secs.saturating_sub(1));
+            if remaining.get_untracked() > 0 {
+                count_down(remaining);
+            }
+        },
+        Duration::from_secs(1),
+    );
+}
 
 #[component]
 pub fn LoginPage() -> impl IntoView {
     let auth = expect_context::<AuthContext>();
     let navigate = use_navigate();
+    let session_notice = auth.session_notice;
     
     let email = RwSignal::new(String::new());
     let password = RwSignal::new(String::new());
     let error = RwSignal::new(None::<String>);
     let is_submitting = RwSignal::new(false);
+    // Seconds until Supabase accepts another sign-in attempt after rate limiting us
+    let retry_in = RwSignal::new(0u64);
 
     let handle_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
@@ -54,6 +73,14 @@ pub fn LoginPage() -> impl IntoView {
                 Ok(_) => {
                     nav("/home", Default::default());
                 }
+                Err(SupabaseError::TooManyAttempts(secs)) => {
+                    error.set(Some(SupabaseError::TooManyAttempts(secs).to_string()));
+                    is_submitting.set(false);
+                    if secs > 0 {
+                        retry_in.set(secs);
+                        count_down(retry_in);
+                    }
+                }
                 Err(e) => {
                     error.set(Some(format!("Login failed: {}", e)));
                     is_submitting.set(false);
@@ -71,6 +98,12 @@ pub fn LoginPage() -> impl IntoView {
                 </div>
                 
                 <div class="bg-white rounded-lg shadow-lg p-8">
+                    {move || session_notice.get().map(|notice| view! {
+                        <div class="bg-yellow-50 border border-yellow-200 text-yellow-800 px-4 py-3 rounded mb-6">
+                            {notice}
+                        </div>
+                    })}
+                    
                     <form on:submit=handle_submit class="space-y-6">
                         <div>
                             <label for="email" class="block text-sm font-medium text-gray-700 mb-2">
@@ -117,15 +150,28 @@ pub fn LoginPage() -> impl IntoView {
                         <button
                             r#type="submit"
                             class="w-full px-4 py-2 bg-indigo-600 text-white rounded-md hover:bg-indigo-700 focus:outline-none focus:ring-2 focus:ring-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
-                            disabled=move || is_submitting.get()
+                            disabled=move || { is_submitting.get() || retry_in.get() > 0 }
                         >
                             {move || if is_submitting.get() {
-                                "Logging in..."
+                                "Logging in...".to_string()
+                            } else if retry_in.get() > 0 {
+                                format!("Try again in {}s", retry_in.get())
                             } else {
-                                "Login"
+                                "Login".to_string()
                             }}
                         </button>
                         
+                        <OAuthButtons/>
+                        
+                        <div class="text-center">
+                            <a
+                                href="/forgot-password"
+                                class="text-sm text-indigo-600 hover:text-indigo-500"
+                            >
+                                "Forgot your password?"
+                            </a>
+                        </div>
+                        
                         <div class="text-center">
                             <a
                                 href="/signup"
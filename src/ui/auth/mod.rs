@@ -1,9 +1,15 @@
+pub mod forgot_password;
 pub mod landing;
 pub mod login;
+pub mod oauth;
+pub mod reset_password;
 pub mod signup;
 pub mod protected;
 
+pub use forgot_password::ForgotPasswordPage;
 pub use landing::LandingPage;
 pub use login::LoginPage;
+pub use oauth::{OAuthButtons, OAuthCallbackPage};
+pub use reset_password::ResetPasswordPage;
 pub use signup::SignupPage;
 
@@ -0,0 +1,97 @@
+use crate::config::{get_oauth_providers, OAUTH_PROVIDERS};
+use crate::context::AuthContext;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+
+/// Sign-in buttons for the OAuth providers enabled in this deployment. Renders nothing
+/// when none are configured.
+#[component]
+pub fn OAuthButtons() -> impl IntoView {
+    let auth = expect_context::<AuthContext>();
+    let error = RwSignal::new(None::<String>);
+    let providers: Vec<(&'static str, &'static str)> = get_oauth_providers()
+        .iter()
+        .filter_map(|name| OAUTH_PROVIDERS.iter().find(|(id, _)| id == name).copied())
+        .collect();
+
+    if providers.is_empty() {
+        return None;
+    }
+
+    Some(view! {
+        <div class="space-y-3">
+            <div class="relative flex items-center">
+                <div class="flex-grow border-t border-gray-200"></div>
+                <span class="mx-3 text-sm text-gray-500">"or continue with"</span>
+                <div class="flex-grow border-t border-gray-200"></div>
+            </div>
+            
+            {providers.into_iter().map(|(id, label)| {
+                let auth = auth.clone();
+                view! {
+                    <button
+                        r#type="button"
+                        class="w-full px-4 py-2 border border-gray-300 rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-indigo-500"
+                        on:click=move |_| {
+                            if let Err(e) = auth.client.sign_in_with_oauth(id) {
+                                error.set(Some(format!("Could not start {} sign-in: {}", label, e)));
+                            }
+                        }
+                    >
+                        {label}
+                    </button>
+                }
+            }).collect_view()}
+            
+            {move || error.get().map(|err| view! {
+                <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                    {err}
+                </div>
+            })}
+        </div>
+    })
+}
+
+/// Page the OAuth provider redirects back to. Stores the session from the URL fragment
+/// and continues to the dashboard.
+#[component]
+pub fn OAuthCallbackPage() -> impl IntoView {
+    let auth = expect_context::<AuthContext>();
+    let navigate = use_navigate();
+    let error = RwSignal::new(None::<String>);
+
+    // Effects only run in the browser, where the fragment is available
+    Effect::new(move |_| {
+        let auth = auth.clone();
+        let nav = navigate.clone();
+        spawn_local(async move {
+            match auth.complete_oauth_sign_in().await {
+                Ok(_) => nav("/home", Default::default()),
+                Err(e) => error.set(Some(format!("Sign-in failed: {}", e))),
+            }
+        });
+    });
+
+    view! {
+        <div class="min-h-screen flex items-center justify-center bg-gradient-to-br from-blue-50 to-indigo-100">
+            <div class="max-w-md w-full p-8">
+                <div class="bg-white rounded-lg shadow-lg p-8 text-center space-y-4">
+                    {move || match error.get() {
+                        Some(err) => view! {
+                            <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                {err}
+                            </div>
+                            <a href="/login" class="text-sm text-indigo-600 hover:text-indigo-500">
+                                "Back to login"
+                            </a>
+                        }.into_any(),
+                        None => view! {
+                            <p class="text-gray-600">"Signing you in..."</p>
+                        }.into_any(),
+                    }}
+                </div>
+            </div>
+        </div>
+    }
+}
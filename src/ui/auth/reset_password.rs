@@ -0,0 +1,160 @@
+#[cfg(target_arch = "wasm32")]
+use crate::client::supabase::parse_recovery_fragment;
+use crate::context::AuthContext;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Landing page for the link in a password reset email. Supabase puts the recovery
+/// access token in the URL hash, which only the browser can read.
+#[component]
+pub fn ResetPasswordPage() -> impl IntoView {
+    let auth = expect_context::<AuthContext>();
+    
+    // `None` until the hash has been read on the client
+    let access_token = RwSignal::new(None::<Result<String, String>>);
+    let password = RwSignal::new(String::new());
+    let confirm_password = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+    let is_done = RwSignal::new(false);
+    let is_submitting = RwSignal::new(false);
+
+    // Effects only run in the browser
+    #[cfg(target_arch = "wasm32")]
+    Effect::new(move |_| {
+        let hash = web_sys::window()
+            .and_then(|w| w.location().hash().ok())
+            .unwrap_or_default();
+        access_token.set(Some(parse_recovery_fragment(&hash)));
+    });
+
+    let handle_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        
+        let token = match access_token.get() {
+            Some(Ok(token)) => token,
+            _ => return,
+        };
+        let password_val = password.get();
+        let confirm_password_val = confirm_password.get();
+        
+        if password_val.len() < 6 {
+            error.set(Some("Password must be at least 6 characters".to_string()));
+            return;
+        }
+        
+        if password_val != confirm_password_val {
+            error.set(Some("Passwords do not match".to_string()));
+            return;
+        }
+        
+        error.set(None);
+        is_submitting.set(true);
+        
+        let auth_clone = auth.clone();
+        
+        spawn_local(async move {
+            match auth_clone.client.update_password(&token, password_val).await {
+                Ok(_) => is_done.set(true),
+                Err(e) => error.set(Some(format!("Could not reset password: {}", e))),
+            }
+            is_submitting.set(false);
+        });
+    };
+
+    view! {
+        <div class="min-h-screen flex items-center justify-center bg-gradient-to-br from-blue-50 to-indigo-100">
+            <div class="max-w-md w-full space-y-8 p-8">
+                <div class="text-center">
+                    <h2 class="text-3xl font-bold text-gray-900 mb-2">"Reset Password"</h2>
+                    <p class="text-gray-600">"Choose a new password"</p>
+                </div>
+                
+                <div class="bg-white rounded-lg shadow-lg p-8">
+                    {move || match (access_token.get(), is_done.get()) {
+                        (_, true) => view! {
+                            <div class="space-y-6">
+                                <div class="bg-green-50 border border-green-200 text-green-700 px-4 py-3 rounded">
+                                    "Your password has been reset."
+                                </div>
+                                <div class="text-center">
+                                    <a href="/login" class="text-sm text-indigo-600 hover:text-indigo-500">
+                                        "Continue to login"
+                                    </a>
+                                </div>
+                            </div>
+                        }.into_any(),
+                        (Some(Err(link_error)), _) => view! {
+                            <div class="space-y-6">
+                                <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                    {link_error}
+                                </div>
+                                <div class="text-center">
+                                    <a href="/forgot-password" class="text-sm text-indigo-600 hover:text-indigo-500">
+                                        "Request a new reset link"
+                                    </a>
+                                </div>
+                            </div>
+                        }.into_any(),
+                        _ => view! {
+                            <form on:submit=handle_submit.clone() class="space-y-6">
+                                <div>
+                                    <label for="password" class="block text-sm font-medium text-gray-700 mb-2">
+                                        "New Password"
+                                    </label>
+                                    <input
+                                        id="password"
+                                        type="password"
+                                        class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                                        placeholder="Enter a new password"
+                                        prop:value=password
+                                        on:input=move |ev| {
+                                            password.set(event_target_value(&ev));
+                                            error.set(None);
+                                        }
+                                        disabled=move || is_submitting.get()
+                                    />
+                                </div>
+                                
+                                <div>
+                                    <label for="confirm_password" class="block text-sm font-medium text-gray-700 mb-2">
+                                        "Confirm Password"
+                                    </label>
+                                    <input
+                                        id="confirm_password"
+                                        type="password"
+                                        class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                                        placeholder="Confirm your new password"
+                                        prop:value=confirm_password
+                                        on:input=move |ev| {
+                                            confirm_password.set(event_target_value(&ev));
+                                            error.set(None);
+                                        }
+                                        disabled=move || is_submitting.get()
+                                    />
+                                </div>
+                                
+                                {move || error.get().map(|err| view! {
+                                    <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                        {err}
+                                    </div>
+                                })}
+                                
+                                <button
+                                    r#type="submit"
+                                    class="w-full px-4 py-2 bg-indigo-600 text-white rounded-md hover:bg-indigo-700 focus:outline-none focus:ring-2 focus:ring-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
+                                    disabled=move || is_submitting.get() || access_token.get().is_none()
+                                >
+                                    {move || if is_submitting.get() {
+                                        "Saving..."
+                                    } else {
+                                        "Reset password"
+                                    }}
+                                </button>
+                            </form>
+                        }.into_any(),
+                    }}
+                </div>
+            </div>
+        </div>
+    }
+}
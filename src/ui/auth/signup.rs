@@ -1,4 +1,6 @@
+use crate::client::SupabaseError;
 use crate::context::AuthContext;
+use crate::ui::auth::OAuthButtons;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
@@ -13,6 +15,8 @@ pub fn SignupPage() -> impl IntoView {
     let confirm_password = RwSignal::new(String::new());
     let error = RwSignal::new(None::<String>);
     let is_submitting = RwSignal::new(false);
+    // Address awaiting email confirmation after a successful sign-up
+    let pending_confirmation = RwSignal::new(None::<String>);
 
     let handle_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
@@ -68,6 +72,10 @@ pub fn SignupPage() -> impl IntoView {
                 Ok(_) => {
                     nav("/home", Default::default());
                 }
+                Err(SupabaseError::ConfirmationRequired(address)) => {
+                    pending_confirmation.set(Some(address));
+                    is_submitting.set(false);
+                }
                 Err(e) => {
                     error.set(Some(format!("Sign up failed: {}", e)));
                     is_submitting.set(false);
@@ -85,91 +93,159 @@ pub fn SignupPage() -> impl IntoView {
                 </div>
                 
                 <div class="bg-white rounded-lg shadow-lg p-8">
-                    <form on:submit=handle_submit class="space-y-6">
-                        <div>
-                            <label for="email" class="block text-sm font-medium text-gray-700 mb-2">
-                                "Email"
-                            </label>
-                            <input
-                                id="email"
-                                type="email"
-                                class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
-                                placeholder="you@example.com"
-                                prop:value=email
-                                on:input=move |ev| {
-                                    email.set(event_target_value(&ev));
-                                    error.set(None);
-                                }
-                                disabled=move || is_submitting.get()
-                            />
-                        </div>
-                        
-                        <div>
-                            <label for="password" class="block text-sm font-medium text-gray-700 mb-2">
-                                "Password"
-                            </label>
-                            <input
-                                id="password"
-                                type="password"
-                                class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
-                                placeholder="Enter your password"
-                                prop:value=password
-                                on:input=move |ev| {
-                                    password.set(event_target_value(&ev));
-                                    error.set(None);
-                                }
-                                disabled=move || is_submitting.get()
-                            />
-                        </div>
-                        
-                        <div>
-                            <label for="confirm_password" class="block text-sm font-medium text-gray-700 mb-2">
-                                "Confirm Password"
-                            </label>
-                            <input
-                                id="confirm_password"
-                                type="password"
-                                class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
-                                placeholder="Confirm your password"
-                                prop:value=confirm_password
-                                on:input=move |ev| {
-                                    confirm_password.set(event_target_value(&ev));
-                                    error.set(None);
-                                }
-                                disabled=move || is_submitting.get()
-                            />
-                        </div>
-                        
-                        {move || error.get().map(|err| view! {
-                            <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
-                                {err}
+                    {move || match pending_confirmation.get() {
+                        Some(address) => view! {
+                            <ConfirmationPending email=address/>
+                        }.into_any(),
+                        None => view! {
+                        <form on:submit=handle_submit.clone() class="space-y-6">
+                            <div>
+                                <label for="email" class="block text-sm font-medium text-gray-700 mb-2">
+                                    "Email"
+                                </label>
+                                <input
+                                    id="email"
+                                    type="email"
+                                    class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                                    placeholder="you@example.com"
+                                    prop:value=email
+                                    on:input=move |ev| {
+                                        email.set(event_target_value(&ev));
+                                        error.set(None);
+                                    }
+                                    disabled=move || is_submitting.get()
+                                />
+                            </div>
+                            
+                            <div>
+                                <label for="password" class="block text-sm font-medium text-gray-700 mb-2">
+                                    "Password"
+                                </label>
+                                <input
+                                    id="password"
+                                    type="password"
+                                    class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                                    placeholder="Enter your password"
+                                    prop:value=password
+                                    on:input=move |ev| {
+                                        password.set(event_target_value(&ev));
+                                        error.set(None);
+                                    }
+                                    disabled=move || is_submitting.get()
+                                />
+                            </div>
+                            
+                            <div>
+                                <label for="confirm_password" class="block text-sm font-medium text-gray-700 mb-2">
+                                    "Confirm Password"
+                                </label>
+                                <input
+                                    id="confirm_password"
+                                    type="password"
+                                    class="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                                    placeholder="Confirm your password"
+                                    prop:value=confirm_password
+                                    on:input=move |ev| {
+                                        confirm_password.set(event_target_value(&ev));
+                                        error.set(None);
+                                    }
+                                    disabled=move || is_submitting.get()
+                                />
                             </div>
-                        })}
-                        
-                        <button
-                            r#type="submit"
-                            class="w-full px-4 py-2 bg-indigo-600 text-white rounded-md hover:bg-indigo-700 focus:outline-none focus:ring-2 focus:ring-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
-                            disabled=move || is_submitting.get()
-                        >
-                            {move || if is_submitting.get() {
-                                "Creating account..."
-                            } else {
-                                "Sign Up"
-                            }}
-                        </button>
-                        
-                        <div class="text-center">
-                            <a
-                                href="/login"
-                                class="text-sm text-indigo-600 hover:text-indigo-500"
+                            
+                            {move || error.get().map(|err| view! {
+                                <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                    {err}
+                                </div>
+                            })}
+                            
+                            <button
+                                r#type="submit"
+                                class="w-full px-4 py-2 bg-indigo-600 text-white rounded-md hover:bg-indigo-700 focus:outline-none focus:ring-2 focus:ring-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
+                                disabled=move || is_submitting.get()
                             >
-                                "Already have an account? Login"
-                            </a>
-                        </div>
-                    </form>
+                                {move || if is_submitting.get() {
+                                    "Creating account..."
+                                } else {
+                                    "Sign Up"
+                                }}
+                            </button>
+                            
+                            <OAuthButtons/>
+                            
+                            <div class="text-center">
+                                <a
+                                    href="/login"
+                                    class="text-sm text-indigo-600 hover:text-indigo-500"
+                                >
+                                    "Already have an account? Login"
+                                </a>
+                            </div>
+                        </form>
+                        }.into_any(),
+                    }}
                 </div>
             </div>
         </div>
     }
 }
 
+
+
+/// Shown after a sign-up that needs email confirmation, with a way to resend the email
+#[component]
+fn ConfirmationPending(email: String) -> impl IntoView {
+    let auth = expect_context::<AuthContext>();
+    let status = RwSignal::new(None::<String>);
+    let is_resending = RwSignal::new(false);
+    let address = StoredValue::new(email.clone());
+
+    let handle_resend = move |_| {
+        is_resending.set(true);
+        status.set(None);
+        
+        let auth_clone = auth.clone();
+        let address = address.get_value();
+        spawn_local(async move {
+            let message = match auth_clone.client.resend_confirmation(address.clone()).await {
+                Ok(_) => format!("Confirmation email sent again to {}.", address),
+                Err(SupabaseError::RateLimited(_)) => {
+                    "A confirmation email was sent recently. Please wait a minute before requesting another.".to_string()
+                }
+                Err(e) => format!("Could not resend the confirmation email: {}", e),
+            };
+            status.set(Some(message));
+            is_resending.set(false);
+        });
+    };
+
+    view! {
+        <div class="space-y-6">
+            <div class="bg-green-50 border border-green-200 text-green-700 px-4 py-3 rounded">
+                "Account created! Please check " {email} " to confirm your account before signing in."
+            </div>
+            
+            {move || status.get().map(|message| view! {
+                <p class="text-sm text-gray-600">{message}</p>
+            })}
+            
+            <div class="flex justify-between">
+                <button
+                    r#type="button"
+                    class="text-sm text-indigo-600 hover:text-indigo-500 disabled:opacity-50"
+                    on:click=handle_resend
+                    disabled=move || is_resending.get()
+                >
+                    {move || if is_resending.get() {
+                        "Sending..."
+                    } else {
+                        "Resend confirmation email"
+                    }}
+                </button>
+                <a href="/login" class="text-sm text-indigo-600 hover:text-indigo-500">
+                    "Go to login"
+                </a>
+            </div>
+        </div>
+    }
+}
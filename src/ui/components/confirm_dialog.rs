@@ -0,0 +1,176 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::future::Future;
+
+/// Whether a [`ConfirmDialog`] is shown, running its action, or showing why it failed
+#[derive(Clone, Copy)]
+struct ConfirmState {
+    open: RwSignal<bool>,
+    error: RwSignal<Option<String>>,
+    is_running: RwSignal<bool>,
+}
+
+impl ConfirmState {
+    fn new(open: RwSignal<bool>) -> Self {
+        ConfirmState {
+            open,
+            error: RwSignal::new(None),
+            is_running: RwSignal::new(false),
+        }
+    }
+
+    /// Mark the action as running; false when it already is, so it isn't run twice
+    fn start(self) -> bool {
+        if self.is_running.get_untracked() {
+            return false;
+        }
+        self.error.set(None);
+        self.is_running.set(true);
+        true
+    }
+
+    /// Close on success, or stay open with the error shown
+    async fn finish(self, action: impl Future<Output = Result<(), String>>) {
+        match action.await {
+            Ok(()) => self.open.set(false),
+            Err(e) => self.error.set(Some(e)),
+        }
+        self.is_running.set(false);
+    }
+
+    fn cancel(self) {
+        if !self.is_running.get_untracked() {
+            self.error.set(None);
+            self.open.set(false);
+        }
+    }
+}
+
+/// Modal asking the user to confirm a destructive action
+///
+/// Shown while `open` is true. Confirming runs `on_confirm` with a spinner on the
+/// button; the dialog closes when it succeeds and stays open with the error shown
+/// inline when it fails, so the user can retry or cancel.
+#[component]
+pub fn ConfirmDialog<F, Fut>(
+    open: RwSignal<bool>,
+    #[prop(into)] message: String,
+    #[prop(into, default = "Confirm".to_string())] confirm_label: String,
+    on_confirm: F,
+) -> impl IntoView
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), String>> + 'static,
+{
+    let state = ConfirmState::new(open);
+    let on_confirm = StoredValue::new(on_confirm);
+
+    let handle_confirm = move || {
+        if state.start() {
+            let action = on_confirm.with_value(|f| f());
+            spawn_local(state.finish(action));
+        }
+    };
+
+    dialog_view(state, message, confirm_label, handle_confirm)
+}
+
+fn dialog_view(
+    state: ConfirmState,
+    message: String,
+    confirm_label: String,
+    handle_confirm: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let ConfirmState { open, error, is_running } = state;
+    let message = StoredValue::new(message);
+    let confirm_label = StoredValue::new(confirm_label);
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="fixed inset-0 z-50 flex items-center justify-center bg-black bg-opacity-40">
+                <div class="bg-white rounded-lg shadow-lg p-6 max-w-sm w-full space-y-4" role="dialog" aria-modal="true">
+                    <p class="text-gray-900">{message.get_value()}</p>
+
+                    {move || error.get().map(|err| view! {
+                        <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                            {err}
+                        </div>
+                    })}
+
+                    <div class="flex justify-end space-x-3">
+                        <button
+                            r#type="button"
+                            class="px-4 py-2 bg-white border border-gray-300 text-gray-700 rounded-md hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed"
+                            on:click=move |_| state.cancel()
+                            disabled=move || is_running.get()
+                        >
+                            "Cancel"
+                        </button>
+                        <button
+                            r#type="button"
+                            class="inline-flex items-center px-4 py-2 bg-red-600 text-white rounded-md hover:bg-red-700 focus:outline-none focus:ring-2 focus:ring-red-500 disabled:opacity-50 disabled:cursor-not-allowed"
+                            on:click=move |_| handle_confirm()
+                            disabled=move || is_running.get()
+                        >
+                            <Show when=move || is_running.get()>
+                                <span class="inline-block animate-spin rounded-full h-4 w-4 border-b-2 border-white mr-2"></span>
+                            </Show>
+                            {confirm_label.get_value()}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    fn render(state: ConfirmState) -> String {
+        dialog_view(state, "Delete job?".to_string(), "Delete".to_string(), || {})
+            .to_html()
+    }
+
+    #[test]
+    fn a_failed_action_keeps_the_dialog_open_with_the_error() {
+        let owner = Owner::new();
+        owner.set();
+        let state = ConfirmState::new(RwSignal::new(true));
+
+        assert!(state.start());
+        assert!(!state.start(), "a running action must not start again");
+        block_on(state.finish(async { Err("Job is locked".to_string()) }));
+
+        assert!(state.open.get_untracked());
+        assert!(!state.is_running.get_untracked());
+        let html = render(state);
+        assert!(html.contains("Delete job?"), "{}", html);
+        assert!(html.contains("Job is locked"), "{}", html);
+
+        // Retrying clears the error and closes the dialog once the action succeeds
+        assert!(state.start());
+        assert_eq!(state.error.get_untracked(), None);
+        block_on(state.finish(async { Ok(()) }));
+        assert!(!state.open.get_untracked());
+        assert!(!render(state).contains("Delete job?"));
+    }
+
+    #[test]
+    fn cancel_is_ignored_while_the_action_runs() {
+        let owner = Owner::new();
+        owner.set();
+        let state = ConfirmState::new(RwSignal::new(true));
+
+        state.start();
+        state.cancel();
+        assert!(state.open.get_untracked());
+
+        block_on(state.finish(async { Err("failed".to_string()) }));
+        state.cancel();
+        assert!(!state.open.get_untracked());
+        assert_eq!(state.error.get_untracked(), None);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::context::{get_database_info_action, AuthContext, DatabaseInfo};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Size in bytes as a short human-readable string (`1.5 MB`)
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes.max(0) as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes.max(0), UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Storage used and region of the signed-in user's database
+///
+/// Renders nothing until the database exists, and reloads when the session changes
+/// or database setup finishes.
+#[component]
+pub fn DatabaseInfoPanel() -> impl IntoView {
+    let auth = expect_context::<AuthContext>();
+    let info = RwSignal::new(None::<DatabaseInfo>);
+
+    Effect::new(move |_| {
+        auth.db_status.track();
+        if auth.session.with(Option::is_none) {
+            info.set(None);
+            return;
+        }
+        let auth = auth.clone();
+        spawn_local(async move {
+            let result = auth.call(get_database_info_action).await;
+            info.set(result.ok());
+        });
+    });
+
+    move || {
+        info.get().map(|info| {
+            let storage = info
+                .storage_used_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "Not measured yet".to_string());
+            let region = info.region.unwrap_or_else(|| "Unknown".to_string());
+
+            view! {
+                <div class="mt-6 bg-white shadow rounded-lg p-4">
+                    <h2 class="text-lg font-semibold text-gray-900 mb-2">"Your database"</h2>
+                    <dl class="grid grid-cols-2 gap-2 text-sm">
+                        <dt class="text-gray-500">"Storage used"</dt>
+                        <dd class="text-gray-900">{storage}</dd>
+                        <dt class="text-gray-500">"Region"</dt>
+                        <dd class="text-gray-900">{region}</dd>
+                    </dl>
+                </div>
+            }
+        })
+    }
+}
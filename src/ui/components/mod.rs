@@ -0,0 +1,5 @@
+pub mod confirm_dialog;
+pub mod database_info;
+
+pub use confirm_dialog::ConfirmDialog;
+pub use database_info::DatabaseInfoPanel;
@@ -0,0 +1,178 @@
+use crate::context::{
+    delete_dashboard_job_action, get_dashboard_jobs_action, set_dashboard_job_enabled_action,
+    AuthContext, DashboardJob,
+};
+use crate::ui::components::ConfirmDialog;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Small spinning indicator used while jobs load or a toggle is saving
+#[component]
+fn Spinner() -> impl IntoView {
+    view! {
+        <span class="inline-block animate-spin rounded-full h-4 w-4 border-b-2 border-gray-600"></span>
+    }
+}
+
+/// The signed-in user's cron jobs with delete and enable/disable controls
+///
+/// Reloads when the session changes or database setup finishes, and after every
+/// delete or toggle so the table always shows what the server stored.
+#[component]
+pub fn CronJobList() -> impl IntoView {
+    // Stored so the closures below stay `Copy`
+    let auth = StoredValue::new(expect_context::<AuthContext>());
+    let jobs = LocalResource::new(move || {
+        let auth = auth.get_value();
+        auth.db_status.track();
+        let signed_in = auth.session.with(Option::is_some);
+        async move {
+            if signed_in {
+                auth.call(get_dashboard_jobs_action).await.map_err(|e| e.to_string())
+            } else {
+                Ok(Vec::new())
+            }
+        }
+    });
+
+    let error = RwSignal::new(None::<String>);
+    let toggling = RwSignal::new(None::<String>);
+    let confirm_open = RwSignal::new(false);
+    let pending_delete = RwSignal::new(None::<DashboardJob>);
+
+    let toggle = move |job: DashboardJob| {
+        error.set(None);
+        toggling.set(Some(job.id.clone()));
+        spawn_local(async move {
+            let enabled = !job.enabled;
+            let result = auth
+                .get_value()
+                .call(|| set_dashboard_job_enabled_action(job.id.clone(), enabled))
+                .await;
+            if let Err(e) = result {
+                error.set(Some(format!("Failed to update {}: {}", job.name, e)));
+            }
+            toggling.set(None);
+            jobs.refetch();
+        });
+    };
+
+    let delete_dialog = move || {
+        pending_delete.get().map(|job| {
+            let job_id = job.id.clone();
+            view! {
+                <ConfirmDialog
+                    open=confirm_open
+                    message=format!("Move \"{}\" to the trash?", job.name)
+                    confirm_label="Delete"
+                    on_confirm=move || {
+                        let job_id = job_id.clone();
+                        async move {
+                            auth.get_value()
+                                .call(|| delete_dashboard_job_action(job_id.clone()))
+                                .await
+                                .map_err(|e| e.to_string())?;
+                            jobs.refetch();
+                            Ok(())
+                        }
+                    }
+                />
+            }
+            .into_any()
+        })
+    };
+
+    let row = move |job: DashboardJob| {
+        let toggle_job = job.clone();
+        let delete_job = job.clone();
+        let job_id = job.id.clone();
+        let is_toggling = move || toggling.get().as_deref() == Some(job_id.as_str());
+        let schedule_human = job.schedule_human.clone();
+
+        view! {
+            <tr class="border-t border-gray-200">
+                <td class="px-4 py-2 text-gray-900">{job.name.clone()}</td>
+                <td class="px-4 py-2">
+                    <code class="text-sm text-gray-700">{job.schedule.clone()}</code>
+                    {schedule_human.map(|human| view! {
+                        <div class="text-xs text-gray-500">{human}</div>
+                    })}
+                </td>
+                <td class="px-4 py-2">
+                    <button
+                        class="inline-flex items-center gap-2 text-sm px-2 py-1 rounded border border-gray-300 hover:bg-gray-100 disabled:opacity-50"
+                        disabled=move || toggling.get().is_some()
+                        on:click=move |_| toggle(toggle_job.clone())
+                    >
+                        {move || is_toggling().then(|| view! { <Spinner/> })}
+                        {if job.enabled { "Enabled" } else { "Disabled" }}
+                    </button>
+                </td>
+                <td class="px-4 py-2 text-right">
+                    <button
+                        class="text-sm text-red-600 hover:text-red-800"
+                        on:click=move |_| {
+                            pending_delete.set(Some(delete_job.clone()));
+                            confirm_open.set(true);
+                        }
+                    >
+                        "Delete"
+                    </button>
+                </td>
+            </tr>
+        }
+        .into_any()
+    };
+
+    view! {
+        <div class="mt-6 bg-white shadow rounded-lg p-4">
+            <h2 class="text-lg font-semibold text-gray-900 mb-2">"Your cron jobs"</h2>
+
+            {move || error.get().map(|err| view! {
+                <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded mb-2">
+                    {err}
+                </div>
+            })}
+
+            <Suspense fallback=|| view! {
+                <div class="flex items-center gap-2 text-gray-500">
+                    <Spinner/>
+                    "Loading jobs..."
+                </div>
+            }>
+                {move || Suspend::new(async move {
+                    match jobs.await {
+                        Ok(list) if list.is_empty() => view! {
+                            <p class="text-gray-500">"No cron jobs yet."</p>
+                        }
+                        .into_any(),
+                        Ok(list) => view! {
+                            <table class="w-full text-left">
+                                <thead>
+                                    <tr class="text-sm text-gray-500">
+                                        <th class="px-4 py-2 font-medium">"Name"</th>
+                                        <th class="px-4 py-2 font-medium">"Schedule"</th>
+                                        <th class="px-4 py-2 font-medium">"Enabled"</th>
+                                        <th class="px-4 py-2"></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {list.into_iter().map(row).collect_view()}
+                                </tbody>
+                            </table>
+                        }
+                        .into_any(),
+                        Err(e) => view! {
+                            <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                {format!("Failed to load jobs: {}", e)}
+                            </div>
+                        }
+                        .into_any(),
+                    }
+                })}
+            </Suspense>
+
+            {delete_dialog}
+        </div>
+    }
+}
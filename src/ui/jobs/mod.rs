@@ -0,0 +1,3 @@
+pub mod job_list;
+
+pub use job_list::CronJobList;
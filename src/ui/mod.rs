@@ -1,2 +1,3 @@
 pub mod auth;
-
+pub mod components;
+pub mod jobs;
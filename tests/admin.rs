@@ -0,0 +1,191 @@
+use cron_jobs::server::error::ServerError;
+use cron_jobs::server::service::create_user_database;
+use chrono::{TimeZone, Utc};
+use cron_jobs::server::turso::{
+    get_supabase_user_by_email, AuthError, ProvisionStatus, ServiceRoleKey, SupabaseConfig, TursoClient,
+    TursoConfig, UserDatabaseFilter,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+/// Supabase config pointing at a stand-in admin API that answers one request with
+/// `status` and `body`, reporting the request line and headers it received
+fn mock_admin_api(status: &str, body: &str) -> (SupabaseConfig, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            request.push_str(&line);
+        }
+        stream.write_all(response.as_bytes()).unwrap();
+        tx.send(request).unwrap();
+    });
+
+    let mut config = TursoConfig::for_testing().supabase;
+    config.url = url;
+    config.service_role_key = ServiceRoleKey::new("service-role".to_string());
+    (config, rx)
+}
+
+const USERS: &str = r#"{"users": [
+    {"id": "u-1", "email": "ann@example.com.au", "phone": "+100"},
+    {"id": "u-2", "email": "Ann@Example.com", "phone": "+200", "created_at": "2024-01-01T00:00:00Z",
+     "user_metadata": {"name": "Ann"}, "identities": [{"provider": "github"}]}
+]}"#;
+
+#[actix_web::test]
+async fn admin_lookup_finds_the_exact_email() {
+    let (config, request) = mock_admin_api("200 OK", USERS);
+
+    let user = get_supabase_user_by_email("ann@example.com", &config).await.unwrap();
+
+    assert_eq!(user.id, "u-2");
+    assert_eq!(user.created_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+    let serialized = serde_json::to_string(&user).unwrap();
+    assert!(!serialized.contains("+200") && !serialized.contains("github"), "{}", serialized);
+    let request = request.recv().unwrap();
+    assert!(request.starts_with("GET /auth/v1/admin/users?filter=ann%40example.com "), "{}", request);
+    assert!(request.to_lowercase().contains("authorization: bearer service-role"), "{}", request);
+}
+
+#[actix_web::test]
+async fn admin_lookup_reports_a_missing_user_as_not_found() {
+    let (config, _request) = mock_admin_api("200 OK", USERS);
+
+    let result = get_supabase_user_by_email("ann@example.org", &config).await;
+
+    assert!(matches!(result, Err(AuthError::UserNotFound(_))), "{:?}", result);
+}
+
+#[actix_web::test]
+async fn admin_lookup_tells_a_rejected_key_apart_from_not_found() {
+    let (config, _request) = mock_admin_api("401 Unauthorized", r#"{"msg": "invalid JWT"}"#);
+
+    let result = get_supabase_user_by_email("ann@example.com", &config).await;
+
+    assert!(matches!(result, Err(AuthError::AdminUnauthorized(_))), "{:?}", result);
+}
+
+#[actix_web::test]
+async fn schema_sync_targets_the_given_user() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+
+    let report = client.sync_user_database_schema("user-1").await.unwrap();
+    assert!(!report.current_version.is_empty());
+    client
+        .record_admin_event("admin-1", "sync_user_schema", Some("user-1"), Some("ok"))
+        .await
+        .unwrap();
+
+    let mut rows = client
+        .get_registry_connection()
+        .await
+        .unwrap()
+        .query("SELECT admin_user_id, action, target_user_id FROM admin_events", ())
+        .await
+        .unwrap();
+    let row = rows.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<String>(0).unwrap(), "admin-1");
+    assert_eq!(row.get::<String>(1).unwrap(), "sync_user_schema");
+    assert_eq!(row.get::<String>(2).unwrap(), "user-1");
+}
+
+#[actix_web::test]
+async fn schema_sync_of_an_unknown_user_is_not_found() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+
+    let result = client.sync_user_database_schema("nobody").await;
+
+    assert!(matches!(result, Err(ServerError::NotFound(_))), "{:?}", result);
+}
+
+#[actix_web::test]
+async fn registry_repair_recreates_what_is_missing() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    client
+        .get_registry_connection()
+        .await
+        .unwrap()
+        .execute("DROP INDEX idx_user_databases_email", ())
+        .await
+        .unwrap();
+
+    let report = client.repair_registry_schema().await.unwrap();
+
+    assert_eq!(report.repaired, vec!["index idx_user_databases_email"]);
+    assert!(client.repair_registry_schema().await.unwrap().repaired.is_empty());
+}
+
+/// Registry entry for `user_id` at provisioning step `status`, created at `created_at`
+async fn seed_registry(client: &TursoClient, user_id: &str, status: &str, created_at: &str) {
+    client
+        .get_registry_connection()
+        .await
+        .unwrap()
+        .execute(
+            "INSERT INTO user_databases
+             (user_id, email, db_name, db_url, db_token, storage_used_bytes, provision_status, created_at, updated_at)
+             VALUES (?1, 'user@example.com', 'user-' || ?1, '', '', 0, ?2, ?3, ?3)",
+            [user_id, status, created_at],
+        )
+        .await
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn registry_listing_is_filtered_by_status_and_creation_window() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    seed_registry(&client, "early", "ready", "2025-03-01T08:00:00+00:00").await;
+    seed_registry(&client, "stuck-early", "pending", "2025-03-01T09:30:00+00:00").await;
+    seed_registry(&client, "stuck-late", "pending", "2025-03-01T18:00:00.250+00:00").await;
+    seed_registry(&client, "half-done", "database_created", "2025-03-02T00:00:00+00:00").await;
+    let users = |filter: UserDatabaseFilter| {
+        let client = &client;
+        async move {
+            let entries = client.list_user_databases(&filter).await.unwrap();
+            entries.into_iter().map(|entry| entry.user_id).collect::<Vec<_>>()
+        }
+    };
+
+    let stuck = users(UserDatabaseFilter {
+        provision_status: Some(ProvisionStatus::Pending),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(stuck, ["stuck-early", "stuck-late"]);
+
+    // Bounds on the same day as the entries
+    let window = users(UserDatabaseFilter {
+        created_after: Some(Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap()),
+        created_before: Some(Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(window, ["stuck-early", "stuck-late"]);
+
+    let stuck_since_noon = users(UserDatabaseFilter {
+        provision_status: Some(ProvisionStatus::Pending),
+        created_after: Some(Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(stuck_since_noon, ["stuck-late"]);
+}
@@ -0,0 +1,112 @@
+use cron_jobs::server::models::{
+    AccountImportMode, CreateCronJobRequest, JobsBackup, ListCronJobsFilter, Pagination,
+    JOBS_BACKUP_FORMAT_VERSION,
+};
+use cron_jobs::server::service::{
+    create_cron_job, create_user_database, export_account, get_user_cron_jobs, import_account,
+};
+use cron_jobs::server::turso::TursoClient;
+
+async fn client(user_id: &str) -> TursoClient {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, user_id, "user@example.com", None).await.unwrap();
+    client
+}
+
+/// The archived jobs in name order, as JSON so whole jobs can be compared
+fn archived_jobs(json: &str) -> serde_json::Value {
+    let mut backup: JobsBackup = serde_json::from_str(json).unwrap();
+    backup.jobs.sort_by(|a, b| a.name.cmp(&b.name));
+    serde_json::to_value(backup.jobs).unwrap()
+}
+
+#[actix_web::test]
+async fn an_exported_account_restores_into_a_fresh_store() {
+    let source = client("alice").await;
+    let requests = [
+        CreateCronJobRequest::builder()
+            .name("Backup")
+            .schedule("0 3 * * *")
+            .http("GET", "https://example.com/backup")
+            .timezone("Europe/Berlin")
+            .retries(3, 60)
+            .build()
+            .unwrap(),
+        CreateCronJobRequest::builder()
+            .name("Ping")
+            .schedule("*/5 * * * *")
+            .http("POST", "https://example.com/ping")
+            .http_body("{}")
+            .enabled(false)
+            .run_history_limit(10)
+            .build()
+            .unwrap(),
+    ];
+    for request in requests {
+        create_cron_job(&source, "alice", request).await.unwrap();
+    }
+    let archive = export_account(&source, "alice").await.unwrap();
+
+    let target = client("alice").await;
+    let report = import_account(&target, "alice", &archive, AccountImportMode::Merge).await.unwrap();
+
+    assert_eq!((report.created, report.failed, report.trashed), (2, 0, 0));
+    let restored = export_account(&target, "alice").await.unwrap();
+    assert_eq!(archived_jobs(&restored), archived_jobs(&archive));
+}
+
+#[actix_web::test]
+async fn replace_trashes_the_jobs_already_in_the_account() {
+    let client = client("alice").await;
+    let request = |name: &str| {
+        CreateCronJobRequest::builder()
+            .name(name)
+            .schedule("0 3 * * *")
+            .http("GET", "https://example.com/hi")
+            .build()
+            .unwrap()
+    };
+    create_cron_job(&client, "alice", request("Archived")).await.unwrap();
+    let archive = export_account(&client, "alice").await.unwrap();
+    create_cron_job(&client, "alice", request("Newer")).await.unwrap();
+
+    let report = import_account(&client, "alice", &archive, AccountImportMode::Replace).await.unwrap();
+
+    assert_eq!((report.trashed, report.created), (2, 1));
+    let page = get_user_cron_jobs(&client, "alice", ListCronJobsFilter::default(), Pagination::default())
+        .await
+        .unwrap();
+    let names: Vec<&str> = page.jobs.iter().map(|job| job.name.as_str()).collect();
+    assert_eq!(names, ["Archived"]);
+}
+
+#[actix_web::test]
+async fn archives_from_a_newer_format_are_refused_before_anything_changes() {
+    let client = client("alice").await;
+    let archive = serde_json::json!({
+        "format_version": JOBS_BACKUP_FORMAT_VERSION + 1,
+        "exported_at": "2026-01-01T00:00:00Z",
+        "jobs": [],
+    })
+    .to_string();
+    create_cron_job(
+        &client,
+        "alice",
+        CreateCronJobRequest::builder()
+            .name("Kept")
+            .schedule("0 3 * * *")
+            .http("GET", "https://example.com/hi")
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let result = import_account(&client, "alice", &archive, AccountImportMode::Replace).await;
+
+    assert!(matches!(&result, Err(msg) if msg.contains("Unsupported backup format version")), "{:?}", result);
+    let page = get_user_cron_jobs(&client, "alice", ListCronJobsFilter::default(), Pagination::default())
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+}
@@ -0,0 +1,25 @@
+use cron_jobs::server::models::BuildMetadata;
+use cron_jobs::server::service::create_user_database;
+use cron_jobs::server::turso::schema::get_current_schema_version;
+use cron_jobs::server::turso::{get_user_schema_version, TursoClient};
+
+#[test]
+fn build_info_reports_the_crate_and_schema_versions() {
+    let build = BuildMetadata::current();
+
+    assert_eq!(build.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(build.schema_version, get_current_schema_version().version);
+    assert!(!build.git_sha.is_empty());
+    assert!(chrono::DateTime::parse_from_rfc3339(&build.built_at).is_ok(), "{}", build.built_at);
+}
+
+#[actix_web::test]
+async fn new_user_databases_are_at_the_reported_schema_version() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+
+    let stored = get_user_schema_version(&conn).await.unwrap().unwrap();
+
+    assert_eq!(stored.version, BuildMetadata::current().schema_version);
+}
@@ -0,0 +1,383 @@
+use chrono::{Duration, Utc};
+use cron_jobs::server::error::ServerError;
+use cron_jobs::server::models::{
+    CreateCronJobRequest, CronJob, CronJobPage, JobType, ListCronJobsFilter, Pagination, UpdateCronJobRequest,
+    MAX_PAGE_SIZE,
+};
+use cron_jobs::server::service::{
+    bulk_update_enabled, check_batch_size, create_cron_job, create_user_database, delete_cron_job, delete_cron_jobs, get_cron_job,
+    get_cron_jobs_by_ids, get_user_cron_jobs, list_trashed_cron_jobs, purge_deleted, update_cron_job,
+};
+use cron_jobs::server::turso::{TursoClient, TursoConfig};
+
+async fn client() -> TursoClient {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    client
+}
+
+fn request(name: &str, schedule: &str, enabled: bool) -> CreateCronJobRequest {
+    CreateCronJobRequest {
+        name: name.to_string(),
+        schedule: schedule.to_string(),
+        command: "https://example.com/hook".to_string(),
+        enabled: Some(enabled),
+        timezone: None,
+        job_type: Some(JobType::Http),
+        http_method: None,
+        http_body: None,
+        max_retries: None,
+        retry_backoff_secs: None,
+        run_history_limit: None,
+        misfire_policy: None,
+        max_catchup: None,
+    }
+}
+
+fn update(fields: serde_json::Value) -> UpdateCronJobRequest {
+    serde_json::from_value(fields).unwrap()
+}
+
+#[test]
+fn batches_are_capped_at_one_page_of_jobs() {
+    let ids = |n: u32| (0..n).map(|i| format!("job-{}", i)).collect::<Vec<_>>();
+
+    assert!(check_batch_size(&ids(MAX_PAGE_SIZE)).is_ok());
+    assert!(matches!(check_batch_size(&ids(MAX_PAGE_SIZE + 1)), Err(ServerError::Validation(_))));
+}
+
+#[actix_web::test]
+async fn an_enabled_job_that_never_fires_is_rejected() {
+    let client = client().await;
+
+    // February never has a 30th
+    let result = create_cron_job(&client, "user-1", request("Never", "0 0 30 2 *", true)).await;
+
+    assert!(matches!(&result, Err(ServerError::Validation(msg)) if msg.contains("never fires")), "{:?}", result);
+}
+
+#[actix_web::test]
+async fn a_disabled_draft_that_never_fires_is_saved_with_a_warning() {
+    let client = client().await;
+
+    let job = create_cron_job(&client, "user-1", request("Draft", "0 0 30 2 *", false)).await.unwrap();
+
+    assert!(!job.enabled);
+    assert!(job.warning.as_deref().unwrap().contains("never fires"));
+    let healthy = create_cron_job(&client, "user-1", request("Healthy", "0 0 * * *", true)).await.unwrap();
+    assert_eq!(healthy.warning, None);
+
+    // Enabling the draft is refused until the schedule is fixed
+    let result = update_cron_job(&client, "user-1", &job.id, update(serde_json::json!({ "enabled": true }))).await;
+    assert!(matches!(result, Err(ServerError::Validation(_))), "{:?}", result);
+    let fixed = update(serde_json::json!({ "enabled": true, "schedule": "0 0 28 2 *" }));
+    let job = update_cron_job(&client, "user-1", &job.id, fixed).await.unwrap();
+    assert!(job.enabled);
+    assert_eq!(job.warning, None);
+}
+
+#[actix_web::test]
+async fn batch_delete_reports_the_ids_it_skipped() {
+    let client = client().await;
+    create_user_database(&client, "user-2", "other@example.com", None).await.unwrap();
+    let first = create_cron_job(&client, "user-1", request("First", "0 1 * * *", true)).await.unwrap();
+    let second = create_cron_job(&client, "user-1", request("Second", "0 2 * * *", true)).await.unwrap();
+    let theirs = create_cron_job(&client, "user-2", request("Theirs", "0 3 * * *", true)).await.unwrap();
+
+    let ids = vec![first.id.clone(), theirs.id.clone(), "missing".to_string(), second.id.clone()];
+    let result = delete_cron_jobs(&client, "user-1", ids).await.unwrap();
+
+    assert_eq!(result.succeeded, [first.id.clone(), second.id.clone()]);
+    assert_eq!(result.skipped, [theirs.id.clone(), "missing".to_string()]);
+    assert!(get_cron_job(&client, "user-2", &theirs.id).await.is_ok());
+    // Already in the trash, so skipped the second time
+    let again = delete_cron_jobs(&client, "user-1", vec![first.id.clone()]).await.unwrap();
+    assert_eq!(again.skipped, [first.id]);
+}
+
+#[actix_web::test]
+async fn bulk_enable_refuses_schedules_that_never_fire() {
+    let client = client().await;
+    let paused = create_cron_job(&client, "user-1", request("Paused", "0 3 * * *", false)).await.unwrap();
+    let draft = create_cron_job(&client, "user-1", request("Draft", "0 0 30 2 *", false)).await.unwrap();
+    let ids = vec![paused.id.clone(), draft.id.clone(), "missing".to_string()];
+
+    let result = bulk_update_enabled(&client, "user-1", &ids, true).await.unwrap();
+
+    assert_eq!(result.succeeded, [paused.id.clone()]);
+    assert_eq!(result.skipped, ["missing"]);
+    assert_eq!(result.failed.len(), 1);
+    assert_eq!(result.failed[0].job_id, draft.id);
+    assert!(result.failed[0].error.contains("never fires"), "{}", result.failed[0].error);
+    assert!(get_cron_job(&client, "user-1", &paused.id).await.unwrap().enabled);
+    assert!(!get_cron_job(&client, "user-1", &draft.id).await.unwrap().enabled);
+
+    // Disabling has nothing to check
+    let result = bulk_update_enabled(&client, "user-1", &ids[..2], false).await.unwrap();
+    assert_eq!(result.succeeded, [paused.id, draft.id]);
+    assert!(result.failed.is_empty());
+}
+
+#[actix_web::test]
+async fn deleting_a_missing_or_foreign_job_is_not_found() {
+    let client = client().await;
+    create_user_database(&client, "user-2", "other@example.com", None).await.unwrap();
+    let theirs = create_cron_job(&client, "user-2", request("Theirs", "0 3 * * *", true)).await.unwrap();
+
+    let missing = delete_cron_job(&client, "user-1", "missing").await;
+    assert!(matches!(missing, Err(ServerError::NotFound(_))), "{:?}", missing);
+
+    let foreign = delete_cron_job(&client, "user-1", &theirs.id).await;
+    assert!(matches!(foreign, Err(ServerError::NotFound(_))), "{:?}", foreign);
+    assert!(get_cron_job(&client, "user-2", &theirs.id).await.is_ok());
+}
+
+#[actix_web::test]
+async fn lookup_by_ids_returns_only_the_users_live_jobs_in_request_order() {
+    let client = client().await;
+    create_user_database(&client, "user-2", "other@example.com", None).await.unwrap();
+    let first = create_cron_job(&client, "user-1", request("First", "0 1 * * *", true)).await.unwrap();
+    let second = create_cron_job(&client, "user-1", request("Second", "0 2 * * *", true)).await.unwrap();
+    let trashed = create_cron_job(&client, "user-1", request("Trashed", "0 4 * * *", true)).await.unwrap();
+    let theirs = create_cron_job(&client, "user-2", request("Theirs", "0 3 * * *", true)).await.unwrap();
+    delete_cron_job(&client, "user-1", &trashed.id).await.unwrap();
+
+    let ids = vec![second.id.clone(), theirs.id, "missing".to_string(), trashed.id, first.id.clone()];
+    let jobs = get_cron_jobs_by_ids(&client, "user-1", ids).await.unwrap();
+
+    let found: Vec<String> = jobs.into_iter().map(|job| job.id).collect();
+    assert_eq!(found, [second.id, first.id]);
+}
+
+#[actix_web::test]
+async fn a_database_error_rolls_the_whole_batch_delete_back() {
+    let client = client().await;
+    let first = create_cron_job(&client, "user-1", request("First", "0 1 * * *", true)).await.unwrap();
+    let second = create_cron_job(&client, "user-1", request("Second", "0 2 * * *", true)).await.unwrap();
+    client
+        .get_user_database_connection("user-1")
+        .await
+        .unwrap()
+        .execute(
+            &format!(
+                "CREATE TRIGGER fail_second BEFORE UPDATE OF deleted_at ON cron_jobs WHEN OLD.id = '{}'
+                 BEGIN SELECT RAISE(ABORT, 'disk I/O error'); END",
+                second.id
+            ),
+            (),
+        )
+        .await
+        .unwrap();
+
+    let result = delete_cron_jobs(&client, "user-1", vec![first.id.clone(), second.id.clone()]).await;
+
+    assert!(matches!(result, Err(ServerError::Database(_))), "{:?}", result);
+    assert_eq!(get_cron_job(&client, "user-1", &first.id).await.unwrap().deleted_at, None);
+}
+
+#[actix_web::test]
+async fn shell_jobs_are_refused_unless_the_operator_allows_them() {
+    let shell = || CreateCronJobRequest {
+        command: "echo hi".to_string(),
+        job_type: Some(JobType::Shell),
+        ..request("Backup", "0 3 * * *", true)
+    };
+
+    let client = client().await;
+    let refused = create_cron_job(&client, "user-1", shell()).await;
+    assert!(matches!(&refused, Err(ServerError::Validation(msg)) if msg.contains("Shell jobs are disabled")), "{:?}", refused);
+    // An HTTP job can't be turned into one either
+    let job = create_cron_job(&client, "user-1", request("Hook", "0 3 * * *", true)).await.unwrap();
+    let changed = update_cron_job(&client, "user-1", &job.id, update(serde_json::json!({ "job_type": "shell", "command": "echo hi" }))).await;
+    assert!(matches!(&changed, Err(ServerError::Validation(msg)) if msg.contains("Shell jobs are disabled")), "{:?}", changed);
+
+    let allowed = TursoClient::new_in_memory_with_config(TursoConfig { allow_shell_jobs: true, ..TursoConfig::for_testing() })
+        .await
+        .unwrap();
+    create_user_database(&allowed, "user-1", "user@example.com", None).await.unwrap();
+    let job = create_cron_job(&allowed, "user-1", shell()).await.unwrap();
+    assert_eq!(job.job_type, JobType::Shell);
+}
+
+#[actix_web::test]
+async fn malformed_schedules_are_refused_on_create_and_update() {
+    let client = client().await;
+
+    let result = create_cron_job(&client, "user-1", request("Typo", "* * *", true)).await;
+    assert!(matches!(&result, Err(ServerError::Validation(msg)) if msg.contains("Invalid schedule")), "{:?}", result);
+
+    let job = create_cron_job(&client, "user-1", request("Fine", "0 * * * *", true)).await.unwrap();
+    let result = update_cron_job(&client, "user-1", &job.id, update(serde_json::json!({ "schedule": "60 * * * *" }))).await;
+    assert!(matches!(&result, Err(ServerError::Validation(msg)) if msg.contains("minute")), "{:?}", result);
+}
+
+#[actix_web::test]
+async fn macros_are_stored_expanded() {
+    let client = client().await;
+
+    let job = create_cron_job(&client, "user-1", request("Nightly", "@daily", true)).await.unwrap();
+    assert_eq!(job.schedule, "0 0 * * *");
+
+    let job = update_cron_job(&client, "user-1", &job.id, update(serde_json::json!({ "schedule": "@hourly" }))).await.unwrap();
+    assert_eq!(job.schedule, "0 * * * *");
+}
+
+#[actix_web::test]
+async fn listed_jobs_carry_a_readable_schedule() {
+    let client = client().await;
+    create_cron_job(&client, "user-1", request("Workdays", "*/15 9-17 * * 1-5", true)).await.unwrap();
+
+    let page = get_user_cron_jobs(&client, "user-1", ListCronJobsFilter::default(), Pagination::default()).await.unwrap();
+
+    assert_eq!(
+        page.jobs[0].schedule_human.as_deref(),
+        Some("Every 15 minutes, 9am–5pm, Monday to Friday")
+    );
+}
+
+#[actix_web::test]
+async fn listing_is_filtered_by_enabled_status_and_text() {
+    let client = client().await;
+    create_cron_job(&client, "user-1", request("Nightly backup", "0 3 * * *", true)).await.unwrap();
+    create_cron_job(&client, "user-1", request("Weekly backup", "0 3 * * 0", false)).await.unwrap();
+    create_cron_job(&client, "user-1", request("Cache_warm", "*/5 * * * *", true)).await.unwrap();
+    let names = |page: CronJobPage| {
+        let mut names: Vec<String> = page.jobs.into_iter().map(|job| job.name).collect();
+        names.sort();
+        names
+    };
+    let list = |filter: ListCronJobsFilter| get_user_cron_jobs(&client, "user-1", filter, Pagination::default());
+
+    let enabled = list(ListCronJobsFilter { enabled_only: true, ..Default::default() }).await.unwrap();
+    assert_eq!(enabled.total, 2);
+    assert_eq!(names(enabled), ["Cache_warm", "Nightly backup"]);
+
+    let backups = list(ListCronJobsFilter { name_contains: Some("backup".to_string()), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(names(backups), ["Nightly backup", "Weekly backup"]);
+
+    let enabled_backups = list(ListCronJobsFilter {
+        enabled_only: true,
+        name_contains: Some("backup".to_string()),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(names(enabled_backups), ["Nightly backup"]);
+
+    // LIKE wildcards and quotes in the search text are matched literally
+    let underscore = list(ListCronJobsFilter { name_contains: Some("_".to_string()), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(names(underscore), ["Cache_warm"]);
+    let quoted = list(ListCronJobsFilter { command_contains: Some("' OR 1=1 --".to_string()), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(quoted.total, 0);
+}
+
+#[actix_web::test]
+async fn a_new_job_has_recent_timestamps() {
+    let client = client().await;
+    let before = Utc::now() - Duration::seconds(5);
+
+    let created = create_cron_job(&client, "user-1", request("Backup", "0 3 * * *", true)).await.unwrap();
+    let stored = get_cron_job(&client, "user-1", &created.id).await.unwrap();
+
+    let after = Utc::now() + Duration::seconds(5);
+    for job in [&created, &stored] {
+        let created_at = job.created_at.expect("created_at should be set");
+        assert!(before <= created_at && created_at <= after, "{}", created_at);
+        assert!(job.updated_at.is_some());
+    }
+    // Second precision survives the trip through JSON
+    let json = serde_json::to_string(&stored).unwrap();
+    let parsed: CronJob = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.created_at, stored.created_at);
+}
+
+#[test]
+fn the_request_builder_fills_in_defaults() {
+    let request = CreateCronJobRequest::builder()
+        .name("  Backup ")
+        .schedule("@daily")
+        .command("echo backup")
+        .build()
+        .unwrap();
+
+    assert_eq!(request.name, "Backup");
+    assert_eq!(request.schedule, "0 0 * * *");
+    assert_eq!(request.enabled, Some(true));
+    assert_eq!(request.timezone.as_deref(), Some("UTC"));
+    assert_eq!(request.job_type, Some(JobType::Shell));
+    assert_eq!((request.max_retries, request.retry_backoff_secs), (Some(0), Some(0)));
+    assert_eq!(request.run_history_limit, None);
+}
+
+#[test]
+fn the_request_builder_rejects_what_create_would() {
+    let builder = || CreateCronJobRequest::builder().name("Backup").command("echo backup");
+
+    assert!(builder().schedule("61 * * * *").build().is_err());
+    assert!(builder().schedule("not a schedule").build().is_err());
+    assert_eq!(builder().build().unwrap_err(), "Schedule is required");
+    assert!(builder().schedule("0 3 * * *").timezone("Mars/Olympus").build().is_err());
+    assert!(CreateCronJobRequest::builder().schedule("0 3 * * *").command("echo").build().is_err());
+}
+
+#[actix_web::test]
+async fn purging_removes_only_tombstones_past_the_cutoff() {
+    let client = client().await;
+    let mut ids = Vec::new();
+    for name in ["Live", "Recently trashed", "Long trashed"] {
+        ids.push(create_cron_job(&client, "user-1", request(name, "0 3 * * *", true)).await.unwrap().id);
+    }
+    delete_cron_job(&client, "user-1", &ids[1]).await.unwrap();
+    delete_cron_job(&client, "user-1", &ids[2]).await.unwrap();
+    client
+        .get_user_database_connection("user-1")
+        .await
+        .unwrap()
+        .execute(
+            "UPDATE cron_jobs SET deleted_at = datetime('now', '-31 days') WHERE id = ?",
+            [ids[2].as_str()],
+        )
+        .await
+        .unwrap();
+
+    let purged = purge_deleted(&client, "user-1", Duration::days(30)).await.unwrap();
+
+    assert_eq!(purged, 1);
+    let trashed: Vec<String> = list_trashed_cron_jobs(&client, "user-1")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|job| job.id)
+        .collect();
+    assert_eq!(trashed, [ids[1].clone()]);
+    assert!(get_cron_job(&client, "user-1", &ids[0]).await.is_ok());
+    assert_eq!(purge_deleted(&client, "user-1", Duration::days(30)).await.unwrap(), 0);
+}
+
+#[actix_web::test]
+async fn an_update_that_changes_nothing_is_rejected() {
+    let client = client().await;
+    let job = create_cron_job(&client, "user-1", request("Backup", "0 3 * * *", true)).await.unwrap();
+
+    let result = update_cron_job(&client, "user-1", &job.id, update(serde_json::json!({}))).await;
+
+    assert!(matches!(&result, Err(ServerError::Validation(msg)) if msg.contains("at least one field")), "{:?}", result);
+}
+
+#[test]
+fn update_fields_are_checked_by_name_and_type() {
+    let parse = |json: &str| serde_json::from_str::<UpdateCronJobRequest>(json).unwrap_err().to_string();
+
+    let unknown = parse(r#"{ "enabled": true, "schedual": "0 3 * * *" }"#);
+    assert!(unknown.contains("unknown field `schedual`"), "{}", unknown);
+
+    let mismatched = parse(r#"{ "enabled": "yes" }"#);
+    assert!(mismatched.contains("invalid type"), "{}", mismatched);
+    assert!(mismatched.contains("expected a boolean"), "{}", mismatched);
+}
@@ -0,0 +1,247 @@
+use cron_jobs::server::error::ServerError;
+use cron_jobs::server::models::{CrontabImportReport, ImportCronJobRow, ImportOutcome, ImportReport};
+use cron_jobs::server::service::{
+    count_cron_jobs, create_user_database, estimate_import_impact, get_cron_job, import_cron_jobs,
+    import_crontab,
+};
+use cron_jobs::server::turso::{TursoClient, TursoConfig};
+
+async fn client_with(config: TursoConfig) -> TursoClient {
+    let client = TursoClient::new_in_memory_with_config(config).await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    client
+}
+
+/// Imported rows are shell jobs, which the server refuses unless the operator allows them
+fn shell_jobs_allowed() -> TursoConfig {
+    TursoConfig {
+        allow_shell_jobs: true,
+        ..TursoConfig::for_testing()
+    }
+}
+
+fn row(key: Option<&str>, name: &str, schedule: &str, command: &str) -> ImportCronJobRow {
+    ImportCronJobRow {
+        idempotency_key: key.map(str::to_string),
+        name: name.to_string(),
+        schedule: schedule.to_string(),
+        command: command.to_string(),
+        enabled: None,
+        timezone: None,
+    }
+}
+
+#[actix_web::test]
+async fn rows_get_the_checks_job_creation_makes() {
+    let client = client_with(shell_jobs_allowed()).await;
+    let rows = vec![
+        row(None, "  Backup  ", "0 3 * * *", " echo backup "),
+        row(None, "   ", "0 3 * * *", "echo unnamed"),
+        row(None, "No command", "0 3 * * *", "  "),
+        row(None, "Bad schedule", "61 * * * *", "echo bad"),
+    ];
+
+    let report = import_cron_jobs(&client, "user-1", rows, false).await.unwrap();
+
+    let outcomes: Vec<ImportOutcome> = report.rows.iter().map(|row| row.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [ImportOutcome::Created, ImportOutcome::Skipped, ImportOutcome::Skipped, ImportOutcome::Skipped]
+    );
+    assert_eq!(report.rows[1].error.as_deref(), Some("Name is required"));
+    assert!(report.rows[2].error.is_some());
+    assert!(report.rows[3].error.is_some());
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 1);
+}
+
+#[actix_web::test]
+async fn rows_are_skipped_unless_shell_jobs_are_allowed() {
+    let client = client_with(TursoConfig::for_testing()).await;
+    let rows = vec![row(None, "Backup", "0 3 * * *", "echo backup")];
+
+    let report = import_cron_jobs(&client, "user-1", rows, false).await.unwrap();
+
+    assert_eq!(report.rows[0].outcome, ImportOutcome::Skipped);
+    let error = report.rows[0].error.as_deref().unwrap_or_default();
+    assert!(error.contains("Shell jobs are disabled"), "{}", error);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 0);
+}
+
+#[actix_web::test]
+async fn import_is_refused_once_the_storage_quota_is_used_up() {
+    let client = client_with(TursoConfig {
+        max_storage_bytes: Some(0),
+        ..shell_jobs_allowed()
+    })
+    .await;
+
+    let result = import_cron_jobs(&client, "user-1", vec![row(None, "Backup", "0 3 * * *", "echo backup")], false).await;
+
+    assert!(matches!(result, Err(ServerError::QuotaExceeded(_))), "{:?}", result);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 0);
+}
+
+#[actix_web::test]
+async fn import_is_refused_when_it_would_pass_the_job_limit() {
+    let client = client_with(TursoConfig {
+        max_jobs_per_user: Some(3),
+        ..shell_jobs_allowed()
+    })
+    .await;
+    let first = vec![row(Some("a"), "A", "0 1 * * *", "echo a"), row(Some("b"), "B", "0 2 * * *", "echo b")];
+    import_cron_jobs(&client, "user-1", first, false).await.unwrap();
+
+    // Two new jobs on top of two existing ones, spread over more than one chunk
+    let mut rows: Vec<ImportCronJobRow> = (0..150)
+        .map(|_| row(Some("a"), "A", "0 1 * * *", "echo a"))
+        .collect();
+    rows.push(row(Some("c"), "C", "0 3 * * *", "echo c"));
+    rows.push(row(None, "D", "0 4 * * *", "echo d"));
+    let result = import_cron_jobs(&client, "user-1", rows, false).await;
+
+    assert!(matches!(result, Err(ServerError::QuotaExceeded(_))), "{:?}", result);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 2);
+
+    // Updates and invalid rows don't count towards the limit
+    let rows = vec![
+        row(Some("a"), "A", "0 5 * * *", "echo a"),
+        row(Some("b"), "B", "0 6 * * *", "echo b"),
+        row(Some("c"), "C", "0 3 * * *", "echo c"),
+        row(Some("c"), "C again", "0 3 * * *", "echo c"),
+        row(None, "Invalid", "not a schedule", "echo x"),
+    ];
+    let report = import_cron_jobs(&client, "user-1", rows, false).await.unwrap();
+    assert_eq!(report.count(ImportOutcome::Created), 1);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 3);
+}
+
+#[actix_web::test]
+async fn crontab_import_is_held_to_the_job_limit() {
+    let client = client_with(TursoConfig {
+        max_jobs_per_user: Some(1),
+        ..shell_jobs_allowed()
+    })
+    .await;
+
+    let result = import_crontab(&client, "user-1", "0 1 * * * echo one\n0 2 * * * echo two\n", false).await;
+
+    assert!(matches!(result, Err(ServerError::QuotaExceeded(_))), "{:?}", result);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 0);
+}
+
+/// Everything a report says about a row except the id of a newly created job
+#[derive(Debug, PartialEq)]
+struct RowOutcome {
+    index: usize,
+    idempotency_key: Option<String>,
+    outcome: ImportOutcome,
+    normalized_schedule: Option<String>,
+    error: Option<String>,
+}
+
+fn outcomes(report: &ImportReport) -> Vec<RowOutcome> {
+    report
+        .rows
+        .iter()
+        .map(|row| RowOutcome {
+            index: row.index,
+            idempotency_key: row.idempotency_key.clone(),
+            outcome: row.outcome,
+            normalized_schedule: row.normalized_schedule.clone(),
+            error: row.error.clone(),
+        })
+        .collect()
+}
+
+#[actix_web::test]
+async fn dry_run_reports_what_a_real_import_does_without_writing() {
+    let client = client_with(shell_jobs_allowed()).await;
+    import_cron_jobs(&client, "user-1", vec![row(Some("existing"), "Existing", "0 1 * * *", "echo 1")], false)
+        .await
+        .unwrap();
+
+    // More rows than fit in one chunk, with a key created early and repeated late
+    let mut rows = vec![row(Some("existing"), "Existing", "@daily", "echo 1")];
+    rows.push(row(Some("new"), "New", "0 2 * * *", "echo 2"));
+    rows.extend((0..120).map(|i| row(None, &format!("Job {}", i), "*/5 * * * *", "echo job")));
+    rows.push(row(Some("new"), "New again", "0 3 * * *", "echo 3"));
+    rows.push(row(None, "Invalid", "not a schedule", "echo x"));
+
+    let before = count_cron_jobs(&client, "user-1").await.unwrap();
+    let dry = import_cron_jobs(&client, "user-1", rows.clone(), true).await.unwrap();
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), before);
+
+    let real = import_cron_jobs(&client, "user-1", rows, false).await.unwrap();
+    assert!(dry.dry_run);
+    assert!(!real.dry_run);
+    assert_eq!(outcomes(&dry), outcomes(&real));
+    assert_eq!(dry.rows[122].outcome, ImportOutcome::Updated);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), before + 121);
+}
+
+#[actix_web::test]
+async fn crontab_dry_run_leaves_the_database_unchanged() {
+    let client = client_with(shell_jobs_allowed()).await;
+    let text = "CRON_TZ=Europe/Berlin\n0 1 * * * echo one\nbad line\n@hourly echo two\n";
+
+    let dry = import_crontab(&client, "user-1", text, true).await.unwrap();
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 0);
+
+    let real = import_crontab(&client, "user-1", text, false).await.unwrap();
+    assert!(dry.dry_run);
+    assert_eq!((dry.imported, dry.skipped), (2, 1));
+    assert_eq!((real.imported, real.skipped), (2, 1));
+    let lines = |report: &CrontabImportReport| {
+        report
+            .lines
+            .iter()
+            .map(|line| (line.line, line.outcome, line.normalized_schedule.clone(), line.error.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(lines(&dry), lines(&real));
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 2);
+}
+
+#[actix_web::test]
+async fn reimporting_a_document_updates_instead_of_duplicating() {
+    let client = client_with(shell_jobs_allowed()).await;
+    let document = |command: &str| {
+        vec![
+            row(Some("backup"), "Backup", "0 3 * * *", command),
+            row(Some("cleanup"), "Cleanup", "0 4 * * *", "echo cleanup"),
+        ]
+    };
+
+    let first = import_cron_jobs(&client, "user-1", document("echo backup"), false).await.unwrap();
+    let second = import_cron_jobs(&client, "user-1", document("echo backup --full"), false).await.unwrap();
+
+    assert_eq!(first.count(ImportOutcome::Created), 2);
+    assert_eq!(second.count(ImportOutcome::Updated), 2);
+    assert_eq!(second.count(ImportOutcome::Created), 0);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 2);
+    let ids = |report: &ImportReport| report.rows.iter().map(|row| row.job_id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&first), ids(&second));
+    let job = get_cron_job(&client, "user-1", first.rows[0].job_id.as_deref().unwrap()).await.unwrap();
+    assert_eq!(job.command, "echo backup --full");
+}
+
+#[actix_web::test]
+async fn the_impact_estimate_counts_rows_and_keeps_the_error_kind() {
+    let client = client_with(shell_jobs_allowed()).await;
+    import_cron_jobs(&client, "user-1", vec![row(Some("existing"), "Existing", "0 1 * * *", "echo 1")], false)
+        .await
+        .unwrap();
+    let rows = vec![
+        row(Some("existing"), "Existing", "0 1 * * *", "echo 1"),
+        row(None, "New", "0 2 * * *", "echo 2"),
+        row(None, "Broken", "61 * * * *", "echo 3"),
+    ];
+
+    let impact = estimate_import_impact(&client, "user-1", &rows).await.unwrap();
+    assert_eq!((impact.new_rows, impact.updated_rows, impact.skipped_rows), (1, 1, 1));
+    assert!(impact.estimated_bytes > 0);
+    assert_eq!(count_cron_jobs(&client, "user-1").await.unwrap(), 1);
+
+    let unknown = estimate_import_impact(&client, "user-2", &rows).await;
+    assert!(matches!(unknown, Err(ServerError::NotFound(_))), "{:?}", unknown);
+}
@@ -0,0 +1,65 @@
+use cron_jobs::server::models::{CreateCronJobRequest, LintLevel};
+use cron_jobs::server::service::{create_cron_job, create_user_database, lint_cron_jobs};
+use cron_jobs::server::turso::TursoClient;
+
+async fn create(client: &TursoClient, name: &str, schedule: &str, enabled: bool) -> String {
+    let request = CreateCronJobRequest::builder()
+        .name(name)
+        .schedule(schedule)
+        .http("GET", "https://example.com/hi")
+        .enabled(enabled)
+        .build()
+        .unwrap();
+    create_cron_job(client, "user-1", request).await.unwrap().id
+}
+
+#[actix_web::test]
+async fn lint_reports_only_the_problem_jobs() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let healthy = create(&client, "Nightly", "0 3 * * *", true).await;
+    let frequent = create(&client, "Poll", "* * * * *", true).await;
+    let never = create(&client, "Draft", "0 0 30 2 *", false).await;
+    let seconds = create(&client, "Seconds", "30 0 3 * * *", true).await;
+    let broken = create(&client, "Broken", "0 4 * * *", true).await;
+    // Written around validation, as an older build might have stored it
+    client
+        .get_user_database_connection("user-1")
+        .await
+        .unwrap()
+        .execute("UPDATE cron_jobs SET schedule = '0 25 * * *' WHERE id = ?", [broken.as_str()])
+        .await
+        .unwrap();
+
+    let lints = lint_cron_jobs(&client, "user-1").await.unwrap();
+
+    let for_job = |id: &str| {
+        lints
+            .iter()
+            .filter(|lint| lint.job_id == id)
+            .map(|lint| (lint.level, lint.message.as_str()))
+            .collect::<Vec<_>>()
+    };
+    assert!(for_job(&healthy).is_empty(), "{:?}", for_job(&healthy));
+    assert!(
+        matches!(for_job(&frequent)[..], [(LintLevel::Warning, msg)] if msg.contains("every minute")),
+        "{:?}",
+        for_job(&frequent)
+    );
+    assert!(
+        matches!(for_job(&never)[..], [(LintLevel::Warning, msg)] if msg.contains("never fires")),
+        "{:?}",
+        for_job(&never)
+    );
+    assert!(
+        matches!(for_job(&seconds)[..], [(LintLevel::Warning, msg)] if msg.contains("seconds field")),
+        "{:?}",
+        for_job(&seconds)
+    );
+    assert!(
+        matches!(for_job(&broken)[..], [(LintLevel::Error, msg)] if msg.starts_with("Invalid schedule")),
+        "{:?}",
+        for_job(&broken)
+    );
+    assert_eq!(lints.len(), 4);
+}
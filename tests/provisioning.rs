@@ -0,0 +1,46 @@
+use cron_jobs::server::service::create_user_database;
+use cron_jobs::server::turso::{ProvisionStatus, TursoClient, TursoConfig};
+
+async fn client_with_prefix(prefix: &str) -> TursoClient {
+    let config = TursoConfig {
+        db_prefix: Some(prefix.to_string()),
+        ..TursoConfig::for_testing()
+    };
+    TursoClient::new_in_memory_with_config(config).await.unwrap()
+}
+
+#[actix_web::test]
+async fn new_databases_use_the_configured_prefix() {
+    let client = client_with_prefix("staging").await;
+
+    let entry = create_user_database(&client, "User_1", "user@example.com", None).await.unwrap();
+
+    assert_eq!(entry.db_name, "staging-user-user-1");
+    assert_eq!(entry.provision_status, ProvisionStatus::Ready);
+}
+
+#[actix_web::test]
+async fn resumed_provisioning_keeps_the_stored_name() {
+    let client = client_with_prefix("staging").await;
+    // Left half-provisioned before TURSO_DB_PREFIX was set
+    client
+        .get_registry_connection()
+        .await
+        .unwrap()
+        .execute(
+            "INSERT INTO user_databases
+             (user_id, email, db_name, db_url, db_token, storage_used_bytes, provision_status, created_at, updated_at)
+             VALUES ('user-1', 'user@example.com', 'user-user-1', '', '', 0, 'pending', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z')",
+            (),
+        )
+        .await
+        .unwrap();
+
+    let entry = create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+
+    assert_eq!(entry.db_name, "user-user-1");
+    assert_eq!(entry.provision_status, ProvisionStatus::Ready);
+    assert_eq!(entry.created_at, "2024-01-01T00:00:00Z");
+    let stored = client.get_user_database_entry("user-1").await.unwrap();
+    assert_eq!(stored.db_name, "user-user-1");
+}
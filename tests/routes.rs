@@ -0,0 +1,82 @@
+use actix_web::{test, web, App};
+use cron_jobs::server::routes::{configure_api, configure_routes};
+use cron_jobs::server::turso::{TursoClient, TursoConfig};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// JWKS endpoint answering one request with an empty key set
+fn mock_jwks() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/auth/v1/.well-known/jwks.json", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 0 && line.trim() != "" {
+            line.clear();
+        }
+        let body = r#"{"keys": []}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    url
+}
+
+async fn client_with_jwks(jwks_url: String) -> TursoClient {
+    let mut config = TursoConfig::for_testing();
+    config.supabase.jwks_url = jwks_url;
+    TursoClient::new_in_memory_with_config(config).await.unwrap()
+}
+
+#[actix_web::test]
+async fn readyz_is_ok_when_the_registry_and_jwks_answer() {
+    let client = client_with_jwks(mock_jwks()).await;
+    let app = test::init_service(App::new().app_data(web::Data::new(client)).configure(configure_routes)).await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/readyz").to_request()).await;
+
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert_eq!(body["status"], "ok");
+}
+
+#[actix_web::test]
+async fn livez_stays_up_while_readyz_reports_a_registry_outage() {
+    let client = client_with_jwks(mock_jwks()).await;
+    // Another connection holds the registry table's write lock, so reads of it fail
+    let blocker = client.get_registry_connection().await.unwrap();
+    blocker.execute("BEGIN IMMEDIATE", ()).await.unwrap();
+    blocker.execute("UPDATE user_databases SET email = email", ()).await.unwrap();
+    let app = test::init_service(App::new().app_data(web::Data::new(client)).configure(configure_routes)).await;
+
+    let live = test::call_service(&app, test::TestRequest::get().uri("/livez").to_request()).await;
+    let ready = test::call_service(&app, test::TestRequest::get().uri("/readyz").to_request()).await;
+
+    assert_eq!(live.status(), 200);
+    assert_eq!(ready.status(), 503);
+    let body: serde_json::Value = test::read_body_json(ready).await;
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["registry"]["status"], "error");
+    assert_eq!(body["jwks"]["status"], "ok");
+}
+
+#[actix_web::test]
+async fn unknown_api_paths_get_a_json_404() {
+    let app = test::init_service(App::new().configure(configure_api)).await;
+
+    for request in [
+        test::TestRequest::get().uri("/api/DoesNotExist"),
+        test::TestRequest::post().uri("/api/DoesNotExist"),
+    ] {
+        let response = test::call_service(&app, request.to_request()).await;
+
+        assert_eq!(response.status(), 404);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body, serde_json::json!({ "error": "unknown_endpoint" }));
+    }
+}
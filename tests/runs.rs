@@ -0,0 +1,213 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use cron_jobs::server::models::{
+    ActivityFilter, ActivityItem, Bucket, CreateCronJobRequest, JobRun, RunBucket, RunStatus,
+};
+use cron_jobs::server::service::{
+    create_cron_job, create_user_database, delete_cron_job, get_activity_feed, get_cron_job, get_job_runs,
+    get_run_summary, purge_cron_job, record_job_run, run_history_limit, run_job_now,
+};
+use cron_jobs::server::turso::TursoClient;
+
+/// Client with one job for `user-1`, scheduled in `timezone`
+async fn client_with_job(timezone: &str) -> (TursoClient, String) {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let request = CreateCronJobRequest::builder()
+        .name("Backup")
+        .schedule("0 3 * * *")
+        .http("GET", "https://example.com/backup")
+        .timezone(timezone)
+        .build()
+        .unwrap();
+    let id = create_cron_job(&client, "user-1", request).await.unwrap().id;
+    (client, id)
+}
+
+fn run(job_id: &str, started_at: DateTime<Utc>, status: RunStatus) -> JobRun {
+    JobRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        job_id: job_id.to_string(),
+        started_at,
+        finished_at: Some(started_at),
+        status,
+        exit_code: None,
+        output_excerpt: None,
+    }
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, day, hour, minute, 0).unwrap()
+}
+
+fn bucket(day: u32, succeeded: u64, failed: u64) -> RunBucket {
+    RunBucket {
+        start: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+        succeeded,
+        failed,
+        skipped: 0,
+    }
+}
+
+#[actix_web::test]
+async fn runs_are_counted_per_day_in_the_jobs_timezone() {
+    let (client, job_id) = client_with_job("Europe/Berlin").await;
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    let runs = [
+        (at(3, 3, 0), RunStatus::Succeeded),
+        (at(3, 12, 0), RunStatus::Failed),
+        (at(4, 3, 0), RunStatus::Succeeded),
+        (at(4, 9, 0), RunStatus::Succeeded),
+        // 00:30 on the 6th in Berlin
+        (at(5, 23, 30), RunStatus::Failed),
+        (at(6, 3, 0), RunStatus::Succeeded),
+    ];
+    for (started_at, status) in runs {
+        record_job_run(&conn, &run(&job_id, started_at, status), 100).await.unwrap();
+    }
+
+    let summary = get_run_summary(&client, "user-1", &job_id, Bucket::Day).await.unwrap();
+
+    assert_eq!(summary, [bucket(3, 1, 1), bucket(4, 2, 0), bucket(6, 1, 1)]);
+}
+
+#[actix_web::test]
+async fn recording_past_the_cap_evicts_the_oldest_runs() {
+    let (client, job_id) = client_with_job("UTC").await;
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+
+    for hour in 1..=5 {
+        record_job_run(&conn, &run(&job_id, at(3, hour, 0), RunStatus::Succeeded), 3).await.unwrap();
+    }
+
+    let runs = get_job_runs(&client, "user-1", &job_id, None).await.unwrap();
+    let started: Vec<DateTime<Utc>> = runs.iter().map(|run| run.started_at).collect();
+    assert_eq!(started, [at(3, 5, 0), at(3, 4, 0), at(3, 3, 0)]);
+}
+
+#[actix_web::test]
+async fn a_jobs_own_history_limit_wins() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let request = CreateCronJobRequest::builder()
+        .name("Backup")
+        .schedule("0 3 * * *")
+        .http("GET", "https://example.com/backup")
+        .run_history_limit(2)
+        .build()
+        .unwrap();
+    let job_id = create_cron_job(&client, "user-1", request).await.unwrap().id;
+    let job = get_cron_job(&client, "user-1", &job_id).await.unwrap();
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+
+    assert_eq!(run_history_limit(&job), 2);
+    for hour in 1..=4 {
+        record_job_run(&conn, &run(&job_id, at(3, hour, 0), RunStatus::Failed), run_history_limit(&job))
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_job_runs(&client, "user-1", &job_id, None).await.unwrap().len(), 2);
+}
+
+#[actix_web::test]
+async fn purging_a_job_removes_its_run_history() {
+    let (client, job_id) = client_with_job("UTC").await;
+    let other = CreateCronJobRequest::builder()
+        .name("Report")
+        .schedule("0 4 * * *")
+        .http("GET", "https://example.com/report")
+        .build()
+        .unwrap();
+    let other_id = create_cron_job(&client, "user-1", other).await.unwrap().id;
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    for id in [&job_id, &job_id, &other_id] {
+        record_job_run(&conn, &run(id, at(3, 3, 0), RunStatus::Succeeded), 100).await.unwrap();
+    }
+
+    delete_cron_job(&client, "user-1", &job_id).await.unwrap();
+    purge_cron_job(&client, "user-1", &job_id).await.unwrap();
+
+    let mut rows = conn
+        .query("SELECT job_id, COUNT(*) FROM job_runs GROUP BY job_id", ())
+        .await
+        .unwrap();
+    let mut remaining = Vec::new();
+    while let Some(row) = rows.next().await.unwrap() {
+        remaining.push((row.get::<String>(0).unwrap(), row.get::<i64>(1).unwrap()));
+    }
+    assert_eq!(remaining, [(other_id, 1)]);
+}
+
+/// Short label of a feed entry: what happened, to which job, at what hour of March 1st
+fn label(item: &ActivityItem) -> String {
+    let (kind, name, at) = match item {
+        ActivityItem::JobCreated { job_name, at, .. } => ("created", job_name, at),
+        ActivityItem::JobUpdated { job_name, at, .. } => ("updated", job_name, at),
+        ActivityItem::JobDeleted { job_name, at, .. } => ("deleted", job_name, at),
+        ActivityItem::Run { job_name, at, .. } => ("run", job_name, at),
+    };
+    format!("{} {} {}", kind, name, at.format("%H:%M"))
+}
+
+#[actix_web::test]
+async fn the_activity_feed_interleaves_job_changes_and_runs_by_time() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    // Inserted directly so the creation times are known
+    conn.execute_batch(
+        "INSERT INTO cron_jobs (id, user_id, name, schedule, command, created_at, updated_at)
+         VALUES ('job-a', 'user-1', 'A', '0 3 * * *', 'echo a', '2025-03-01 08:00:00', '2025-03-01 08:00:00'),
+                ('job-b', 'user-1', 'B', '0 4 * * *', 'echo b', '2025-03-01 10:00:00', '2025-03-01 10:00:00');",
+    )
+    .await
+    .unwrap();
+    for (job_id, hour, status) in [
+        ("job-a", 9, RunStatus::Succeeded),
+        ("job-b", 11, RunStatus::Failed),
+        ("job-a", 12, RunStatus::Succeeded),
+    ] {
+        record_job_run(&conn, &run(job_id, at(1, hour, 0), status), 100).await.unwrap();
+    }
+
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = get_activity_feed(&client, "user-1", &ActivityFilter::default(), cursor, Some(2))
+            .await
+            .unwrap();
+        pages.push(page.items.iter().map(label).collect::<Vec<_>>());
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(
+        pages,
+        [
+            vec!["run A 12:00", "run B 11:00"],
+            vec!["created B 10:00", "run A 09:00"],
+            vec!["created A 08:00"],
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn a_shell_job_is_not_run_unless_shell_jobs_are_allowed() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    // Stored before shell jobs were switched off
+    conn.execute(
+        "INSERT INTO cron_jobs (id, user_id, name, schedule, command, job_type)
+         VALUES ('job-1', 'user-1', 'Backup', '0 3 * * *', 'echo backup', 'shell')",
+        (),
+    )
+    .await
+    .unwrap();
+
+    let refused = run_job_now(&client, "user-1", "job-1", true).await;
+
+    assert!(matches!(&refused, Err(e) if e.contains("Shell jobs are disabled")), "{:?}", refused);
+    assert!(get_job_runs(&client, "user-1", "job-1", None).await.unwrap().is_empty());
+}
@@ -0,0 +1,102 @@
+use cron_jobs::server::service::{
+    check_schedule_fireable, describe_schedule, normalize_schedule, validate_schedule, REBOOT_SENTINEL,
+};
+
+#[test]
+fn standard_and_seconds_forms_are_accepted() {
+    for schedule in [
+        "* * * * *",
+        "*/15 9-17 * * 1-5",
+        "0 0 1,15 * *",
+        "30 2 * JAN-MAR mon",
+        "0 0 * * 7",
+        "  5   4  * * *  ",
+        "0 */5 * * * *",
+        "59 59 23 31 12 6",
+    ] {
+        assert!(validate_schedule(schedule).is_ok(), "{:?}: {:?}", schedule, validate_schedule(schedule));
+    }
+}
+
+#[test]
+fn malformed_expressions_are_rejected() {
+    for schedule in ["", "* * *", "* * * * * * *", "every day", "*/0 * * * *", "5-1 * * * *", "1,,2 * * * *"] {
+        assert!(validate_schedule(schedule).is_err(), "{:?} should be rejected", schedule);
+    }
+}
+
+#[test]
+fn out_of_range_values_name_the_field() {
+    for (schedule, field) in [
+        ("60 * * * *", "minute"),
+        ("* 24 * * *", "hour"),
+        ("* * 0 * *", "day-of-month"),
+        ("* * * 13 *", "month"),
+        ("* * * * 8", "day-of-week"),
+        ("60 * * * * *", "second"),
+    ] {
+        let error = validate_schedule(schedule).unwrap_err();
+        assert!(error.contains(field), "{:?}: {}", schedule, error);
+    }
+}
+
+#[test]
+fn macros_are_accepted_and_unknown_ones_rejected() {
+    for schedule in ["@daily", "@HOURLY", "@weekly", "@monthly", "@yearly", "@annually", "@midnight", "@reboot"] {
+        assert!(validate_schedule(schedule).is_ok(), "{:?}", schedule);
+    }
+    assert_eq!(validate_schedule("@fortnightly").unwrap_err(), "Unknown schedule macro '@fortnightly'");
+}
+
+#[test]
+fn macros_expand_to_their_five_field_form() {
+    assert_eq!(normalize_schedule("@daily").unwrap(), "0 0 * * *");
+    assert_eq!(normalize_schedule(" @Daily ").unwrap(), "0 0 * * *");
+    assert_eq!(normalize_schedule("@midnight").unwrap(), "0 0 * * *");
+    assert_eq!(normalize_schedule("@hourly").unwrap(), "0 * * * *");
+    assert_eq!(normalize_schedule("@weekly").unwrap(), "0 0 * * 0");
+    assert_eq!(normalize_schedule("@monthly").unwrap(), "0 0 1 * *");
+    assert_eq!(normalize_schedule("@yearly").unwrap(), "0 0 1 1 *");
+    assert_eq!(normalize_schedule("@annually").unwrap(), "0 0 1 1 *");
+    assert_eq!(normalize_schedule("  */5   * * * * ").unwrap(), "*/5 * * * *");
+    assert!(normalize_schedule("@sometimes").is_err());
+}
+
+#[test]
+fn reboot_is_kept_as_a_flagged_sentinel() {
+    assert_eq!(normalize_schedule("@reboot").unwrap(), REBOOT_SENTINEL);
+    let warning = check_schedule_fireable(REBOOT_SENTINEL, true).unwrap();
+    assert!(warning.unwrap().contains("@reboot"));
+}
+
+#[test]
+fn schedules_are_described_in_english() {
+    for (schedule, described) in [
+        ("* * * * *", "Every minute"),
+        ("*/15 9-17 * * 1-5", "Every 15 minutes, 9am–5pm, Monday to Friday"),
+        ("0 9 * * MON-FRI", "At 9am, Monday to Friday"),
+        ("5 4 * * sun", "At 4:05am, on Sunday"),
+        ("30 2 1,15 * *", "At 2:30am, on days 1 and 15 of the month"),
+        ("0 0 * JAN,JUL *", "At 12am, in January and July"),
+        ("15,45 * * * *", "At minutes 15 and 45 past the hour"),
+        ("0 8-18/2 * * *", "At minute 0 past the hour, every 2 hours, 8am–6pm"),
+        ("0 0 1 */3 *", "At 12am, on day 1 of the month, every 3 months"),
+        ("0 12 1 * 1", "At 12pm, on day 1 of the month or on Monday"),
+    ] {
+        assert_eq!(describe_schedule(schedule), described, "{:?}", schedule);
+    }
+}
+
+#[test]
+fn macros_and_seconds_are_described_like_their_expansion() {
+    assert_eq!(describe_schedule("@daily"), "At 12am");
+    assert_eq!(describe_schedule("0 0 0 1 1 *"), "At 12am, on day 1 of the month, in January");
+    assert_eq!(describe_schedule("@reboot"), "When the scheduler starts");
+}
+
+#[test]
+fn unparseable_schedules_are_returned_unchanged() {
+    assert_eq!(describe_schedule("not a schedule"), "not a schedule");
+    assert_eq!(describe_schedule("61 * * * *"), "61 * * * *");
+    assert_eq!(describe_schedule("@sometimes"), "@sometimes");
+}
@@ -0,0 +1,97 @@
+use cron_jobs::server::models::{CreateCronJobRequest, JobType, ListCronJobsFilter, Pagination};
+use cron_jobs::server::service::{create_cron_job, create_user_database, get_cron_job, get_user_cron_jobs};
+use cron_jobs::server::turso::schema::{apply_schema_migrations, get_current_tables, get_expected_schema};
+use cron_jobs::server::turso::{MigrationPolicy, TursoClient};
+
+async fn client_with_job() -> (TursoClient, String) {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let request = CreateCronJobRequest::builder()
+        .name("Backup")
+        .schedule("0 3 * * *")
+        .http("GET", "https://example.com/backup")
+        .build()
+        .unwrap();
+    let id = create_cron_job(&client, "user-1", request).await.unwrap().id;
+    (client, id)
+}
+
+#[actix_web::test]
+async fn the_timestamp_trigger_stops_even_with_recursive_triggers_on() {
+    let (client, id) = client_with_job().await;
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    conn.execute("PRAGMA recursive_triggers = ON", ()).await.unwrap();
+
+    // Unguarded, the trigger's own UPDATE would fire it again until SQLite gives up
+    conn.execute(
+        "UPDATE cron_jobs SET name = 'Nightly backup', updated_at = '2000-01-01 00:00:00' WHERE id = ?",
+        [id.as_str()],
+    )
+    .await
+    .unwrap();
+
+    let mut rows = conn
+        .query("SELECT name, updated_at FROM cron_jobs WHERE id = ?", [id.as_str()])
+        .await
+        .unwrap();
+    let row = rows.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<String>(0).unwrap(), "Nightly backup");
+    assert_ne!(row.get::<String>(1).unwrap(), "2000-01-01 00:00:00", "the trigger should have run");
+}
+
+#[actix_web::test]
+async fn jobs_read_from_an_unmigrated_table_get_defaults_for_newer_columns() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    // The table as it was before any optional column was added
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    conn.execute_batch(
+        "DROP TABLE cron_jobs;
+         CREATE TABLE cron_jobs (
+             id TEXT PRIMARY KEY NOT NULL,
+             user_id TEXT NOT NULL,
+             name TEXT NOT NULL,
+             schedule TEXT NOT NULL,
+             command TEXT NOT NULL,
+             enabled BOOLEAN NOT NULL DEFAULT 1,
+             created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+             updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+         );
+         INSERT INTO cron_jobs (id, user_id, name, schedule, command, created_at, updated_at)
+         VALUES ('job-1', 'user-1', 'Legacy', '0 3 * * *', 'echo legacy', '2024-01-01 00:00:00', '2024-01-01 00:00:00');",
+    )
+    .await
+    .unwrap();
+    client.invalidate_table_columns("user-1");
+
+    let page = get_user_cron_jobs(&client, "user-1", ListCronJobsFilter::default(), Pagination::default())
+        .await
+        .unwrap();
+    let job = get_cron_job(&client, "user-1", "job-1").await.unwrap();
+
+    assert_eq!(page.total, 1);
+    assert_eq!(page.jobs[0].id, "job-1");
+    assert_eq!(job.name, "Legacy");
+    assert!(job.enabled);
+    assert_eq!(job.timezone, "UTC");
+    assert_eq!(job.job_type, JobType::Shell);
+    assert_eq!((job.max_retries, job.retry_backoff_secs), (0, 0));
+    assert_eq!((job.http_method, job.http_body, job.run_history_limit), (None, None, None));
+    assert_eq!(job.deleted_at, None);
+}
+
+#[actix_web::test]
+async fn destructive_migrations_keep_unexpected_tables_unless_drops_are_allowed() {
+    // The kill switch is off unless SCHEMA_ALLOW_DROPS is set, as in production
+    std::env::remove_var("SCHEMA_ALLOW_DROPS");
+    let (client, _) = client_with_job().await;
+    let conn = client.get_user_database_connection("user-1").await.unwrap();
+    conn.execute("CREATE TABLE legacy_notes (id TEXT PRIMARY KEY, body TEXT)", ()).await.unwrap();
+    conn.execute("INSERT INTO legacy_notes VALUES ('n-1', 'keep me')", ()).await.unwrap();
+
+    apply_schema_migrations(&conn, &get_expected_schema(), MigrationPolicy::Destructive).await.unwrap();
+
+    assert!(get_current_tables(&conn).await.unwrap().contains(&"legacy_notes".to_string()));
+    let mut rows = conn.query("SELECT body FROM legacy_notes", ()).await.unwrap();
+    assert_eq!(rows.next().await.unwrap().unwrap().get::<String>(0).unwrap(), "keep me");
+}
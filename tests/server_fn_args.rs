@@ -0,0 +1,94 @@
+//! Read-only server functions are sent as GET requests (`input = GetUrl`), so their
+//! arguments travel in the query string. These check that every argument type survives
+//! that encoding the way the server decodes it.
+
+use chrono::{TimeZone, Utc};
+use cron_jobs::server::actions::{AdminListUserDatabases, GetActivityFeed, GetCronJobs, GetCronJobsByIds, GetJobRuns, GetRunSummary};
+use cron_jobs::server::models::{ActivityCursor, ActivityFilter, ActivitySource, Bucket, ListCronJobsFilter, Pagination};
+use cron_jobs::server::turso::{ProvisionStatus, UserDatabaseFilter};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Encode like the browser client, decode like the server, and encode again
+fn round_trip<T: Serialize + DeserializeOwned>(args: &T) -> (String, String) {
+    let query = serde_qs::to_string(args).unwrap();
+    let decoded: T = serde_qs::Config::new(5, false).deserialize_str(&query).unwrap();
+    (query, serde_qs::to_string(&decoded).unwrap())
+}
+
+fn assert_round_trips<T: Serialize + DeserializeOwned>(args: &T) {
+    let (query, again) = round_trip(args);
+    assert_eq!(query, again);
+}
+
+#[test]
+fn job_listing_arguments() {
+    assert_round_trips(&GetCronJobs {
+        filter: ListCronJobsFilter {
+            enabled_only: true,
+            name_contains: Some("back up & restore".to_string()),
+            command_contains: None,
+        },
+        pagination: Pagination {
+            limit: Some(20),
+            offset: Some(40),
+        },
+    });
+    assert_round_trips(&GetCronJobs {
+        filter: ListCronJobsFilter::default(),
+        pagination: Pagination::default(),
+    });
+    assert_round_trips(&GetCronJobsByIds {
+        job_ids: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+    });
+    assert_round_trips(&GetCronJobsByIds { job_ids: Vec::new() });
+}
+
+#[test]
+fn run_history_arguments() {
+    assert_round_trips(&GetJobRuns {
+        job_id: "job-1".to_string(),
+        limit: Some(5),
+    });
+    assert_round_trips(&GetRunSummary {
+        job_id: "job-1".to_string(),
+        bucket: Bucket::Week,
+    });
+    assert_round_trips(&GetActivityFeed {
+        filter: ActivityFilter {
+            job_id: Some("job-1".to_string()),
+            source: Some(ActivitySource::Runs),
+        },
+        cursor: Some(ActivityCursor {
+            at: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+            key: "run:42".to_string(),
+        }),
+        limit: None,
+    });
+    assert_round_trips(&GetActivityFeed {
+        filter: ActivityFilter::default(),
+        cursor: None,
+        limit: None,
+    });
+}
+
+#[test]
+fn admin_listing_arguments() {
+    assert_round_trips(&AdminListUserDatabases {
+        filter: UserDatabaseFilter {
+            provision_status: Some(ProvisionStatus::Pending),
+            created_after: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            created_before: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+            limit: None,
+            offset: None,
+        },
+        pagination: Pagination {
+            limit: Some(10),
+            offset: None,
+        },
+    });
+    assert_round_trips(&AdminListUserDatabases {
+        filter: UserDatabaseFilter::default(),
+        pagination: Pagination::default(),
+    });
+}
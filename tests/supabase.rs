@@ -0,0 +1,83 @@
+use cron_jobs::client::supabase::{
+    parse_retry_after, resolve_expires_at, Session, SignInResponse, SupabaseError, User,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+#[test]
+fn an_expires_in_only_response_gets_an_absolute_expiry() {
+    let body = r#"{
+        "access_token": "aaa.bbb.ccc",
+        "refresh_token": "refresh",
+        "expires_in": 3600,
+        "token_type": "bearer",
+        "user": { "id": "user-1", "email": "user@example.com" }
+    }"#;
+
+    let response: SignInResponse = serde_json::from_str(body).unwrap();
+
+    assert_eq!(response.expires_at, None);
+    assert_eq!(resolve_expires_at(response.expires_at, response.expires_in, NOW), Some(NOW + 3600));
+}
+
+#[test]
+fn an_absolute_expiry_wins_over_expires_in() {
+    assert_eq!(resolve_expires_at(Some(NOW + 60), Some(3600), NOW), Some(NOW + 60));
+    assert_eq!(resolve_expires_at(None, None, NOW), None);
+}
+
+/// Stand-in for the browser's date parser, knowing a single HTTP date
+fn parse_date(value: &str) -> Option<i64> {
+    (value == "Tue, 14 Nov 2023 22:14:20 GMT").then_some(NOW + 90)
+}
+
+#[test]
+fn retry_after_is_read_as_seconds_or_an_http_date() {
+    assert_eq!(parse_retry_after("30", NOW, parse_date), Some(30));
+    assert_eq!(parse_retry_after(" 30 ", NOW, parse_date), Some(30));
+    assert_eq!(parse_retry_after("Tue, 14 Nov 2023 22:14:20 GMT", NOW, parse_date), Some(90));
+    // A date already in the past means retry now
+    assert_eq!(parse_retry_after("Tue, 14 Nov 2023 22:14:20 GMT", NOW + 120, parse_date), Some(0));
+    assert_eq!(parse_retry_after("soon", NOW, parse_date), None);
+}
+
+#[test]
+fn too_many_attempts_tells_the_user_how_long_to_wait() {
+    let error = SupabaseError::TooManyAttempts(parse_retry_after("30", NOW, parse_date).unwrap());
+
+    assert_eq!(error.to_string(), "Too many attempts, try again in 30 seconds");
+}
+
+fn session(expires_at: Option<i64>) -> Session {
+    Session {
+        access_token: "aaa.bbb.ccc".to_string(),
+        refresh_token: "refresh".to_string(),
+        expires_at,
+        user: User {
+            id: "user-1".to_string(),
+            email: "user@example.com".to_string(),
+            user_metadata: serde_json::Value::Null,
+        },
+    }
+}
+
+#[test]
+fn a_session_counts_as_expired_from_the_buffer_onwards() {
+    let session = session(Some(NOW + 60));
+
+    assert_eq!(session.seconds_until_expiry(NOW), Some(60));
+    assert!(!session.is_expired(NOW - 1, 60));
+    assert!(session.is_expired(NOW, 60));
+    assert!(session.is_expired(NOW + 61, 60));
+    // Without a buffer only the actual expiry counts
+    assert!(!session.is_expired(NOW + 59, 0));
+    assert!(session.is_expired(NOW + 60, 0));
+}
+
+#[test]
+fn a_session_without_an_expiry_never_expires() {
+    let session = session(None);
+
+    assert_eq!(session.seconds_until_expiry(NOW), None);
+    assert!(!session.is_expired(i64::MAX, 60));
+}
@@ -0,0 +1,34 @@
+use cron_jobs::server::models::CreateCronJobRequest;
+use cron_jobs::server::service::{create_cron_job, create_user_database, normalize_timezone};
+use cron_jobs::server::turso::TursoClient;
+
+#[test]
+fn deprecated_aliases_resolve_to_their_canonical_zone() {
+    assert_eq!(normalize_timezone("US/Eastern").unwrap(), "America/New_York");
+    assert_eq!(normalize_timezone(" us/pacific ").unwrap(), "America/Los_Angeles");
+    assert_eq!(normalize_timezone("europe/berlin").unwrap(), "Europe/Berlin");
+    assert_eq!(normalize_timezone("UTC").unwrap(), "UTC");
+}
+
+#[test]
+fn unknown_zones_are_rejected() {
+    assert_eq!(normalize_timezone("Mars/Olympus_Mons").unwrap_err(), "Unknown timezone: Mars/Olympus_Mons");
+    assert!(normalize_timezone("   ").is_err());
+}
+
+#[actix_web::test]
+async fn jobs_store_the_canonical_name() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "user-1", "user@example.com", None).await.unwrap();
+    let request = CreateCronJobRequest::builder()
+        .name("Report")
+        .schedule("0 9 * * *")
+        .http("GET", "https://example.com/report")
+        .timezone("US/Eastern")
+        .build()
+        .unwrap();
+
+    let job = create_cron_job(&client, "user-1", request).await.unwrap();
+
+    assert_eq!(job.timezone, "America/New_York");
+}
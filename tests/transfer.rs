@@ -0,0 +1,48 @@
+use cron_jobs::server::models::{CreateCronJobRequest, ListCronJobsFilter, Pagination};
+use cron_jobs::server::service::{create_cron_job, create_user_database, get_user_cron_jobs, transfer_jobs};
+use cron_jobs::server::turso::TursoClient;
+
+#[actix_web::test]
+async fn a_database_error_stops_the_transfer_and_keeps_the_report() {
+    let client = TursoClient::new_in_memory().await.unwrap();
+    create_user_database(&client, "alice", "alice@example.com", None).await.unwrap();
+    create_user_database(&client, "bob", "bob@example.com", None).await.unwrap();
+    let mut ids = Vec::new();
+    for name in ["Backup", "Report", "Cleanup"] {
+        let request = CreateCronJobRequest::builder()
+            .name(name)
+            .schedule("0 3 * * *")
+            .http("GET", "https://example.com/hi")
+            .build()
+            .unwrap();
+        ids.push(create_cron_job(&client, "alice", request).await.unwrap().id);
+    }
+    // Bob's database fails on the second copy
+    client
+        .get_user_database_connection("bob")
+        .await
+        .unwrap()
+        .execute(
+            "CREATE TRIGGER fail_report BEFORE INSERT ON cron_jobs WHEN NEW.name = 'Report'
+             BEGIN SELECT RAISE(ABORT, 'disk I/O error'); END",
+            (),
+        )
+        .await
+        .unwrap();
+
+    let report = transfer_jobs(&client, "alice", "bob", ids.clone(), true).await.unwrap();
+
+    assert!(report.error.as_deref().unwrap().contains("disk I/O error"), "{:?}", report.error);
+    assert_eq!((report.transferred, report.failed, report.originals_trashed), (1, 1, 1));
+    let attempted: Vec<&str> = report.jobs.iter().map(|job| job.source_job_id.as_str()).collect();
+    assert_eq!(attempted, [ids[0].as_str(), ids[1].as_str()]);
+
+    let jobs = |user| get_user_cron_jobs(&client, user, ListCronJobsFilter::default(), Pagination::default());
+    let bob: Vec<String> = jobs("bob").await.unwrap().jobs.into_iter().map(|job| job.name).collect();
+    assert_eq!(bob, ["Backup"]);
+    let mut alice: Vec<String> = jobs("alice").await.unwrap().jobs.into_iter().map(|job| job.id).collect();
+    alice.sort();
+    let mut left = vec![ids[1].clone(), ids[2].clone()];
+    left.sort();
+    assert_eq!(alice, left);
+}